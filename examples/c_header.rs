@@ -0,0 +1,7 @@
+//! Generating the C header declaring the C API
+//!
+//! Run with `cargo run --example c_header > include/oxforth.h`
+
+fn main() {
+    print!("{}", oxforth::ffi::header());
+}
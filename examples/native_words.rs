@@ -0,0 +1,39 @@
+//! Extending OxForth with words written in Rust
+//!
+//! Run with `cargo run --example native_words`
+
+#![allow(clippy::needless_return)]
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+use oxforth::vm::interpreter::evaluate;
+use oxforth::vm::io::StdoutSink;
+use oxforth::vm::{VirtualMachineError, VM};
+
+fn main() -> Result<(), VirtualMachineError> {
+    let mut vm: VM = VM::default();
+    vm.define_core_words();
+    vm.output = Box::new(StdoutSink);
+
+    // ( a b -- c ) length of a right triangle's hypotenuse
+    vm.define_native("HYPOT", 2, 1, |vm: &mut VM| {
+        let b: i64 = vm.pop()?;
+        let a: i64 = vm.pop()?;
+        vm.push(((a * a + b * b) as f64).sqrt() as i64);
+        return Result::Ok(());
+    });
+
+    // ( n -- ) a running total kept by the host
+    let total: Rc<Cell<i64>> = Rc::new(Cell::new(0));
+    let sum: Rc<Cell<i64>> = total.clone();
+    vm.define_native("TALLY", 1, 0, move |vm: &mut VM| {
+        sum.set(sum.get() + vm.pop()?);
+        return Result::Ok(());
+    });
+
+    evaluate(&mut vm, ": SIDES 3 4 HYPOT DUP . TALLY 5 12 HYPOT DUP . TALLY ;\n")?;
+    evaluate(&mut vm, "SIDES CR\n")?;
+    println!("total: {}", total.get());
+    return Result::Ok(());
+}
@@ -0,0 +1,78 @@
+/*  OxForth - C API
+ *
+ *  Declarations of the functions exported by the OxForth C dynamic library
+ *  (liboxforth), as defined in src/ffi.rs, from which this file is generated:
+ *
+ *      cargo run --example c_header > include/oxforth.h
+ *
+ *  A Forth system is created by oxforth_create() and passed to every other
+ *  call until it's destroyed by oxforth_destroy(). Functions that can fail
+ *  return OXFORTH_ERROR, and describe what went wrong in a message fetched by
+ *  oxforth_last_error().
+ */
+
+#ifndef OXFORTH_H
+#define OXFORTH_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* The call succeeded */
+#define OXFORTH_OK 0
+
+/* The program asked to stop (e.g. via BYE) */
+#define OXFORTH_HALTED 1
+
+/* The call failed, as described by oxforth_last_error() */
+#define OXFORTH_ERROR (-1)
+
+/* A Forth system */
+typedef struct oxforth oxforth;
+
+/* Function run when a native word is executed. It's given the Forth system
+ * executing it, and the context it was registered with, and returns
+ * OXFORTH_OK unless it failed. While it runs, the system may only be used
+ * through the pointer it's given, not one kept from elsewhere (e.g. in the
+ * context). */
+typedef int (*oxforth_native)(oxforth *forth, void *context);
+
+/* Create a Forth system, with the core words defined */
+oxforth *oxforth_create(void);
+
+/* Destroy a Forth system. Does nothing if forth is NULL. */
+void oxforth_destroy(oxforth *forth);
+
+/* Interpret length bytes of Forth source. Returns OXFORTH_HALTED if the
+ * program halted, storing its exit code (0 if none was given) in code unless
+ * it's NULL. */
+int oxforth_eval(oxforth *forth, const char *source, size_t length, int *code);
+
+/* Push a cell onto the data stack */
+void oxforth_push(oxforth *forth, int64_t n);
+
+/* Pop a cell off the data stack into n */
+int oxforth_pop(oxforth *forth, int64_t *n);
+
+/* Number of cells on the data stack */
+size_t oxforth_depth(const oxforth *forth);
+
+/* Define a word named name which calls callback with context. It takes inputs
+ * cells off the data stack and leaves outputs cells in their place, which is
+ * checked each time it's executed. context must remain valid for as long as
+ * the word may be executed. */
+int oxforth_define_native(oxforth *forth, const char *name, size_t inputs, size_t outputs,
+                          oxforth_native callback, void *context);
+
+/* Why the last call on this thread that failed did so, or NULL if none has.
+ * The message lasts until the next call on this thread that fails. */
+const char *oxforth_last_error(void);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* OXFORTH_H */
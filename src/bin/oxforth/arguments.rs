@@ -16,15 +16,42 @@ pub enum Behavior {
     /// Read, Execute, Print Loop
     REPL,
 
+    /// Interpret source files, in order
+    RUN,
+
+    /// Interpret expressions given on the command line, in order, after any
+    /// source files
+    EVAL,
+
     /// Display standard CLI help
     HELP,
 
 }
 
 /// Options that affect the behavior of the CLI.
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Default, PartialEq)]
 pub struct Options {
     pub verbose: bool,
+
+    /// Whether the REPL is entered after the source files are interpreted
+    pub interactive: bool,
+
+    /// Source files to interpret, in order. The last is the program.
+    pub files: Vec<String>,
+
+    /// Expressions to interpret after the source files, in order
+    pub expressions: Vec<String>,
+
+    /// Whether the program is denied access to files, other than those in the
+    /// readable directories
+    pub sandbox: bool,
+
+    /// Directories the program may read files within, despite the sandbox
+    pub readable: Vec<String>,
+
+    /// Command-line arguments made available to the program (e.g. via ARG),
+    /// beginning with the program itself
+    pub arguments: Vec<String>,
 }
 
 /// Helpful information about the CLI printed out via --help
@@ -34,9 +61,31 @@ it under the terms of the GNU General Public License as published by
 the Free Software Foundation, version 3 of the License. This program
 comes with ABSOLUTELY NO WARRANTY.
 
+Usage:
+    oxforth [options]                   Start the REPL.
+    oxforth [options] FILE [ARGS...]    Interpret FILE, then exit.
+    oxforth [options] -e TEXT [ARGS...] Interpret TEXT, then exit.
+
 Arguments and options:
     --help, -h          Print the text you're currently reading.
     --verbose, -v       REPL will print more details when executing.
+    --interactive, -i   Start the REPL after interpreting source files.
+    --load, -l FILE     Interpret FILE before the program. May be repeated.
+    --eval, -e TEXT     Interpret TEXT after any files. May be repeated.
+    --sandbox           Deny the program access to files (e.g. via INCLUDE or
+                        OPEN-FILE). Files given on the command line are still
+                        interpreted.
+    --allow-read DIR    Allow the program to read files within DIR, but deny it
+                        access to other files. May be repeated.
+
+Arguments following FILE are left for the program, which may read them via
+ARGC, ARG and NEXT-ARG. The program is the zeroth argument. When TEXT is
+given, there's no program file; the zeroth argument is -e, and every
+argument following the options is left for TEXT.
+
+When standard input isn't a terminal, the REPL reads it as a script instead:
+there are no prompts, and it exits at the end of input, or with a nonzero
+status at the first error.
 ";
 
 /// Standard print out of helpful information about the CLI
@@ -56,44 +105,123 @@ where
 
     // Default behavior: a low-verbosity REPL
     if arguments.len() == 0 {
-        return Result::Ok(
-            (
-                Behavior::REPL,
-                Options {
-                    verbose: false,
-                }
-            ),
-        );
+        return Result::Ok((Behavior::REPL, Options::default()));
     }
 
-    let behavior: Behavior = Behavior::REPL;
-    let mut options: Options = Options {
-        verbose: false,
-    };
+    let mut options: Options = Options::default();
 
     // Dump the iterator, since multiple passes over args may be needed.
     let arguments: Vec<String> = arguments.collect();
 
-    // Any placement of --help is sufficient to get help
-    if arguments.iter().any( |arg| { arg == "--help" || arg == "-h" }) {
-        return Result::Ok((Behavior::HELP, options));
+    // Any placement of --help among the options is sufficient to get help, but
+    // arguments following them are left for the program, even if they're -h
+    let mut scanned = arguments.iter();
+    while let Some(arg) = scanned.next() {
+        match arg.as_str() {
+            "--help" | "-h" => return Result::Ok((Behavior::HELP, options)),
+            "--load" | "-l" | "--eval" | "-e" | "--allow-read" => {
+                scanned.next();
+            },
+            _ if arg.starts_with('-') => {},
+            _ => break,
+        }
     }
 
-    for arg in arguments.iter() {
+    let mut arguments = arguments.into_iter();
+    while let Some(arg) = arguments.next() {
 
-        if arg == "--verbose" {
+        if arg == "--verbose" || arg == "-v" {
             options.verbose = true;
             continue
         }
 
-        return Result::Err(
-            ArgParseError {
-                msg: String::from(format!("unknown argument: {}", arg)),
+        if arg == "--interactive" || arg == "-i" {
+            options.interactive = true;
+            continue
+        }
+
+        if arg == "--load" || arg == "-l" {
+            match arguments.next() {
+                Some(file) => options.files.push(file),
+                None => {
+                    return Result::Err(
+                        ArgParseError {
+                            msg: format!("{} requires a file", arg),
+                        }
+                    )
+                },
             }
-        )
+            continue
+        }
+
+        if arg == "--sandbox" {
+            options.sandbox = true;
+            continue
+        }
+
+        if arg == "--allow-read" {
+            match arguments.next() {
+                Some(directory) => {
+                    options.sandbox = true;
+                    options.readable.push(directory);
+                },
+                None => {
+                    return Result::Err(
+                        ArgParseError {
+                            msg: format!("{} requires a directory", arg),
+                        }
+                    )
+                },
+            }
+            continue
+        }
+
+        if arg == "--eval" || arg == "-e" {
+            match arguments.next() {
+                Some(expression) => options.expressions.push(expression),
+                None => {
+                    return Result::Err(
+                        ArgParseError {
+                            msg: format!("{} requires an expression", arg),
+                        }
+                    )
+                },
+            }
+            continue
+        }
+
+        if arg.starts_with('-') {
+            return Result::Err(
+                ArgParseError {
+                    msg: format!("unknown argument: {}", arg),
+                }
+            )
+        }
+
+        // Expressions take the place of the program
+        if !options.expressions.is_empty() {
+            options.arguments.push(arg);
+            options.arguments.extend(arguments);
+            break;
+        }
+
+        // The program, and everything after it is left for the program
+        options.files.push(arg.clone());
+        options.arguments.push(arg);
+        options.arguments.extend(arguments);
+        break;
 
     }
 
+    if !options.expressions.is_empty() {
+        options.arguments.insert(0, String::from("-e"));
+        return Result::Ok((Behavior::EVAL, options));
+    }
+
+    let behavior: Behavior = match options.files.is_empty() {
+        true => Behavior::REPL,
+        false => Behavior::RUN,
+    };
     return Result::Ok((behavior, options));
 
 }
@@ -152,39 +280,138 @@ mod tests {
 
         test_case_args_and_result!(
             ["oxforth", "--invalid-argument", "--help"],
-            Ok((Behavior::HELP, Options {
-                verbose: false,
-            }))
+            Ok((Behavior::HELP, Options::default()))
         );
 
         test_case_args_and_result!(
             ["oxforth", "--help", "--invalid-argument"],
-            Ok((Behavior::HELP, Options {
-                verbose: false,
-            }))
+            Ok((Behavior::HELP, Options::default()))
         );
 
         test_case_args_and_result!(
             ["oxforth", "--invalid-argument", "-h"],
-            Ok((Behavior::HELP, Options {
-                verbose: false,
-            }))
+            Ok((Behavior::HELP, Options::default()))
         );
 
         test_case_args_and_result!(
             ["oxforth"],
+            Ok((Behavior::REPL, Options::default()))
+        );
+
+        test_case_args_and_result!(
+            ["oxforth", "--verbose"],
             Ok((Behavior::REPL, Options {
-                verbose: false,
+                verbose: true,
+                ..Options::default()
             }))
         );
 
         test_case_args_and_result!(
-            ["oxforth", "--verbose"],
+            ["oxforth", "-v", "-i"],
             Ok((Behavior::REPL, Options {
                 verbose: true,
+                interactive: true,
+                ..Options::default()
+            }))
+        );
+
+        // everything after the program is left for the program
+        test_case_args_and_result!(
+            ["oxforth", "-l", "lib.fs", "--load", "more.fs", "main.fs", "-v", "arg"],
+            Ok((Behavior::RUN, Options {
+                files: vec![String::from("lib.fs"), String::from("more.fs"), String::from("main.fs")],
+                arguments: vec![String::from("main.fs"), String::from("-v"), String::from("arg")],
+                ..Options::default()
+            }))
+        );
+
+        test_case_args_and_result!(
+            ["oxforth", "main.fs", "-h", "--help"],
+            Ok((Behavior::RUN, Options {
+                files: vec![String::from("main.fs")],
+                arguments: vec![String::from("main.fs"), String::from("-h"), String::from("--help")],
+                ..Options::default()
+            }))
+        );
+
+        test_case_args_and_result!(
+            ["oxforth", "--interactive", "--load", "lib.fs"],
+            Ok((Behavior::RUN, Options {
+                interactive: true,
+                files: vec![String::from("lib.fs")],
+                ..Options::default()
+            }))
+        );
+
+        test_case_args_and_result!(
+            ["oxforth", "--load"],
+            Err(ArgParseError { msg: String::from("--load requires a file") })
+        );
+
+        // arguments following the expressions are left for them
+        test_case_args_and_result!(
+            ["oxforth", "-l", "lib.fs", "-e", "1 .", "--eval", "ARGC .", "arg", "-v"],
+            Ok((Behavior::EVAL, Options {
+                files: vec![String::from("lib.fs")],
+                expressions: vec![String::from("1 ."), String::from("ARGC .")],
+                arguments: vec![String::from("-e"), String::from("arg"), String::from("-v")],
+                ..Options::default()
+            }))
+        );
+
+        test_case_args_and_result!(
+            ["oxforth", "-e", "ARGC .", "arg", "-h"],
+            Ok((Behavior::EVAL, Options {
+                expressions: vec![String::from("ARGC .")],
+                arguments: vec![String::from("-e"), String::from("arg"), String::from("-h")],
+                ..Options::default()
+            }))
+        );
+
+        test_case_args_and_result!(
+            ["oxforth", "-l", "lib.fs", "-h", "main.fs"],
+            Ok((Behavior::HELP, Options::default()))
+        );
+
+        test_case_args_and_result!(
+            ["oxforth", "-e", "2 3 + ."],
+            Ok((Behavior::EVAL, Options {
+                expressions: vec![String::from("2 3 + .")],
+                arguments: vec![String::from("-e")],
+                ..Options::default()
+            }))
+        );
+
+        // directories may only be read from within the sandbox
+        test_case_args_and_result!(
+            ["oxforth", "--sandbox", "main.fs"],
+            Ok((Behavior::RUN, Options {
+                files: vec![String::from("main.fs")],
+                arguments: vec![String::from("main.fs")],
+                sandbox: true,
+                ..Options::default()
             }))
         );
 
+        test_case_args_and_result!(
+            ["oxforth", "--allow-read", "lib", "--allow-read", "data"],
+            Ok((Behavior::REPL, Options {
+                sandbox: true,
+                readable: vec![String::from("lib"), String::from("data")],
+                ..Options::default()
+            }))
+        );
+
+        test_case_args_and_result!(
+            ["oxforth", "--allow-read"],
+            Err(ArgParseError { msg: String::from("--allow-read requires a directory") })
+        );
+
+        test_case_args_and_result!(
+            ["oxforth", "-i", "--eval"],
+            Err(ArgParseError { msg: String::from("--eval requires an expression") })
+        );
+
     }
 
 }
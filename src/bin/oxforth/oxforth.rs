@@ -15,8 +15,17 @@
 
 //! OxForth - Command-line interface / binary entry point
 
+// Returns are always explicit in this codebase
+#![allow(clippy::needless_return)]
+
+use std::io::{stdout, Write};
+use std::path::Path;
 use std::process::exit;
 
+use oxforth::vm::files::{Access, Policy};
+use oxforth::vm::io::StdoutSink;
+use oxforth::vm::{Outcome, VM};
+
 use arguments::{Behavior, Options};
 
 /// Where can a user find this source code?
@@ -27,11 +36,86 @@ const SOURCE_VERSION: &str = "0.2";
 
 pub mod arguments;
 pub mod repl;
+pub mod run;
+pub mod terminal;
 
-/// Entry point
-fn main() {
+/// Standard print out of what's being run
+fn banner() {
     println!("OxForth {} - {}", SOURCE_VERSION, SOURCE_LOCATION);
+}
 
+/// A VM reading from and writing to the terminal, with the program's arguments
+/// and access to files
+fn vm(options: &Options) -> VM {
+    let mut vm: VM = VM::default();
+    vm.define_core_words();
+    vm.input = terminal::input_source();
+    vm.output = Box::new(StdoutSink);
+    vm.arguments = options.arguments.clone();
+    if options.sandbox {
+        vm.files.policy = Policy::sandboxed();
+    }
+    for directory in options.readable.iter() {
+        if let Err(err) = vm.files.policy.allow(Path::new(directory), Access::READ) {
+            eprintln!("error: {}: {}", directory, err);
+            exit(1);
+        }
+    }
+    return vm;
+}
+
+/// Act on the outcome of interpreting a source file or expression. Halting
+/// exits, as do errors unless the REPL is to be started afterward. Returns
+/// whether interpretation should carry on.
+fn proceed(options: &Options, vm: &mut VM, result: Result<Outcome, String>) -> bool {
+    stdout().flush().unwrap();
+    match result {
+        Result::Ok(Outcome::COMPLETED) => {
+            return true;
+        },
+        Result::Ok(Outcome::HALTED(code)) => {
+            exit(code.unwrap_or(0));
+        },
+
+        // Execution is never given a time slice here
+        Result::Ok(Outcome::YIELDED) => unreachable!(),
+
+        Result::Err(msg) => {
+            eprintln!("error: {}", msg);
+            if !options.interactive {
+                exit(1);
+            }
+            vm.reset();
+            return false;
+        },
+    }
+}
+
+/// Interact with the user at the REPL, or interpret standard input as a
+/// script when it isn't a terminal (e.g. it's piped in)
+fn interact(options: &Options, mut vm: VM) {
+    if terminal::stdin_is_terminal() {
+        banner();
+        repl::repl(options, vm);
+        return;
+    }
+    let result: Result<Outcome, String> = run::run_input(&mut vm);
+    stdout().flush().unwrap();
+    match result {
+        Result::Ok(Outcome::COMPLETED) => {},
+        Result::Ok(Outcome::HALTED(code)) => {
+            exit(code.unwrap_or(0));
+        },
+        Result::Ok(Outcome::YIELDED) => unreachable!(),
+        Result::Err(msg) => {
+            eprintln!("error: {}", msg);
+            exit(1);
+        },
+    }
+}
+
+/// Entry point
+fn main() {
     let arg_parse_result = arguments::parse();
     if let Err(err) = arg_parse_result {
         eprintln!("{}", err.msg);
@@ -42,11 +126,33 @@ fn main() {
     let (behavior, options): (Behavior, Options) = arg_parse_result.unwrap();
     match behavior {
         Behavior::HELP => {
+            banner();
             arguments::help();
         },
         Behavior::REPL => {
-            repl::repl(&options);
-        }
+            interact(&options, vm(&options));
+        },
+        Behavior::RUN | Behavior::EVAL => {
+            let mut vm: VM = vm(&options);
+            let mut proceeding: bool = true;
+            for file in options.files.iter() {
+                let result: Result<Outcome, String> = run::run_file(&mut vm, file);
+                proceeding = proceed(&options, &mut vm, result);
+                if !proceeding {
+                    break;
+                }
+            }
+            for expression in options.expressions.iter() {
+                if !proceeding {
+                    break;
+                }
+                let result: Result<Outcome, String> = run::run_expression(&mut vm, expression);
+                proceeding = proceed(&options, &mut vm, result);
+            }
+            if options.interactive {
+                interact(&options, vm);
+            }
+        },
     }
 
 }
@@ -1,20 +1,18 @@
 //! OxForth CLI - Read, Execute, Print Loop
 
-use std::io::{stdin, stdout, Write};
+use std::io::{stdout, Write};
+use std::process::exit;
 
-use oxforth::compiler::scanner::scan;
+use oxforth::compiler::scanner::{scan, scan_remaining};
 use oxforth::compiler::parser::parse;
 use oxforth::vm::interpreter::execute;
-use oxforth::vm::{Data, VM};
+use oxforth::vm::{Data, Outcome, VM};
 
 use super::arguments::Options;
 
-pub fn repl(options: &Options) {
-    println!("Ctrl-C to exit");
-    println!("");
-
-    let mut vm = VM::default();
-    vm.define_core_words();
+pub fn repl(options: &Options, mut vm: VM) {
+    println!("Ctrl-D to exit");
+    println!();
 
     stdout().flush().unwrap();
 
@@ -22,99 +20,137 @@ pub fn repl(options: &Options) {
         print!("< ");
         stdout().flush().unwrap();
 
-        // Read text from the user
-        let mut input = String::new();
-        stdin().read_line(&mut input).unwrap();
+        // Read text from the user, stopping at the end of input (e.g. Ctrl-D)
+        let input: String = match vm.input.read_line() {
+            Result::Ok(Some(input)) => input,
+            Result::Ok(None) => {
+                println!();
+                break;
+            },
+            Result::Err(err) => {
+                eprintln!("error: {}", err);
+                exit(1);
+            },
+        };
         if input.trim().is_empty() {
             continue;
         }
 
-        // Scan the input for tokens
-        if options.verbose {
-            print!("> scan ");
-            stdout().flush().unwrap();
-        }
-        let scan_result = scan(&input, &mut vm);
-        if let Err(err) = scan_result {
-            print!("error: {}\n\n", err.msg);
-            stdout().flush().unwrap();
-            continue
-        }
-        if options.verbose {
-            print!("ok: {} tokens\n", vm.tokens.len());
-            for token in vm.tokens.iter() {
-                print!("\t{:?}\n", token);
+        // Scanning stops at each word, so the line is interpreted one word at
+        // a time until the parse area is exhausted or an error occurs.
+        let mut scan_result = scan(&input, &mut vm);
+        let mut failed: bool = false;
+        loop {
+
+            // Scan the input for tokens
+            if options.verbose {
+                print!("> scan ");
+                stdout().flush().unwrap();
+            }
+            if let Err(err) = scan_result {
+                print!("error: {}\n\n", err.msg);
+                stdout().flush().unwrap();
+                failed = true;
+                break;
+            }
+            if vm.tokens.is_empty() {
+                if options.verbose {
+                    println!("ok: end of input");
+                }
+                break;
+            }
+            if options.verbose {
+                println!("ok: {} tokens", vm.tokens.len());
+                for token in vm.tokens.iter() {
+                    println!("\t{:?}", token);
+                }
             }
-        }
 
-        // Parse the scanned tokens into operations
-        if options.verbose {
-            print!("> parse ");
-            stdout().flush().unwrap();
-        }
-        let parse_result = parse(&mut vm);
-        if let Err(ref err) = parse_result {
-            println!("error: {}", err.msg);
-            stdout().flush().unwrap();
-        } else if options.verbose {
-            println!("ok: {} stack items, {} operations", vm.data_stack.len(), vm.operations.len());
-        }
-        if options.verbose {
-            println!("\tdata stack:");
-            for data in vm.data_stack.iter().rev() {
-                println!("\t\t{:?}", data);
+            // Parse the scanned tokens into operations
+            if options.verbose {
+                print!("> parse ");
+                stdout().flush().unwrap();
             }
-            println!("\toperations:");
-            for operation in vm.operations.iter() {
-                println!("\t\t{:?}", operation);
+            let parse_result = parse(&mut vm);
+            if let Err(ref err) = parse_result {
+                println!("error: {}", err.msg);
+                stdout().flush().unwrap();
+            } else if options.verbose {
+                println!("ok: {} stack items, {} operations", vm.data_stack.len(), vm.operations.len());
             }
-        }
-        if parse_result.is_err() {
             if options.verbose {
-                println!("");
+                println!("\tdata stack:");
+                for data in vm.data_stack.iter().rev() {
+                    println!("\t\t{:?}", data);
+                }
+                println!("\toperations:");
+                for operation in vm.operations.iter() {
+                    println!("\t\t{:?}", operation);
+                }
+            }
+            if parse_result.is_err() {
+                if options.verbose {
+                    println!();
+                }
+                failed = true;
+                break;
             }
-            continue;
-        }
 
-        // Apply the operations against the VM
-        if options.verbose {
-            println!("> output:");
-        }
-        let apply_result = execute(&mut vm);
-        stdout().flush().unwrap();
-        if options.verbose {
-            print!("> execute ");
-        }
-        if let Err(ref err) = apply_result {
-            println!("error: {}", err.msg);
+            // Apply the operations against the VM
+            if options.verbose {
+                println!("> output:");
+            }
+            let apply_result = execute(&mut vm);
             stdout().flush().unwrap();
-        } else if options.verbose {
-            println!("ok");
-        }
-        if options.verbose {
-            println!("\tdata stack:");
-            for data in vm.data_stack.iter().rev() {
-                println!("\t\t{:?}", data);
+            if options.verbose {
+                print!("> execute ");
+            }
+            if let Err(ref err) = apply_result {
+                println!("error: {}", err.msg);
+                stdout().flush().unwrap();
+            } else if options.verbose {
+                println!("ok");
             }
-            println!("\toperations:");
-            for operation in vm.operations.iter() {
-                println!("\t\t{:?}", operation);
+            if options.verbose {
+                println!("\tdata stack:");
+                for data in vm.data_stack.iter().rev() {
+                    println!("\t\t{:?}", data);
+                }
+                println!("\toperations:");
+                for operation in vm.operations.iter() {
+                    println!("\t\t{:?}", operation);
+                }
+                println!();
             }
-            println!("");
-        } else {
-            if let Some(cell) = vm.data_stack.last() {
+            if apply_result.is_err() {
+                failed = true;
+                break;
+            }
+            if let Ok(Outcome::HALTED(code)) = apply_result {
+                println!("It's time to say goodbye~");
+                stdout().flush().unwrap();
+                exit(code.unwrap_or(0));
+            }
+
+            scan_result = scan_remaining(&mut vm);
+        }
+
+        // Errors abandon the rest of the line, and any definition in progress
+        if failed {
+            vm.reset();
+        }
+
+        if !options.verbose && !failed {
+            if vm.compiling.is_some() {
+                println!("compiled");
+            } else if let Some(cell) = vm.data_stack.last() {
                 match cell {
                     Data::NUMBER(n) => {
                         println!("{}", n)
                     },
-                    Data::STRING(s) => {
-                        println!("{}", s);
-                    }
                 }
             } else {
-                if apply_result.is_ok() {
-                    println!("ok");
-                }
+                println!("ok");
             }
         }
         stdout().flush().unwrap();
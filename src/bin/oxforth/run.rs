@@ -0,0 +1,65 @@
+//! OxForth CLI - Running source files, expressions, and scripts
+
+use std::fs::read_to_string;
+use std::path::Path;
+
+use oxforth::vm::interpreter::{evaluate, include_source};
+use oxforth::vm::{Outcome, VM};
+
+/// Interpret one line of a source named `name`. Errors are described along
+/// with where in the source they occurred.
+fn run_line(vm: &mut VM, name: &str, number: usize, line: &str) -> Result<Outcome, String> {
+    let line: String = match line.ends_with('\n') {
+        true => line.to_string(),
+        false => format!("{}\n", line),
+    };
+    return evaluate(vm, &line).map_err(|err| format!("{}:{}: {}", name, number, err.msg));
+}
+
+/// Definitions can't span sources
+fn check_terminated(vm: &VM, name: &str) -> Result<Outcome, String> {
+    if vm.compiling.is_some() {
+        return Result::Err(format!("{}: unterminated definition at end of input", name));
+    }
+    return Result::Ok(Outcome::COMPLETED);
+}
+
+/// Interpret a source file, as done by INCLUDED. Files it includes are found
+/// relative to it. The file was chosen by the user rather than the program, so
+/// it's read regardless of the VM's policy on file access.
+pub fn run_file(vm: &mut VM, path: &str) -> Result<Outcome, String> {
+    let source: String = read_to_string(path).map_err(|err| format!("{}: {}", path, err))?;
+    if let Outcome::HALTED(code) = include_source(vm, Path::new(path), &source).map_err(|err| err.msg)? {
+        return Result::Ok(Outcome::HALTED(code));
+    }
+    return check_terminated(vm, path);
+}
+
+/// Interpret an expression given on the command line
+pub fn run_expression(vm: &mut VM, expression: &str) -> Result<Outcome, String> {
+    for (number, line) in expression.lines().enumerate() {
+        if let Outcome::HALTED(code) = run_line(vm, "-e", number + 1, line)? {
+            return Result::Ok(Outcome::HALTED(code));
+        }
+    }
+    return check_terminated(vm, "-e");
+}
+
+/// Interpret the VM's input source as a script, a line at a time until the
+/// end of input. Words reading input (e.g. ACCEPT) consume the lines that
+/// follow the one being interpreted.
+pub fn run_input(vm: &mut VM) -> Result<Outcome, String> {
+    let mut number: usize = 0;
+    loop {
+        let line: String = match vm.input.read_line() {
+            Result::Ok(Some(line)) => line,
+            Result::Ok(None) => break,
+            Result::Err(err) => return Result::Err(format!("stdin: {}", err)),
+        };
+        number += 1;
+        if let Outcome::HALTED(code) = run_line(vm, "stdin", number, &line)? {
+            return Result::Ok(Outcome::HALTED(code));
+        }
+    }
+    return check_terminated(vm, "stdin");
+}
@@ -0,0 +1,328 @@
+//! OxForth CLI - Terminal input
+//!
+//! Lines typed at the REPL are read with the terminal in its usual (cooked)
+//! mode, where the terminal echoes and edits input a line at a time. KEY and
+//! friends need each key as soon as it's pressed, so the terminal is switched
+//! into raw mode by the first key read, and stays that way for as long as the
+//! program keeps reading keys. It's switched back by the next line read, or
+//! when the process exits.
+//!
+//! Standard input is read unbuffered, a line or a key at a time, so that KEY?
+//! can ask the operating system whether there's anything left to read.
+
+use std::collections::VecDeque;
+use std::io;
+
+use oxforth::vm::io::InputSource;
+
+#[cfg(unix)]
+extern "C" {
+    fn isatty(fd: std::os::raw::c_int) -> std::os::raw::c_int;
+}
+
+/// Whether the process' standard input is an interactive terminal
+pub fn stdin_is_terminal() -> bool {
+    #[cfg(unix)]
+    {
+        // Safety: isatty only inspects the given file descriptor
+        return unsafe { isatty(0) } == 1;
+    }
+    #[cfg(not(unix))]
+    {
+        return false;
+    }
+}
+
+/// Switching standard input between cooked and raw mode via termios(3). The
+/// layout of struct termios varies between platforms, so it's only declared
+/// for the ones it's known on.
+#[cfg(all(
+    any(target_os = "linux", target_os = "macos"),
+    any(target_arch = "x86_64", target_arch = "aarch64"),
+))]
+mod raw {
+
+    use std::io;
+    use std::mem::MaybeUninit;
+    use std::os::raw::c_int;
+    use std::sync::{Mutex, Once};
+
+    #[cfg(target_os = "linux")]
+    type Flags = std::os::raw::c_uint;
+    #[cfg(target_os = "macos")]
+    type Flags = std::os::raw::c_ulong;
+
+    #[cfg(target_os = "linux")]
+    const NCCS: usize = 32;
+    #[cfg(target_os = "macos")]
+    const NCCS: usize = 20;
+
+    #[cfg(target_os = "linux")]
+    const ICANON: Flags = 0o2;
+    #[cfg(target_os = "macos")]
+    const ICANON: Flags = 0x100;
+
+    const ECHO: Flags = 0o10;
+
+    #[cfg(target_os = "linux")]
+    const VMIN: usize = 6;
+    #[cfg(target_os = "macos")]
+    const VMIN: usize = 16;
+
+    #[cfg(target_os = "linux")]
+    const VTIME: usize = 5;
+    #[cfg(target_os = "macos")]
+    const VTIME: usize = 17;
+
+    const TCSANOW: c_int = 0;
+
+    /// Terminal settings, laid out as the C library's struct termios
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    struct Termios {
+        c_iflag: Flags,
+        c_oflag: Flags,
+        c_cflag: Flags,
+        c_lflag: Flags,
+        #[cfg(target_os = "linux")]
+        c_line: u8,
+        c_cc: [u8; NCCS],
+        c_ispeed: Flags,
+        c_ospeed: Flags,
+    }
+
+    extern "C" {
+        fn tcgetattr(fd: c_int, termios: *mut Termios) -> c_int;
+        fn tcsetattr(fd: c_int, action: c_int, termios: *const Termios) -> c_int;
+        fn atexit(function: extern "C" fn()) -> c_int;
+    }
+
+    /// Settings standard input had before it was switched into raw mode, while
+    /// it's in raw mode
+    static COOKED: Mutex<Option<Termios>> = Mutex::new(None);
+
+    static LEAVE_AT_EXIT: Once = Once::new();
+
+    /// Switch standard input into raw mode, where reads wait for a single key,
+    /// unless it already is
+    pub fn enter() -> io::Result<()> {
+        let mut cooked = COOKED.lock().unwrap();
+        if cooked.is_some() {
+            return Result::Ok(());
+        }
+        let mut settings: MaybeUninit<Termios> = MaybeUninit::uninit();
+        // Safety: tcgetattr fills in the settings if it succeeds
+        if unsafe { tcgetattr(0, settings.as_mut_ptr()) } != 0 {
+            return Result::Err(io::Error::last_os_error());
+        }
+        let settings: Termios = unsafe { settings.assume_init() };
+        let mut raw: Termios = settings;
+        raw.c_lflag &= !(ICANON | ECHO);
+        raw.c_cc[VMIN] = 1;
+        raw.c_cc[VTIME] = 0;
+        if unsafe { tcsetattr(0, TCSANOW, &raw) } != 0 {
+            return Result::Err(io::Error::last_os_error());
+        }
+        *cooked = Some(settings);
+        LEAVE_AT_EXIT.call_once(|| {
+            unsafe { atexit(leave_at_exit) };
+        });
+        return Result::Ok(());
+    }
+
+    /// Switch standard input back to the mode it was in before raw mode, if
+    /// it's in raw mode
+    pub fn leave() -> io::Result<()> {
+        let cooked: Option<Termios> = match COOKED.lock() {
+            Result::Ok(mut cooked) => cooked.take(),
+            Result::Err(_) => None,
+        };
+        if let Some(cooked) = cooked {
+            if unsafe { tcsetattr(0, TCSANOW, &cooked) } != 0 {
+                return Result::Err(io::Error::last_os_error());
+            }
+        }
+        return Result::Ok(());
+    }
+
+    extern "C" fn leave_at_exit() {
+        let _ = leave();
+    }
+
+}
+
+/// Elsewhere, keys are read as the terminal hands them over, i.e. a line at a
+/// time
+#[cfg(not(all(
+    any(target_os = "linux", target_os = "macos"),
+    any(target_arch = "x86_64", target_arch = "aarch64"),
+)))]
+mod raw {
+
+    use std::io;
+
+    pub fn enter() -> io::Result<()> {
+        return Result::Ok(());
+    }
+
+    pub fn leave() -> io::Result<()> {
+        return Result::Ok(());
+    }
+
+}
+
+/// Reading standard input's file descriptor directly, bypassing the buffer
+/// std keeps for it
+#[cfg(unix)]
+mod descriptor {
+
+    use std::io;
+    use std::os::raw::{c_int, c_short, c_void};
+
+    #[cfg(target_os = "linux")]
+    type Nfds = std::os::raw::c_ulong;
+    #[cfg(not(target_os = "linux"))]
+    type Nfds = std::os::raw::c_uint;
+
+    const POLLIN: c_short = 1;
+
+    #[repr(C)]
+    struct PollFd {
+        fd: c_int,
+        events: c_short,
+        revents: c_short,
+    }
+
+    extern "C" {
+        fn read(fd: c_int, buf: *mut c_void, count: usize) -> isize;
+        fn poll(fds: *mut PollFd, nfds: Nfds, timeout: c_int) -> c_int;
+    }
+
+    /// Read what's available of standard input into `buf`, waiting for
+    /// something if there's nothing yet. Returns 0 at the end of input.
+    pub fn read_stdin(buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            // Safety: buf is writable for its length
+            let read: isize = unsafe { read(0, buf.as_mut_ptr() as *mut c_void, buf.len()) };
+            if read >= 0 {
+                return Result::Ok(read as usize);
+            }
+            let err: io::Error = io::Error::last_os_error();
+            if err.kind() != io::ErrorKind::Interrupted {
+                return Result::Err(err);
+            }
+        }
+    }
+
+    /// Whether reading standard input would return without waiting
+    pub fn stdin_ready() -> io::Result<bool> {
+        let mut fd: PollFd = PollFd { fd: 0, events: POLLIN, revents: 0 };
+        // Safety: poll only writes to the one entry it's given
+        let ready: c_int = unsafe { poll(&mut fd, 1, 0) };
+        if ready < 0 {
+            return Result::Err(io::Error::last_os_error());
+        }
+        return Result::Ok(ready > 0);
+    }
+
+}
+
+/// Without file descriptors to poll, only std's buffer is read
+#[cfg(not(unix))]
+mod descriptor {
+
+    use std::io::{self, stdin, Read};
+
+    pub fn read_stdin(buf: &mut [u8]) -> io::Result<usize> {
+        return stdin().lock().read(buf);
+    }
+
+    pub fn stdin_ready() -> io::Result<bool> {
+        return Result::Ok(false);
+    }
+
+}
+
+/// Input read from standard input, which is switched into raw mode for key
+/// reads if it's a terminal
+#[derive(Default)]
+pub struct TerminalSource {
+
+    /// Whether standard input is a terminal, which is switched into raw mode
+    raw: bool,
+
+    /// Bytes read but not yet handed out (e.g. the rest of a line, or the rest
+    /// of a character whose first byte was read by KEY)
+    pending: VecDeque<u8>,
+
+}
+
+impl TerminalSource {
+
+    /// Read more of standard input into the pending bytes. Returns false at the
+    /// end of input.
+    fn fill(&mut self) -> io::Result<bool> {
+        let mut buf: [u8; 1024] = [0; 1024];
+        let read: usize = descriptor::read_stdin(&mut buf)?;
+        self.pending.extend(&buf[..read]);
+        return Result::Ok(read > 0);
+    }
+
+    /// Switch the terminal into raw mode, if standard input is one
+    fn enter_raw(&self) -> io::Result<()> {
+        if self.raw {
+            raw::enter()?;
+        }
+        return Result::Ok(());
+    }
+
+}
+
+impl Drop for TerminalSource {
+    fn drop(&mut self) {
+        let _ = raw::leave();
+    }
+}
+
+impl InputSource for TerminalSource {
+    fn read_line(&mut self) -> io::Result<Option<String>> {
+        raw::leave()?;
+        while !self.pending.contains(&b'\n') && self.fill()? {}
+        if self.pending.is_empty() {
+            return Result::Ok(None);
+        }
+
+        // Characters are decoded whole, even if KEY read the start of one
+        let length: usize = match self.pending.iter().position(|byte| *byte == b'\n') {
+            Some(newline) => newline + 1,
+            None => self.pending.len(),
+        };
+        let line: Vec<u8> = self.pending.drain(..length).collect();
+        return Result::Ok(Some(String::from_utf8_lossy(&line).into_owned()));
+    }
+    fn read_key(&mut self) -> io::Result<Option<u8>> {
+        if self.pending.is_empty() {
+            self.enter_raw()?;
+            self.fill()?;
+        }
+        return Result::Ok(self.pending.pop_front());
+    }
+    fn key_available(&mut self) -> io::Result<bool> {
+        if !self.pending.is_empty() {
+            return Result::Ok(true);
+        }
+        self.enter_raw()?;
+        return descriptor::stdin_ready();
+    }
+}
+
+/// The source REPL input is read from: standard input, whether it's the
+/// terminal or not (e.g. it's piped in)
+pub fn input_source() -> Box<dyn InputSource> {
+    return Box::new(
+        TerminalSource {
+            raw: stdin_is_terminal(),
+            pending: VecDeque::new(),
+        }
+    );
+}
@@ -2,9 +2,9 @@
 
 use super::CompilerError;
 
-use super::scanner::Symbol;
+use super::scanner::{parse_float, Symbol};
 
-use super::super::vm::{Data, VM};
+use super::super::vm::{Data, Definition, VM};
 
 /// Operations change VM state (e.g. dictionary, stacks, etc).
 #[allow(non_camel_case_types)]
@@ -21,14 +21,123 @@ pub enum Operation {
     /// https://forth-standard.org/standard/core/ABS
     ABS,
 
+    /// ( c-addr +n1 -- +n2 ) Receive a line of at most n1 characters, storing them at
+    /// c-addr. n2 is the number of characters received.
+    /// https://forth-standard.org/standard/core/ACCEPT
+    ACCEPT,
+
+    /// ( "<spaces>name" -- xt ) xt is the execution token name, a deferred word, is set to
+    /// execute. When compiling, this is done at run-time instead.
+    /// https://forth-standard.org/standard/core/ACTION-OF
+    ACTION_OF,
+
     /// ( n1 | u1 n2 | u2 -- n3 | u3 ) Add n2 | u2 to n1 | u1, giving the sum n3 | u3.
     /// https://forth-standard.org/standard/core/Plus
     ADD,
 
+    /// ( n -- ) Reserve n address units of data space, or release them if n is negative.
+    /// https://forth-standard.org/standard/core/ALLOT
+    ALLOT,
+
+    /// ( -- ) Duplicate the first wordlist in the search order, so that it's searched
+    /// first until replaced (e.g. by executing a vocabulary).
+    /// https://forth-standard.org/standard/search/ALSO
+    ALSO,
+
+    /// ( n -- c-addr u ) c-addr u is the nth command-line argument given to the program,
+    /// or is empty if there isn't one. The zeroth is the program itself.
+    ARG,
+
+    /// ( -- u ) u is the number of command-line arguments given to the program, including
+    /// the program itself.
+    ARGC,
+
+    /// ( -- a-addr ) a-addr is the address of a cell containing the current
+    /// number-conversion radix.
+    /// https://forth-standard.org/standard/core/BASE
+    BASE,
+
+    /// ( fam1 -- fam2 ) Modify the file access method fam1 to select binary access.
+    /// https://forth-standard.org/standard/file/BIN
+    BIN,
+
+    /// ( c-addr u -- ) If u is greater than zero, store the character value for space
+    /// in u consecutive character positions beginning at c-addr.
+    /// https://forth-standard.org/standard/string/BLANK
+    BLANK,
+
+    /// Compilation: ( "<spaces>arg ... | val ... -- out ... :}" -- ) Declare locals of the
+    /// definition being compiled. At run-time, the args are initialized ( x1 ... xn -- )
+    /// from the stack, with the first arg taking the deepest item.
+    /// https://forth-standard.org/standard/locals/bColon
+    BRACE_COLON,
+
+    /// Compilation: ( "<spaces>name" -- ) Parse name and compile the value of its first
+    /// character as a literal. Interpretation: like CHAR.
+    /// https://forth-standard.org/standard/core/BracketCHAR
+    BRACKET_CHAR,
+
+    /// Compilation: ( -- quotation-sys colon-sys ) Suspend the definition being compiled,
+    /// if any, and begin compiling a nameless definition ("quotation") within it.
+    /// https://forth-standard.org/standard/core/BracketColon
+    BRACKET_COLON,
+
+    /// Compilation: ( "<spaces>name" -- ) Compile the execution token of name as a literal.
+    /// https://forth-standard.org/standard/core/BracketTick
+    BRACKET_TICK,
+
+    /// ( -- ) Continue execution at the given index of the definition being executed.
+    BRANCH(usize),
+
+    /// ( x -- ) If x is zero, continue execution at the given index of the definition
+    /// being executed.
+    BRANCH_IF_ZERO(usize),
+
     /// "Return control to the host operating system"
     /// https://forth-standard.org/standard/tools/BYE
     BYE,
 
+    /// ( n -- ) Return control to the host, with exit code n.
+    BYE_CODE,
+
+    /// ( c-addr -- char ) Fetch the character stored at c-addr.
+    /// https://forth-standard.org/standard/core/CFetch
+    C_FETCH,
+
+    /// ( "ccc<quote>" -- c-addr ) Parse ccc delimited by a double-quote, giving the
+    /// counted string at c-addr. The string is transient when interpreted.
+    /// https://forth-standard.org/standard/core/Cq
+    C_QUOTE,
+
+    /// ( char c-addr -- ) Store char at c-addr.
+    /// https://forth-standard.org/standard/core/CStore
+    C_STORE,
+
+    /// Compilation: ( -- case-sys ) Begin a CASE ... ENDCASE structure.
+    /// https://forth-standard.org/standard/core/CASE
+    CASE,
+
+    /// ( i * x -- j * x ) Execute the definition with the given execution token.
+    CALL(usize),
+
+    /// ( fileid -- ior ) Close the file identified by fileid.
+    /// https://forth-standard.org/standard/file/CLOSE-FILE
+    CLOSE_FILE,
+
+    /// ( c-addr1 c-addr2 u -- ) Copy u characters from c-addr1 to c-addr2, proceeding
+    /// character-by-character from lower addresses to higher addresses.
+    /// https://forth-standard.org/standard/string/CMOVE
+    CMOVE,
+
+    /// ( c-addr1 c-addr2 u -- ) Copy u characters from c-addr1 to c-addr2, proceeding
+    /// character-by-character from higher addresses to lower addresses.
+    /// https://forth-standard.org/standard/string/CMOVEtop
+    CMOVE_UP,
+
+    /// ( "<spaces>name" -- char ) Parse name and push the value of its first character.
+    /// https://forth-standard.org/standard/core/CHAR
+    CHAR,
+
     /// ( x1 x2 -- flag ) flag is true if and only if x1 is bit-for-bit the same as x2.
     /// https://forth-standard.org/standard/core/Equal
     CMP_EQ,
@@ -45,11 +154,93 @@ pub enum Operation {
     /// https://forth-standard.org/standard/core/ne
     CMP_NE,
 
+    /// ( "<spaces>name" -- colon-sys ) Parse name and begin compiling a definition of it.
+    /// https://forth-standard.org/standard/core/Colon
+    COLON,
+
+    /// ( c-addr1 u1 c-addr2 u2 -- n ) Compare the two strings lexicographically. n is
+    /// zero if they're identical, -1 if the first is less, and 1 otherwise.
+    /// https://forth-standard.org/standard/string/COMPARE
+    COMPARE,
+
+    /// ( c-addr1 -- c-addr2 u ) Return the character string specification for the
+    /// counted string stored at c-addr1.
+    /// https://forth-standard.org/standard/core/COUNT
+    COUNT,
+
+    /// ( -- ) Cause subsequent output to appear at the beginning of the next line.
+    /// https://forth-standard.org/standard/core/CR
+    CR,
+
+    /// ( c-addr u fam -- fileid ior ) Create the file named by c-addr u, replacing any
+    /// existing file, and open it with the file access method fam.
+    /// https://forth-standard.org/standard/file/CREATE-FILE
+    CREATE_FILE,
+
+    /// ( d -- ) ( F: -- r ) r is the floating-point equivalent of d.
+    /// https://forth-standard.org/standard/float/DtoF
+    D_TO_F,
+
+    /// ( c-addr u1 -- c-addr u2 ) u2 is u1 less the number of trailing spaces.
+    /// https://forth-standard.org/standard/string/MinusTRAILING
+    DASH_TRAILING,
+
+    /// ( -- ) Set the numeric conversion radix to ten (decimal).
+    /// https://forth-standard.org/standard/core/DECIMAL
+    DECIMAL,
+
+    /// ( "<spaces>name" -- ) Define name as a deferred word, which executes whatever
+    /// execution token it's set to execute (e.g. via IS).
+    /// https://forth-standard.org/standard/core/DEFER
+    DEFER,
+
+    /// ( xt1 -- xt2 ) xt2 is the execution token xt1, a deferred word, is set to execute.
+    /// https://forth-standard.org/standard/core/DEFERFetch
+    DEFER_FETCH,
+
+    /// ( xt2 xt1 -- ) Set the deferred word xt1 to execute xt2.
+    /// https://forth-standard.org/standard/core/DEFERStore
+    DEFER_STORE,
+
+    /// ( i * x -- j * x ) Execute the given execution token in place of the deferred word
+    /// being executed. Deferred words that haven't been set yet have none.
+    DEFERRED(Option<usize>),
+
+    /// ( -- ) Make the first wordlist in the search order the compilation wordlist.
+    /// https://forth-standard.org/standard/search/DEFINITIONS
+    DEFINITIONS,
+
+    /// ( c-addr u -- ior ) Delete the file named by c-addr u.
+    /// https://forth-standard.org/standard/file/DELETE-FILE
+    DELETE_FILE,
+
     /// ( n1 n2 -- n3 ) Divide n1 by n2, giving the single-cell quotient n3.
     /// An ambiguous condition exists if n2 is zero.
     /// https://forth-standard.org/standard/core/Div
     DIV,
 
+    /// Compilation: ( -- do-sys ) Begin a DO ... LOOP structure, which at run-time
+    /// repeats ( n1 | u1 n2 | u2 -- ) with n2 | u2 as the index and n1 | u1 as the limit.
+    /// https://forth-standard.org/standard/core/DO
+    DO,
+
+    /// ( n -- ) Display n followed by a space.
+    /// https://forth-standard.org/standard/core/d
+    DOT,
+
+    /// ( "ccc<quote>" -- ) Parse ccc delimited by a double-quote and display it. When
+    /// compiling, it's displayed at run-time instead.
+    /// https://forth-standard.org/standard/core/Dotq
+    DOT_QUOTE,
+
+    /// ( n1 n2 -- ) Display n1 right aligned in a field n2 characters wide.
+    /// https://forth-standard.org/standard/core/DotR
+    DOT_R,
+
+    /// ( -- ) Display the contents of the data stack without changing it.
+    /// https://forth-standard.org/standard/tools/DotS
+    DOT_S,
+
     /// ( x -- ) Remove x from the stack.
     /// https://forth-standard.org/standard/core/DROP
     DROP,
@@ -58,10 +249,424 @@ pub enum Operation {
     /// https://forth-standard.org/standard/core/DUP
     DUP,
 
+    /// ( -- x ) Receive one keyboard event x.
+    /// https://forth-standard.org/standard/facility/EKEY
+    EKEY,
+
+    /// ( x -- char true | x false ) If the keyboard event x corresponds to a character,
+    /// return the character and true. Otherwise return x and false.
+    /// https://forth-standard.org/standard/facility/EKEYtoCHAR
+    EKEY_TO_CHAR,
+
+    /// Compilation: ( orig1 -- orig2 ) Resolve the IF before, and continue compilation
+    /// after the THEN to come.
+    /// https://forth-standard.org/standard/core/ELSE
+    ELSE,
+
+    /// ( x -- ) Display the character x.
+    /// https://forth-standard.org/standard/core/EMIT
+    EMIT,
+
+    /// Compilation: ( case-sys -- ) End a CASE structure. At run-time, discard the case
+    /// selector ( x -- ) if no OF matched it.
+    /// https://forth-standard.org/standard/core/ENDCASE
+    ENDCASE,
+
+    /// Compilation: ( case-sys1 of-sys -- case-sys2 ) End an OF clause, continuing after
+    /// ENDCASE at run-time.
+    /// https://forth-standard.org/standard/core/ENDOF
+    ENDOF,
+
+    /// ( i * x xt -- j * x ) Execute the definition identified by xt.
+    /// https://forth-standard.org/standard/core/EXECUTE
+    EXECUTE,
+
+    /// ( -- ) ( R: nest-sys -- ) Return from the definition being executed.
+    /// https://forth-standard.org/standard/core/EXIT
+    EXIT,
+
+    /// ( F: r1 -- r2 ) r2 is the absolute value of r1.
+    /// https://forth-standard.org/standard/float/FABS
+    F_ABS,
+
+    /// ( F: r1 -- r2 ) r2 is the principal radian angle whose cosine is r1.
+    /// https://forth-standard.org/standard/float/FACOS
+    F_ACOS,
+
+    /// ( F: r1 -- r2 ) r2 is the floating-point value whose hyperbolic cosine is r1.
+    /// https://forth-standard.org/standard/float/FACOSH
+    F_ACOSH,
+
+    /// ( F: r1 r2 -- r3 ) Add r1 to r2, giving the sum r3.
+    /// https://forth-standard.org/standard/float/FPlus
+    F_ADD,
+
+    /// ( -- ) Align the data-space pointer to hold a float.
+    /// https://forth-standard.org/standard/float/FALIGN
+    F_ALIGN,
+
+    /// ( addr -- f-addr ) f-addr is the first address at or after addr that may hold a float.
+    /// https://forth-standard.org/standard/float/FALIGNED
+    F_ALIGNED,
+
+    /// ( F: r1 -- r2 ) Raise ten to the power r1, giving r2.
+    /// https://forth-standard.org/standard/float/FALOG
+    F_ALOG,
+
+    /// ( F: r1 -- r2 ) r2 is the principal radian angle whose sine is r1.
+    /// https://forth-standard.org/standard/float/FASIN
+    F_ASIN,
+
+    /// ( F: r1 -- r2 ) r2 is the floating-point value whose hyperbolic sine is r1.
+    /// https://forth-standard.org/standard/float/FASINH
+    F_ASINH,
+
+    /// ( F: r1 -- r2 ) r2 is the principal radian angle whose tangent is r1.
+    /// https://forth-standard.org/standard/float/FATAN
+    F_ATAN,
+
+    /// ( F: r1 r2 -- r3 ) r3 is the radian angle whose tangent is r1/r2, in the quadrant
+    /// given by the signs of r1 and r2.
+    /// https://forth-standard.org/standard/float/FATAN2
+    F_ATAN2,
+
+    /// ( F: r1 -- r2 ) r2 is the floating-point value whose hyperbolic tangent is r1.
+    /// https://forth-standard.org/standard/float/FATANH
+    F_ATANH,
+
+    /// ( -- flag ) ( F: r1 r2 -- ) flag is true if and only if r1 is less than r2.
+    /// https://forth-standard.org/standard/float/Fless
+    F_CMP_LT,
+
+    /// ( "<spaces>name" -- ) ( F: r -- ) Define name to place r on the floating-point stack.
+    /// https://forth-standard.org/standard/float/FCONSTANT
+    F_CONSTANT,
+
+    /// ( F: r1 -- r2 ) r2 is the cosine of the radian angle r1.
+    /// https://forth-standard.org/standard/float/FCOS
+    F_COS,
+
+    /// ( F: r1 -- r2 ) r2 is the hyperbolic cosine of r1.
+    /// https://forth-standard.org/standard/float/FCOSH
+    F_COSH,
+
+    /// ( -- +n ) +n is the number of values on the floating-point stack.
+    /// https://forth-standard.org/standard/float/FDEPTH
+    F_DEPTH,
+
+    /// ( F: r1 r2 -- r3 ) Divide r1 by r2, giving the quotient r3.
+    /// https://forth-standard.org/standard/float/FDiv
+    F_DIV,
+
+    /// ( F: r -- ) Display r in fixed-point notation followed by a space.
+    /// https://forth-standard.org/standard/float/Fd
+    F_DOT,
+
+    /// ( F: r -- ) Remove r from the floating-point stack.
+    /// https://forth-standard.org/standard/float/FDROP
+    F_DROP,
+
+    /// ( F: r -- r r ) Duplicate r.
+    /// https://forth-standard.org/standard/float/FDUP
+    F_DUP,
+
+    /// ( F: r -- ) Display r in engineering notation, with an exponent that's a multiple
+    /// of three, followed by a space.
+    /// https://forth-standard.org/standard/float/FEd
+    F_E_DOT,
+
+    /// ( F: r1 -- r2 ) Raise e to the power r1, giving r2.
+    /// https://forth-standard.org/standard/float/FEXP
+    F_EXP,
+
+    /// ( F: r1 -- r2 ) Raise e to the power r1 and subtract one, giving r2.
+    /// https://forth-standard.org/standard/float/FEXPMinus1
+    F_EXPM1,
+
+    /// ( f-addr -- ) ( F: -- r ) r is the float stored at f-addr.
+    /// https://forth-standard.org/standard/float/FFetch
+    F_FETCH,
+
+    /// ( F: r1 -- r2 ) Round r1 toward negative infinity, giving r2.
+    /// https://forth-standard.org/standard/float/FLOOR
+    F_FLOOR,
+
+    /// ( F: -- r ) Place r on the floating-point stack, as compiled for a float literal.
+    F_LITERAL(f64),
+
+    /// ( F: r1 -- r2 ) r2 is the natural logarithm of r1.
+    /// https://forth-standard.org/standard/float/FLN
+    F_LN,
+
+    /// ( F: r1 -- r2 ) r2 is the natural logarithm of r1 plus one.
+    /// https://forth-standard.org/standard/float/FLNP1
+    F_LNP1,
+
+    /// ( F: r1 -- r2 ) r2 is the base-ten logarithm of r1.
+    /// https://forth-standard.org/standard/float/FLOG
+    F_LOG,
+
+    /// ( F: r1 r2 -- r3 ) r3 is the greater of r1 and r2.
+    /// https://forth-standard.org/standard/float/FMAX
+    F_MAX,
+
+    /// ( F: r1 r2 -- r3 ) r3 is the lesser of r1 and r2.
+    /// https://forth-standard.org/standard/float/FMIN
+    F_MIN,
+
+    /// ( F: r1 r2 -- r3 ) Multiply r1 by r2, giving r3.
+    /// https://forth-standard.org/standard/float/FTimes
+    F_MUL,
+
+    /// ( F: r1 -- r2 ) r2 is the negation of r1.
+    /// https://forth-standard.org/standard/float/FNEGATE
+    F_NEGATE,
+
+    /// ( F: r1 r2 -- r1 r2 r1 ) Place a copy of r1 on top of the floating-point stack.
+    /// https://forth-standard.org/standard/float/FOVER
+    F_OVER,
+
+    /// ( F: r1 r2 -- r3 ) Raise r1 to the power r2, giving r3.
+    /// https://forth-standard.org/standard/float/FTimesTimes
+    F_POW,
+
+    /// ( -- flag ) ( F: r1 r2 r3 -- ) flag is true if r1 and r2 are approximately equal:
+    /// within r3 if it's positive, identical if it's zero, and within |r3| times the sum
+    /// of their magnitudes if it's negative.
+    /// https://forth-standard.org/standard/float/Ftilde
+    F_PROXIMATE,
+
+    /// ( F: r1 r2 r3 -- r2 r3 r1 ) Rotate the top three floats.
+    /// https://forth-standard.org/standard/float/FROT
+    F_ROT,
+
+    /// ( F: r1 -- r2 ) Round r1 to the nearest integral value, giving r2. Halfway cases
+    /// are rounded to even.
+    /// https://forth-standard.org/standard/float/FROUND
+    F_ROUND,
+
+    /// ( F: r -- ) Display r in scientific notation followed by a space.
+    /// https://forth-standard.org/standard/float/FSd
+    F_S_DOT,
+
+    /// ( F: r1 -- r2 ) r2 is the sine of the radian angle r1.
+    /// https://forth-standard.org/standard/float/FSIN
+    F_SIN,
+
+    /// ( F: r1 -- r2 r3 ) r2 is the sine and r3 the cosine of the radian angle r1.
+    /// https://forth-standard.org/standard/float/FSINCOS
+    F_SINCOS,
+
+    /// ( F: r1 -- r2 ) r2 is the hyperbolic sine of r1.
+    /// https://forth-standard.org/standard/float/FSINH
+    F_SINH,
+
+    /// ( F: r1 -- r2 ) r2 is the square root of r1.
+    /// https://forth-standard.org/standard/float/FSQRT
+    F_SQRT,
+
+    /// ( f-addr -- ) ( F: r -- ) Store r at f-addr.
+    /// https://forth-standard.org/standard/float/FStore
+    F_STORE,
+
+    /// ( F: r1 r2 -- r3 ) Subtract r2 from r1, giving the difference r3.
+    /// https://forth-standard.org/standard/float/FMinus
+    F_SUB,
+
+    /// ( F: r1 r2 -- r2 r1 ) Exchange the top two floats.
+    /// https://forth-standard.org/standard/float/FSWAP
+    F_SWAP,
+
+    /// ( F: r1 -- r2 ) r2 is the tangent of the radian angle r1.
+    /// https://forth-standard.org/standard/float/FTAN
+    F_TAN,
+
+    /// ( F: r1 -- r2 ) r2 is the hyperbolic tangent of r1.
+    /// https://forth-standard.org/standard/float/FTANH
+    F_TANH,
+
+    /// ( -- d ) ( F: r -- ) d is the integer part of r.
+    /// https://forth-standard.org/standard/float/FtoD
+    F_TO_D,
+
+    /// ( -- n ) ( F: r -- ) n is the integer part of r.
+    /// https://forth-standard.org/standard/float/FtoS
+    F_TO_S,
+
+    /// ( F: r1 -- r2 ) Round r1 toward zero, giving r2.
+    /// https://forth-standard.org/standard/float/FTRUNC
+    F_TRUNC,
+
+    /// ( "<spaces>name" -- ) Define name to place the address of a float-sized region of
+    /// data space on the stack.
+    /// https://forth-standard.org/standard/float/FVARIABLE
+    F_VARIABLE,
+
+    /// ( -- flag ) ( F: r -- ) flag is true if and only if r is equal to zero.
+    /// https://forth-standard.org/standard/float/FZeroEqual
+    F_ZERO_EQ,
+
+    /// ( -- flag ) ( F: r -- ) flag is true if and only if r is less than zero.
+    /// https://forth-standard.org/standard/float/FZeroless
+    F_ZERO_LT,
+
+    /// ( a-addr -- x ) x is the value stored at a-addr.
+    /// https://forth-standard.org/standard/core/Fetch
+    FETCH,
+
+    /// ( fileid -- ud ior ) ud is the current position within the file identified by fileid.
+    /// https://forth-standard.org/standard/file/FILE-POSITION
+    FILE_POSITION,
+
+    /// ( fileid -- ud ior ) ud is the size, in characters, of the file identified by fileid.
+    /// https://forth-standard.org/standard/file/FILE-SIZE
+    FILE_SIZE,
+
+    /// Compilation: ( F: r -- ) Compile r as a literal, to be placed on the floating-point
+    /// stack at run-time.
+    /// https://forth-standard.org/standard/float/FLITERAL
+    FLITERAL,
+
+    /// ( f-addr1 -- f-addr2 ) Add the size of a float to f-addr1.
+    /// https://forth-standard.org/standard/float/FLOATPlus
+    FLOAT_PLUS,
+
+    /// ( n1 -- n2 ) n2 is the size of n1 floats.
+    /// https://forth-standard.org/standard/float/FLOATS
+    FLOATS,
+
+    /// ( "<spaces>name" -- ) Remove name and every word defined after it, releasing the data
+    /// space allotted since.
+    /// https://forth-standard.org/standard/tools/FORGET
+    FORGET,
+
+    /// ( -- wid ) wid identifies the wordlist the words built into the VM are kept in.
+    /// https://forth-standard.org/standard/search/FORTH-WORDLIST
+    FORTH_WORDLIST,
+
+    /// ( -- wid ) wid identifies the compilation wordlist.
+    /// https://forth-standard.org/standard/search/GET-CURRENT
+    GET_CURRENT,
+
+    /// ( -- widn ... wid1 n ) Return the n wordlists of the search order, wid1 being
+    /// searched first.
+    /// https://forth-standard.org/standard/search/GET-ORDER
+    GET_ORDER,
+
+    /// ( -- addr ) addr is the data-space pointer.
+    /// https://forth-standard.org/standard/core/HERE
+    HERE,
+
+    /// ( -- ) Set contents of BASE to sixteen.
+    /// https://forth-standard.org/standard/core/HEX
+    HEX,
+
+    /// ( char -- ) Add char to the beginning of the pictured numeric output string.
+    /// https://forth-standard.org/standard/core/HOLD
+    HOLD,
+
+    /// ( c-addr u -- ) Add the string to the beginning of the pictured numeric output string.
+    /// https://forth-standard.org/standard/core/HOLDS
+    HOLDS,
+
+    /// ( -- n | u ) ( R: loop-sys -- loop-sys ) n | u is the index of the innermost loop.
+    /// https://forth-standard.org/standard/core/I
+    I,
+
+    /// Compilation: ( -- orig ) Compile a branch past the matching ELSE or THEN, taken at
+    /// run-time when the top of the stack is zero.
+    /// https://forth-standard.org/standard/core/IF
+    IF,
+
+    /// ( i*x "<spaces>name" -- j*x ) Interpret the source file named by name.
+    /// https://forth-standard.org/standard/file/INCLUDE
+    INCLUDE,
+
+    /// ( i*x c-addr u -- j*x ) Interpret the source file named by c-addr u. Relative
+    /// paths are relative to the source file being interpreted, if any.
+    /// https://forth-standard.org/standard/file/INCLUDED
+    INCLUDED,
+
+    /// ( xt "<spaces>name" -- ) Set name, a deferred word, to execute xt. When compiling,
+    /// this is done at run-time instead.
+    /// https://forth-standard.org/standard/core/IS
+    IS,
+
+    /// ( -- n | u ) ( R: loop-sys1 loop-sys2 -- loop-sys1 loop-sys2 ) n | u is the index
+    /// of the next outer loop.
+    /// https://forth-standard.org/standard/core/J
+    J,
+
     /// ( n1 -- n2 ) Negate n1, giving its arithmetic inverse n2.
     /// https://forth-standard.org/standard/core/NEGATE
     NEGATE,
 
+    /// ( -- c-addr u ) Remove the first command-line argument following the program and
+    /// return it, or an empty string if there isn't one.
+    NEXT_ARG,
+
+    /// Compilation: ( -- of-sys ) Begin an OF clause, which at run-time ( x1 x2 -- | x1 )
+    /// discards both values and continues if they're equal. Otherwise, it discards x2 and
+    /// continues after the clause's ENDOF.
+    /// https://forth-standard.org/standard/core/OF
+    OF,
+
+    /// ( -- char ) Receive one character.
+    /// https://forth-standard.org/standard/core/KEY
+    KEY,
+
+    /// ( -- flag ) If a character is available, return true. Otherwise, return false.
+    /// https://forth-standard.org/standard/facility/KEYq
+    KEY_QUESTION,
+
+    /// ( -- ) ( R: loop-sys -- ) Discard the innermost loop's control parameters and
+    /// continue after its LOOP or +LOOP.
+    /// https://forth-standard.org/standard/core/LEAVE
+    LEAVE,
+
+    /// ( -- x ) Push the given value.
+    LITERAL(i64),
+
+    /// ( -- x ) Push the value of the given local of the definition being executed.
+    LOCAL_FETCH(usize),
+
+    /// ( x -- ) Store x in the given local of the definition being executed.
+    LOCAL_STORE(usize),
+
+    /// ( x1 ... xn -- ) Add locals to the definition being executed: the given number
+    /// initialized from the stack (x1 first), then the given number initialized to zero.
+    LOCALS(usize, usize),
+
+    /// Compilation: ( "<spaces>name ... |" -- ) Declare locals of the definition being
+    /// compiled. At run-time, they're initialized ( x1 ... xn -- ) from the stack, with
+    /// the first name taking the top item.
+    /// https://forth-standard.org/standard/locals/LOCALSbar
+    LOCALS_BAR,
+
+    /// Compilation: ( do-sys -- ) End a DO loop, adding one to the index at run-time.
+    /// https://forth-standard.org/standard/core/LOOP
+    LOOP,
+
+    /// ( n1 | u1 n2 | u2 -- ) ( R: -- loop-sys ) Begin a loop with index n2 | u2 and limit
+    /// n1 | u1, which continues at the given index after the loop is left.
+    LOOP_ENTER(usize),
+
+    /// ( n1 | u1 n2 | u2 -- ) ( R: -- | loop-sys ) Like LOOP_ENTER, but if n1 | u1 and
+    /// n2 | u2 are equal, skip the loop by continuing at the given index.
+    LOOP_ENTER_IF_NE(usize),
+
+    /// ( -- ) ( R: loop-sys1 -- | loop-sys2 ) Add one to the loop index. Continue at the
+    /// given index unless the loop index crossed the limit, otherwise end the loop.
+    LOOP_NEXT(usize),
+
+    /// ( n -- ) ( R: loop-sys1 -- | loop-sys2 ) Like LOOP_NEXT, but add n to the loop index.
+    LOOP_NEXT_BY(usize),
+
+    /// ( "<spaces>name" -- ) Define name to roll the dictionary, data space and search
+    /// order back to their state before name was defined.
+    /// https://forth-standard.org/standard/core/MARKER
+    MARKER,
+
     /// ( n1 n2 -- n3 ) n3 is the greater of n1 and n2.
     /// https://forth-standard.org/standard/core/MAX
     MAX,
@@ -79,10 +684,316 @@ pub enum Operation {
     /// https://forth-standard.org/standard/core/Times
     MUL,
 
+    /// ( i*x -- j*x ) Execute the native word with the given index, which was written
+    /// in Rust by the host (e.g. via VM::define_native()).
+    NATIVE(usize),
+
+    /// ( ud1 -- ud2 ) Divide ud1 by BASE, giving the quotient ud2, and add the digit
+    /// of the remainder to the beginning of the pictured numeric output string.
+    /// https://forth-standard.org/standard/core/num
+    NUM,
+
+    /// ( xd -- c-addr u ) Drop xd. Make the pictured numeric output string available
+    /// as a character string.
+    /// https://forth-standard.org/standard/core/num-end
+    NUM_END,
+
+    /// ( ud1 -- ud2 ) Convert one digit of ud1 according to the rule for #. Continue
+    /// conversion until the quotient is zero. ud2 is zero.
+    /// https://forth-standard.org/standard/core/numS
+    NUM_S,
+
+    /// ( -- ) Initialize the pictured numeric output conversion process.
+    /// https://forth-standard.org/standard/core/num-start
+    NUM_START,
+
+    /// ( -- ) Reduce the search order to the minimum search order, which is just
+    /// FORTH-WORDLIST.
+    /// https://forth-standard.org/standard/search/ONLY
+    ONLY,
+
+    /// ( c-addr u fam -- fileid ior ) Open the file named by c-addr u with the file
+    /// access method fam.
+    /// https://forth-standard.org/standard/file/OPEN-FILE
+    OPEN_FILE,
+
+    /// ( -- ) Display the wordlists of the search order, the first searched first, and
+    /// then the compilation wordlist.
+    /// https://forth-standard.org/standard/search/ORDER
+    ORDER,
+
+    /// ( -- ) Replace the first wordlist in the search order with the given one, as done
+    /// by a vocabulary (e.g. FORTH).
+    /// https://forth-standard.org/standard/search/FORTH
+    ORDER_TOP(usize),
+
+    /// ( x1 x2 -- x1 x2 x1 ) Place a copy of x1 on top of the stack.
+    /// https://forth-standard.org/standard/core/OVER
+    OVER,
+
+    /// ( -- ) Move to another page for output; on a terminal, clear the screen.
+    /// https://forth-standard.org/standard/facility/PAGE
+    PAGE,
+
+    /// Compilation: ( do-sys -- ) End a DO loop, adding ( n -- ) n to the index at
+    /// run-time.
+    /// https://forth-standard.org/standard/core/PlusLOOP
+    PLUS_LOOP,
+
+    /// ( -- ) Remove the first wordlist from the search order.
+    /// https://forth-standard.org/standard/search/PREVIOUS
+    PREVIOUS,
+
+    /// ( -- u ) u is the number of significant digits floats are displayed with.
+    /// https://forth-standard.org/standard/float/PRECISION
+    PRECISION,
+
+    /// ( a-addr -- ) Display the value stored at a-addr.
+    /// https://forth-standard.org/standard/tools/q
+    QUESTION,
+
+    /// Compilation: ( -- do-sys ) Like DO, but at run-time the loop is skipped if the
+    /// index and limit are equal.
+    /// https://forth-standard.org/standard/core/qDO
+    QUESTION_DO,
+
+    /// ( -- fam ) fam is the file access method for reading only.
+    /// https://forth-standard.org/standard/file/RO
+    R_O,
+
+    /// ( -- fam ) fam is the file access method for reading and writing.
+    /// https://forth-standard.org/standard/file/RW
+    R_W,
+
+    /// ( c-addr u1 fileid -- u2 ior ) Read at most u1 characters from the file identified
+    /// by fileid into c-addr. u2 is the number of characters read, which is zero at the
+    /// end of the file.
+    /// https://forth-standard.org/standard/file/READ-FILE
+    READ_FILE,
+
+    /// ( c-addr u1 fileid -- u2 flag ior ) Read the next line, of at most u1 characters,
+    /// from the file identified by fileid into c-addr. u2 is the length of the line, less
+    /// its terminator. flag is false at the end of the file.
+    /// https://forth-standard.org/standard/file/READ-LINE
+    READ_LINE,
+
+    /// Compilation: ( -- ) Compile a call to the definition being compiled.
+    /// https://forth-standard.org/standard/core/RECURSE
+    RECURSE,
+
+    /// ( -- ) Roll back to the state saved by the given marker, as done by executing it.
+    ROLL_BACK(usize),
+
+    /// ( c-addr1 u1 c-addr2 u2 -- ior ) Rename the file named by c-addr1 u1 to c-addr2 u2.
+    /// https://forth-standard.org/standard/file/RENAME-FILE
+    RENAME_FILE,
+
+    /// ( c-addr1 u1 c-addr2 u2 -- ) Set the string c-addr1 u1 as the text to substitute
+    /// for the substitution named by c-addr2 u2.
+    /// https://forth-standard.org/standard/string/REPLACES
+    REPLACES,
+
+    /// ( ud fileid -- ior ) Set the position within the file identified by fileid to ud.
+    /// https://forth-standard.org/standard/file/REPOSITION-FILE
+    REPOSITION_FILE,
+
+    /// ( c-addr u -- n flag1 flag2 ) ( F: r -- ) Store the u most significant digits of r
+    /// at c-addr. n is the decimal exponent, such that r is 0.digits times ten to the
+    /// power n. flag1 is true if r is negative, and flag2 is true if r is valid.
+    /// https://forth-standard.org/standard/float/REPRESENT
+    REPRESENT,
+
+    /// ( i*x "<spaces>name" -- j*x ) Interpret the source file named by name, unless it's
+    /// already been included.
+    /// https://forth-standard.org/standard/file/REQUIRE
+    REQUIRE,
+
+    /// ( i*x c-addr u -- j*x ) Interpret the source file named by c-addr u, unless it's
+    /// already been included.
+    /// https://forth-standard.org/standard/file/REQUIRED
+    REQUIRED,
+
+    /// ( x1 x2 x3 -- x2 x3 x1 ) Rotate the top three stack entries.
+    /// https://forth-standard.org/standard/core/ROT
+    ROT,
+
+    /// ( "ccc<quote>" -- c-addr u ) Parse ccc delimited by a double-quote, translating
+    /// backslash escape sequences, giving the string c-addr u.
+    /// https://forth-standard.org/standard/core/Seq
+    S_BACKSLASH_QUOTE,
+
+    /// ( "ccc<quote>" -- c-addr u ) Parse ccc delimited by a double-quote, giving the
+    /// string c-addr u. The string is transient when interpreted.
+    /// https://forth-standard.org/standard/core/Sq
+    S_QUOTE,
+
+    /// ( n -- d ) Convert the number n to the double-cell number d with the same
+    /// numerical value.
+    /// https://forth-standard.org/standard/core/StoD
+    S_TO_D,
+
+    /// ( n -- ) ( F: -- r ) r is the floating-point equivalent of n.
+    /// https://forth-standard.org/standard/float/StoF
+    S_TO_F,
+
+    /// ( c-addr1 u1 c-addr2 u2 -- c-addr3 u3 flag ) Search the string c-addr1 u1 for the
+    /// string c-addr2 u2. If found, c-addr3 u3 is the remainder of c-addr1 u1 starting
+    /// at the match and flag is true. Otherwise c-addr3 u3 is c-addr1 u1 and flag is false.
+    /// https://forth-standard.org/standard/string/SEARCH
+    SEARCH,
+
+    /// ( c-addr u wid -- 0 | xt 1 | xt -1 ) Find the word named c-addr u in the wordlist
+    /// wid. If found, return its execution token xt and 1 if it's immediate or -1
+    /// otherwise. Otherwise return 0.
+    /// https://forth-standard.org/standard/search/SEARCH-WORDLIST
+    SEARCH_WORDLIST,
+
+    /// Compilation: ( colon-sys -- ) End the definition being compiled, making it
+    /// available by name.
+    /// https://forth-standard.org/standard/core/Semi
+    SEMICOLON,
+
+    /// Compilation: ( quotation-sys colon-sys -- ) End the quotation being compiled, and
+    /// resume the suspended definition, compiling the quotation's execution token
+    /// ( -- xt ) as a literal. If nothing was suspended, just push the execution token.
+    /// https://forth-standard.org/standard/core/SemiBracket
+    SEMICOLON_BRACKET,
+
+    /// ( wid -- ) Make the wordlist wid the compilation wordlist.
+    /// https://forth-standard.org/standard/search/SET-CURRENT
+    SET_CURRENT,
+
+    /// ( widn ... wid1 n -- ) Make the n wordlists the search order, wid1 being searched
+    /// first. If n is -1, use the minimum search order.
+    /// https://forth-standard.org/standard/search/SET-ORDER
+    SET_ORDER,
+
+    /// ( u -- ) Display floats with u significant digits.
+    /// https://forth-standard.org/standard/float/SET-PRECISION
+    SET_PRECISION,
+
+    /// ( -- ) Align the data-space pointer to hold a single-precision float.
+    /// https://forth-standard.org/standard/float/SFALIGN
+    SF_ALIGN,
+
+    /// ( addr -- sf-addr ) sf-addr is the first address at or after addr that may hold a
+    /// single-precision float.
+    /// https://forth-standard.org/standard/float/SFALIGNED
+    SF_ALIGNED,
+
+    /// ( sf-addr -- ) ( F: -- r ) r is the single-precision float stored at sf-addr.
+    /// https://forth-standard.org/standard/float/SFFetch
+    SF_FETCH,
+
+    /// ( sf-addr -- ) ( F: r -- ) Store r at sf-addr, rounded to single precision.
+    /// https://forth-standard.org/standard/float/SFStore
+    SF_STORE,
+
+    /// ( sf-addr1 -- sf-addr2 ) Add the size of a single-precision float to sf-addr1.
+    /// https://forth-standard.org/standard/float/SFLOATPlus
+    SFLOAT_PLUS,
+
+    /// ( n1 -- n2 ) n2 is the size of n1 single-precision floats.
+    /// https://forth-standard.org/standard/float/SFLOATS
+    SFLOATS,
+
+    /// ( n -- ) If n is negative, add a minus sign to the beginning of the pictured
+    /// numeric output string.
+    /// https://forth-standard.org/standard/core/SIGN
+    SIGN,
+
+    /// ( c-addr1 u1 n -- c-addr2 u2 ) Adjust the string c-addr1 u1 by n characters.
+    /// https://forth-standard.org/standard/string/DivSTRING
+    SLASH_STRING,
+
+    /// ( c-addr1 u -- ) Compile-only: append the run-time semantics ( -- c-addr2 u ) of
+    /// the string c-addr1 u to the current definition.
+    /// https://forth-standard.org/standard/string/SLITERAL
+    SLITERAL,
+
+    /// ( -- ) Display one space.
+    /// https://forth-standard.org/standard/core/SPACE
+    SPACE,
+
+    /// ( n -- ) If n is greater than zero, display n spaces.
+    /// https://forth-standard.org/standard/core/SPACES
+    SPACES,
+
+    /// ( x a-addr -- ) Store x at a-addr.
+    /// https://forth-standard.org/standard/core/Store
+    STORE,
+
+    /// ( c-addr1 u1 c-addr2 u2 -- c-addr2 u3 n ) Perform substitutions on the string
+    /// c-addr1 u1, placing the result in the buffer c-addr2 u2. n is the number of
+    /// substitutions made, or negative if the result didn't fit.
+    /// https://forth-standard.org/standard/string/SUBSTITUTE
+    SUBSTITUTE,
+
     /// ( n1 | u1 n2 | u2 -- n3 | u3 ) Subtract n2 | u2 from n1 | u1, giving the difference n3 | u3.
     /// https://forth-standard.org/standard/core/Minus
     SUB,
 
+    /// ( x1 x2 -- x2 x1 ) Exchange the top two stack items.
+    /// https://forth-standard.org/standard/core/SWAP
+    SWAP,
+
+    /// ( i * x -- j * x ) Execute the definition with the given execution token in place
+    /// of the definition being executed, as when calling it and then returning.
+    TAIL_CALL(usize),
+
+    /// Compilation: ( orig -- ) Resolve the preceding IF or ELSE.
+    /// https://forth-standard.org/standard/core/THEN
+    THEN,
+
+    /// ( "<spaces>name" -- xt ) xt is the execution token of name.
+    /// https://forth-standard.org/standard/core/Tick
+    TICK,
+
+    /// Compilation: ( "<spaces>name" -- ) Compile storing ( x -- ) x in name, a local.
+    /// https://forth-standard.org/standard/locals/TO
+    TO,
+
+    /// ( c-addr u -- flag ) ( F: -- r | ) Convert the string c-addr u to a float. If
+    /// it's valid, r is the float and flag is true. Otherwise flag is false.
+    /// https://forth-standard.org/standard/float/toFLOAT
+    TO_FLOAT,
+
+    /// ( c-addr u -- ) Display the character string specified by c-addr and u.
+    /// https://forth-standard.org/standard/core/TYPE
+    TYPE,
+
+    /// ( c-addr1 u1 c-addr2 -- c-addr2 u2 ) Replace each '%' in c-addr1 u1 with two,
+    /// placing the result at c-addr2.
+    /// https://forth-standard.org/standard/string/UNESCAPE
+    UNESCAPE,
+
+    /// ( -- ) ( R: loop-sys -- ) Discard the innermost loop's control parameters, as is
+    /// required before EXIT within a loop.
+    /// https://forth-standard.org/standard/core/UNLOOP
+    UNLOOP,
+
+    /// ( "<spaces>name" -- ) Create a wordlist and define name to replace the first
+    /// wordlist in the search order with it.
+    /// https://forth-standard.org/standard/search/VOCABULARY
+    VOCABULARY,
+
+    /// ( -- wid ) Create an empty wordlist, identified by wid.
+    /// https://forth-standard.org/standard/search/WORDLIST
+    WORDLIST,
+
+    /// ( -- fam ) fam is the file access method for writing only.
+    /// https://forth-standard.org/standard/file/WO
+    W_O,
+
+    /// ( c-addr u fileid -- ior ) Write the string to the file identified by fileid.
+    /// https://forth-standard.org/standard/file/WRITE-FILE
+    WRITE_FILE,
+
+    /// ( c-addr u fileid -- ior ) Write the string, followed by a line terminator, to the
+    /// file identified by fileid.
+    /// https://forth-standard.org/standard/file/WRITE-LINE
+    WRITE_LINE,
+
     /// ( x -- flag ) flag is true if and only if x is equal to zero.
     /// https://forth-standard.org/standard/core/ZeroEqual
     ZERO_EQ,
@@ -108,22 +1019,71 @@ pub fn parse(vm: &mut VM) -> Result<(), CompilerError> {
         match token.symbol {
 
             Symbol::NUMBER => {
-                let parsed_token: i64 = token.token.parse::<i64>().unwrap();
-                vm.data_stack.push(
-                    Data::NUMBER(parsed_token)
-                );
+                let base: u32 = vm.memory.base().unwrap_or(10);
+                let parsed_token: i64 = match i64::from_str_radix(&token.token, base) {
+                    Result::Ok(n) => n,
+                    Result::Err(_) => {
+                        vm.tokens.clear();
+                        return Result::Err(
+                            CompilerError {
+                                msg: format!("number out of range: {}", token.token),
+                            }
+                        );
+                    },
+                };
+                match vm.compiling {
+                    Some(xt) => {
+                        vm.definitions[xt].body.push(Operation::LITERAL(parsed_token));
+                    },
+                    None => {
+                        vm.data_stack.push(
+                            Data::NUMBER(parsed_token)
+                        );
+                    },
+                }
+            },
+
+            Symbol::FLOAT => {
+                let parsed_token: f64 = parse_float(token.token.as_str(), true).unwrap();
+                match vm.compiling {
+                    Some(xt) => {
+                        vm.definitions[xt].body.push(Operation::F_LITERAL(parsed_token));
+                    },
+                    None => {
+                        vm.float_stack.push(parsed_token);
+                    },
+                }
             },
 
+            // While compiling, words are appended to the definition being
+            // compiled, unless they're immediate. Locals take precedence.
             Symbol::WORD => {
-                let word_ops: Vec<Operation> = vm.dictionary.get(token.token.as_str()).unwrap().to_vec();
-                vm.operations.extend(word_ops);
+                if let (Some(xt), Some(local)) = (vm.compiling, vm.find_local(token.token.as_str())) {
+                    vm.definitions[xt].body.push(Operation::LOCAL_FETCH(local));
+                    continue;
+                }
+                let word: usize = vm.find(token.token.as_str()).unwrap();
+                let definition: &Definition = &vm.definitions[word];
+                let immediate: bool = definition.immediate;
+                let word_ops: Vec<Operation> = match definition.inline {
+                    true => definition.body.to_vec(),
+                    false => vec![Operation::CALL(word)],
+                };
+                match vm.compiling {
+                    Some(xt) if !immediate => {
+                        vm.definitions[xt].body.extend(word_ops);
+                    },
+                    _ => {
+                        vm.operations.extend(word_ops);
+                    },
+                }
             },
 
             Symbol::UNDEFINED => {
                 vm.tokens.clear();
                 return Result::Err(
                     CompilerError {
-                        msg: String::from(format!("undefined word: {:?}", token)),
+                        msg: format!("undefined word: {:?}", token),
                     }
                 );
             },
@@ -135,12 +1095,14 @@ pub fn parse(vm: &mut VM) -> Result<(), CompilerError> {
 }
 
 #[cfg(test)]
+#[allow(clippy::field_reassign_with_default)]
 mod tests {
     use super::*;
 
     use std::collections::VecDeque;
 
     use super::super::scanner::Token;
+    use super::super::super::vm::memory::BASE_ADDR;
 
     #[test]
     fn parser_test_numbers() {
@@ -168,16 +1130,55 @@ mod tests {
                 Data::NUMBER(3),
             ]
         );
+
+        // numbers are converted in the current BASE
+        vm.data_stack.clear();
+        assert!(vm.memory.store_cell(BASE_ADDR as i64, 16).is_ok());
+        vm.tokens.push_back(
+            Token {
+                token: String::from("ff"),
+                symbol: Symbol::NUMBER,
+            }
+        );
+        assert!(parse(&mut vm).is_ok());
+        assert_eq!(vm.data_stack, vec![Data::NUMBER(255)]);
+    }
+
+    #[test]
+    fn parser_test_number_out_of_range() {
+        let mut vm: VM = VM::default();
+        vm.tokens.push_back(
+            Token {
+                token: String::from("99999999999999999999"),
+                symbol: Symbol::NUMBER,
+            }
+        );
+        assert_eq!(parse(&mut vm).unwrap_err().msg, "number out of range: 99999999999999999999");
+        assert!(vm.data_stack.is_empty());
     }
 
     #[test]
     fn parser_test_words() {
         let mut vm: VM = VM::default();
-        vm.dictionary.insert(
-            "NOP_INC",
-            vec![
-                Operation::NOP_INC,
-            ],
+        vm.define(
+            Definition {
+                name: String::from("NOP_INC"),
+                body: vec![
+                    Operation::NOP_INC,
+                ],
+                inline: true,
+                ..Definition::default()
+            }
+        );
+        let xt: usize = vm.define(
+            Definition {
+                name: String::from("COLON"),
+                body: vec![
+                    Operation::NOP_INC,
+                    Operation::EXIT,
+                ],
+                ..Definition::default()
+            }
         );
         vm.tokens = VecDeque::from([
             Token {
@@ -188,6 +1189,10 @@ mod tests {
                 token: String::from("NOP_INC"),
                 symbol: Symbol::WORD,
             },
+            Token {
+                token: String::from("COLON"),
+                symbol: Symbol::WORD,
+            },
         ]);
         assert!(parse(&mut vm).is_ok());
         assert_eq!(
@@ -200,6 +1205,62 @@ mod tests {
             vm.operations,
             vec![
                 Operation::NOP_INC,
+                Operation::CALL(xt),
+            ]
+        );
+    }
+
+    #[test]
+    fn parser_test_compiling() {
+        let mut vm: VM = VM::default();
+        vm.define(
+            Definition {
+                name: String::from("NOP_INC"),
+                body: vec![Operation::NOP_INC],
+                inline: true,
+                ..Definition::default()
+            }
+        );
+        vm.define(
+            Definition {
+                name: String::from("NOP"),
+                body: vec![Operation::NOP],
+                immediate: true,
+                inline: true,
+                ..Definition::default()
+            }
+        );
+        vm.definitions.push(Definition::default());
+        vm.compiling = Some(2);
+        vm.tokens = VecDeque::from([
+            Token {
+                token: String::from("1"),
+                symbol: Symbol::NUMBER,
+            },
+            Token {
+                token: String::from("NOP_INC"),
+                symbol: Symbol::WORD,
+            },
+            Token {
+                token: String::from("NOP"),
+                symbol: Symbol::WORD,
+            },
+        ]);
+        assert!(parse(&mut vm).is_ok());
+
+        // numbers and words are compiled, but immediate words are executed
+        assert!(vm.data_stack.is_empty());
+        assert_eq!(
+            vm.definitions[2].body,
+            vec![
+                Operation::LITERAL(1),
+                Operation::NOP_INC,
+            ]
+        );
+        assert_eq!(
+            vm.operations,
+            vec![
+                Operation::NOP,
             ]
         );
     }
@@ -1,5 +1,6 @@
 //! Scanner
 
+use std::cmp;
 use std::vec::Vec;
 
 use super::super::vm::VM;
@@ -9,16 +10,20 @@ use super::CompilerError;
 /// Forth is about words and numbers.
 ///
 /// Words come in two flavors:
-///     - Defined words, which are words kept in the VM 'dictionary'.
-///     - Undefined words, which are words missing from the VM dictionary.
+///     - Defined words, which are words found in the VM 'dictionary' by following
+///       its search order (or locals of the definition being compiled).
+///     - Undefined words, which are words missing from the search order.
 ///
-/// Undefined words that match the number pattern are considered to be numbers.
-/// Otherwise, they're considered to be undefined (a symbol neither defined in
-/// the dictionary nor a number).
+/// Undefined words made up of digits in the current BASE (e.g. "FF" while BASE
+/// is hexadecimal) are considered to be numbers.
+/// Those that match the float pattern (e.g. "1.5E0") while BASE is decimal are
+/// considered to be floats. Otherwise, they're considered to be undefined (a
+/// symbol neither defined in the dictionary nor a number).
 #[derive(PartialEq, Debug)]
 pub enum Symbol {
     WORD,
     NUMBER,
+    FLOAT,
     UNDEFINED,
 }
 
@@ -33,40 +38,46 @@ pub struct Token {
     pub symbol: Symbol,
 }
 
-/// Given the dictionary, are the given bytes a word or number?
+/// Given the search order, are the given bytes a word or number?
 fn token_from_bytes(word_or_number: &mut Vec<u8>, vm: &VM) -> Token {
     let name: String = String::from_utf8(word_or_number.to_vec()).unwrap();
     word_or_number.clear();
-    for char in name.as_bytes().iter() {
-        // If the number contains not-numbers, then it's probably a word
-        if !char.is_ascii_digit() {
-            let token: String = String::from(name);
-            match vm.dictionary.contains_key(token.as_str()) {
-                true => {
-                    return Token {
-                        token: token,
-                        symbol: Symbol::WORD,
-                    };
-                },
-                false => {
-                    return Token {
-                        token: token,
-                        symbol: Symbol::UNDEFINED,
-                    };
-                },
-            }
-        }
+
+    // Words are looked up first, so they aren't mistaken for numbers in bases
+    // above 10 (e.g. a word named ADD while BASE is hexadecimal)
+    let local: bool = vm.compiling.is_some() && vm.find_local(name.as_str()).is_some();
+    if local || vm.find(name.as_str()).is_some() {
+        return Token {
+            token: name,
+            symbol: Symbol::WORD,
+        };
     }
-    return Token {
-        token: String::from(name),
-        symbol: Symbol::NUMBER,
+    let base: u32 = vm.memory.base().unwrap_or(10);
+    if name.chars().all(|char| char.is_digit(base)) {
+        return Token {
+            token: name,
+            symbol: Symbol::NUMBER,
+        };
+    }
+    if base == 10 && parse_float(name.as_str(), true).is_some() {
+        return Token {
+            token: name,
+            symbol: Symbol::FLOAT,
+        };
     }
+    return Token {
+        token: name,
+        symbol: Symbol::UNDEFINED,
+    };
 }
 
 /// Scan the 'parse area' for tokens (see Token defined above). Only ASCII
 /// characters are permitted in the parse area. Whitespace is required to be
 /// present at the end of the parse area, but is otherwise ignored. Scanning
 /// stops at the first word, whether defined or undefined.
+///
+/// The given string replaces the VM's input buffer. Use scan_remaining() to
+/// continue scanning after the first word.
 pub fn scan(string: &str, vm: &mut VM) -> Result<(), CompilerError> {
 
     // For simplicity, assume the parse area is all ASCII characters
@@ -78,10 +89,36 @@ pub fn scan(string: &str, vm: &mut VM) -> Result<(), CompilerError> {
         )
     }
 
+    // The parse area must always end with a new line (or any non-graphic character).
+    if let Some(char) = string.as_bytes().last() {
+        if char.is_ascii_graphic() {
+            return Result::Err(
+                CompilerError {
+                    msg: String::from("parse space doesn't end with whitespace"),
+                }
+            )
+        }
+    }
+
+    vm.input_buffer = String::from(string);
+    vm.input_offset = 0;
+    return scan_remaining(vm);
+
+}
+
+/// Continue scanning the parse area from where the previous scan stopped.
+///
+/// Scanning stops at each word so that the word may be executed before the
+/// rest of the input buffer is scanned. Some words (e.g. S\") consume part of
+/// the parse area themselves.
+pub fn scan_remaining(vm: &mut VM) -> Result<(), CompilerError> {
+
     let mut word_or_number: Vec<u8> = Vec::new();
 
     // Search for numbers and words, stopping on the first defined "word".
-    for char in string.as_bytes().iter() {
+    while vm.input_offset < vm.input_buffer.len() {
+        let char: u8 = vm.input_buffer.as_bytes()[vm.input_offset];
+        vm.input_offset += 1;
 
         // Ignore characters that can't be displayed (whitespace, control chars, etc)
         if !char.is_ascii_graphic() {
@@ -94,14 +131,14 @@ pub fn scan(string: &str, vm: &mut VM) -> Result<(), CompilerError> {
                     vm.tokens.push_back(token);
                     continue;
                 },
-                Symbol::WORD | Symbol::UNDEFINED => {
+                Symbol::FLOAT | Symbol::WORD | Symbol::UNDEFINED => {
                     vm.tokens.push_back(token);
                     return Result::Ok(());
                 },
             }
         }
 
-        word_or_number.push(*char);
+        word_or_number.push(char);
     }
 
     // The parse area must always end with a new line (or any non-graphic character).
@@ -117,12 +154,195 @@ pub fn scan(string: &str, vm: &mut VM) -> Result<(), CompilerError> {
 
 }
 
+/// Convert text representing a floating-point number to the number. Float
+/// literals must have an exponent marked by "E" (e.g. "1.5E0" or "1E"), so
+/// they aren't mistaken for integers:
+///
+/// ```text
+/// [+|-] digits [. [digits]] E [+|-] [digits]
+/// ```
+///
+/// https://forth-standard.org/standard/float#float:conv
+///
+/// Otherwise (e.g. for >FLOAT), the significand may begin with "." and the
+/// exponent may be marked by "D" or a sign, or left out entirely.
+///
+/// https://forth-standard.org/standard/float/toFLOAT
+pub fn parse_float(text: &str, literal: bool) -> Option<f64> {
+    let bytes: &[u8] = text.as_bytes();
+    let digits = |start: usize| -> usize {
+        return start + bytes[start..].iter().take_while(|char| char.is_ascii_digit()).count();
+    };
+    let or_zero = |digits: &str| -> String {
+        return match digits.is_empty() {
+            true => String::from("0"),
+            false => String::from(digits),
+        };
+    };
+
+    let mut offset: usize = 0;
+    let negative: bool = bytes.first() == Some(&b'-');
+    if matches!(bytes.first(), Some(b'+') | Some(b'-')) {
+        offset += 1;
+    }
+    let end: usize = digits(offset);
+    let integer: &str = &text[offset..end];
+    offset = end;
+    let mut fraction: &str = "";
+    if bytes.get(offset) == Some(&b'.') {
+        let end: usize = digits(offset + 1);
+        fraction = &text[offset + 1..end];
+        offset = end;
+    }
+    if integer.is_empty() && (literal || fraction.is_empty()) {
+        return None;
+    }
+
+    let marked: bool = match bytes.get(offset) {
+        Some(b'E') | Some(b'e') => true,
+        Some(b'D') | Some(b'd') => !literal,
+        _ => false,
+    };
+    if marked {
+        offset += 1;
+    } else if literal {
+        return None;
+    }
+    let exponent_negative: bool = bytes.get(offset) == Some(&b'-');
+    if matches!(bytes.get(offset), Some(b'+') | Some(b'-')) {
+        offset += 1;
+    }
+    let end: usize = digits(offset);
+    let exponent: &str = &text[offset..end];
+    if end != bytes.len() {
+        return None;
+    }
+
+    let number: String = format!(
+        "{}{}.{}e{}{}",
+        if negative { "-" } else { "" },
+        or_zero(integer),
+        or_zero(fraction),
+        if exponent_negative { "-" } else { "" },
+        or_zero(exponent),
+    );
+    return number.parse::<f64>().ok();
+}
+
+/// Parse the next name from the parse area, skipping leading whitespace
+/// ("PARSE-NAME"). The name is empty if the parse area is exhausted.
+pub fn parse_name(vm: &mut VM) -> String {
+    let bytes: &[u8] = vm.input_buffer.as_bytes();
+    while vm.input_offset < bytes.len() && !bytes[vm.input_offset].is_ascii_graphic() {
+        vm.input_offset += 1;
+    }
+    let start: usize = vm.input_offset;
+    while vm.input_offset < bytes.len() && bytes[vm.input_offset].is_ascii_graphic() {
+        vm.input_offset += 1;
+    }
+    let name: String = String::from(&vm.input_buffer[start..vm.input_offset]);
+
+    // The delimiter following the name is consumed too
+    if vm.input_offset < bytes.len() {
+        vm.input_offset += 1;
+    }
+    return name;
+}
+
+/// Parse text from the parse area up to the given delimiter ("PARSE"). The
+/// delimiter is consumed, but not included in the returned text. If the
+/// delimiter is missing, the rest of the parse area is returned.
+pub fn parse_delimited(vm: &mut VM, delimiter: u8) -> String {
+    let bytes: &[u8] = vm.input_buffer.as_bytes();
+    let start: usize = cmp::min(vm.input_offset, bytes.len());
+    let end: usize = match bytes[start..].iter().position(|char| *char == delimiter) {
+        Some(length) => start + length,
+        None => bytes.len(),
+    };
+    let text: String = String::from(&vm.input_buffer[start..end]);
+    vm.input_offset = cmp::min(end + 1, bytes.len());
+    return text;
+}
+
+/// Parse a string terminated by a double-quote, translating the escape
+/// sequences of S\" as they're encountered.
+///
+/// https://forth-standard.org/standard/core/Seq
+pub fn parse_escaped(vm: &mut VM) -> Result<Vec<u8>, CompilerError> {
+    let bytes: Vec<u8> = vm.input_buffer.as_bytes().to_vec();
+    let mut string: Vec<u8> = Vec::new();
+
+    while vm.input_offset < bytes.len() {
+        let char: u8 = bytes[vm.input_offset];
+        vm.input_offset += 1;
+
+        if char == b'"' {
+            return Result::Ok(string);
+        }
+        if char != b'\\' {
+            string.push(char);
+            continue;
+        }
+
+        let escape: Option<&u8> = bytes.get(vm.input_offset);
+        vm.input_offset = cmp::min(vm.input_offset + 1, bytes.len());
+        match escape {
+            Some(b'a') => string.push(7),
+            Some(b'b') => string.push(8),
+            Some(b'e') => string.push(27),
+            Some(b'f') => string.push(12),
+            Some(b'l') => string.push(10),
+            Some(b'm') => string.extend([13, 10]),
+            Some(b'n') => string.push(10),
+            Some(b'q') | Some(b'"') => string.push(b'"'),
+            Some(b'r') => string.push(13),
+            Some(b't') => string.push(9),
+            Some(b'v') => string.push(11),
+            Some(b'z') => string.push(0),
+            Some(b'\\') => string.push(b'\\'),
+            Some(b'x') => {
+                let digits: Option<&[u8]> = bytes.get(vm.input_offset..vm.input_offset + 2);
+                let value: Option<u8> = digits
+                    .and_then(|digits| std::str::from_utf8(digits).ok())
+                    .and_then(|digits| u8::from_str_radix(digits, 16).ok());
+                match value {
+                    Some(value) => string.push(value),
+                    None => {
+                        return Result::Err(
+                            CompilerError {
+                                msg: String::from("\\x requires two hexadecimal digits"),
+                            }
+                        );
+                    },
+                }
+                vm.input_offset += 2;
+            },
+            Some(other) => {
+                return Result::Err(
+                    CompilerError {
+                        msg: format!("unknown escape sequence: \\{}", *other as char),
+                    }
+                );
+            },
+            None => break,
+        }
+    }
+
+    return Result::Err(
+        CompilerError {
+            msg: String::from("unterminated string"),
+        }
+    );
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     use std::collections::VecDeque;
 
+    use super::super::super::vm::memory::BASE_ADDR;
+
     /// Test:  Assert numbers are recognized
     #[test]
     fn scan_test_numbers() {
@@ -149,6 +369,17 @@ mod tests {
                 },
             ]),
         );
+        vm.tokens.clear();
+
+        // digits are those of the current BASE
+        assert!(vm.memory.store_cell(BASE_ADDR as i64, 16).is_ok());
+        assert!(scan("ff 1A G\n", &mut vm).is_ok());
+        let symbols: Vec<Symbol> = vm.tokens.drain(..).map(|token| token.symbol).collect();
+        assert_eq!(symbols, vec![Symbol::NUMBER, Symbol::NUMBER, Symbol::UNDEFINED]);
+        assert!(vm.memory.store_cell(BASE_ADDR as i64, 2).is_ok());
+        assert!(scan("101 2\n", &mut vm).is_ok());
+        let symbols: Vec<Symbol> = vm.tokens.drain(..).map(|token| token.symbol).collect();
+        assert_eq!(symbols, vec![Symbol::NUMBER, Symbol::UNDEFINED]);
 
     }
 
@@ -269,7 +500,7 @@ mod tests {
         vm.tokens.clear();
 
         // define the word "example"
-        vm.dictionary.insert("example", vec![]);
+        vm.dictionary.insert(String::from("example"), 0);
 
         // scan reports that "example" is a defined word
         assert!(scan("example\n", &mut vm).is_ok());
@@ -286,4 +517,116 @@ mod tests {
 
     }
 
+    /// Test:  Assert float literals are recognized while BASE is decimal
+    #[test]
+    fn scan_test_floats() {
+
+        // test setup
+        let mut vm: VM = VM::default();
+
+        for float in ["1.5e0", "1E", "-1.E-5", "+25e+1"] {
+            assert!(scan(format!("{}\n", float).as_str(), &mut vm).is_ok());
+            assert_eq!(vm.tokens.pop_front().unwrap().symbol, Symbol::FLOAT);
+            assert!(vm.tokens.is_empty());
+        }
+        for undefined in ["1.5", ".5E0", "E", "1E5.", "1D0", "1e--1"] {
+            assert!(scan(format!("{}\n", undefined).as_str(), &mut vm).is_ok());
+            assert_eq!(vm.tokens.pop_front().unwrap().symbol, Symbol::UNDEFINED);
+        }
+
+        // floats are only recognized in decimal; elsewhere 1E is a number
+        assert!(vm.memory.store_cell(BASE_ADDR as i64, 16).is_ok());
+        assert!(scan("1E 1.5E0\n", &mut vm).is_ok());
+        assert_eq!(vm.tokens.pop_front().unwrap().symbol, Symbol::NUMBER);
+        assert_eq!(vm.tokens.pop_front().unwrap().symbol, Symbol::UNDEFINED);
+
+    }
+
+    /// Test:  Assert the text of floats is converted, with or without an exponent
+    #[test]
+    fn scan_test_parse_float() {
+        assert_eq!(parse_float("1.5e0", true), Some(1.5));
+        assert_eq!(parse_float("1E", true), Some(1.0));
+        assert_eq!(parse_float("-25.E-1", true), Some(-2.5));
+        assert_eq!(parse_float("1.5", true), None);
+        assert_eq!(parse_float("1.5", false), Some(1.5));
+        assert_eq!(parse_float(".5", false), Some(0.5));
+        assert_eq!(parse_float("1d2", false), Some(100.0));
+        assert_eq!(parse_float("1+2", false), Some(100.0));
+        assert_eq!(parse_float("1-2", false), Some(0.01));
+        assert_eq!(parse_float(".", false), None);
+        assert_eq!(parse_float("", false), None);
+        assert_eq!(parse_float("1.5x", false), None);
+    }
+
+    /// Test:  Assert scanning resumes after the first word
+    #[test]
+    fn scan_test_remaining() {
+
+        // test setup
+        let mut vm: VM = VM::default();
+        vm.dictionary.insert(String::from("example"), 0);
+
+        assert!(scan("1 example 2 undefined_word 3\n", &mut vm).is_ok());
+        assert_eq!(vm.tokens.len(), 2);
+        assert_eq!(vm.tokens.pop_back().unwrap().symbol, Symbol::WORD);
+        vm.tokens.clear();
+
+        assert!(scan_remaining(&mut vm).is_ok());
+        assert_eq!(vm.tokens.len(), 2);
+        assert_eq!(vm.tokens.pop_back().unwrap().symbol, Symbol::UNDEFINED);
+        vm.tokens.clear();
+
+        assert!(scan_remaining(&mut vm).is_ok());
+        assert_eq!(
+            vm.tokens,
+            VecDeque::from([
+                Token {
+                    token: String::from("3"),
+                    symbol: Symbol::NUMBER,
+                },
+            ]),
+        );
+        vm.tokens.clear();
+
+        // parse area is exhausted
+        assert!(scan_remaining(&mut vm).is_ok());
+        assert!(vm.tokens.is_empty());
+
+        // the input buffer must end with whitespace
+        assert!(scan("1 2 example", &mut vm).is_err());
+
+    }
+
+    /// Replace the input buffer without scanning it
+    fn set_parse_area(vm: &mut VM, text: &str) {
+        vm.input_buffer = String::from(text);
+        vm.input_offset = 0;
+    }
+
+    /// Test:  Assert words can parse the remainder of the parse area
+    #[test]
+    fn scan_test_parsing() {
+
+        // test setup
+        let mut vm: VM = VM::default();
+
+        set_parse_area(&mut vm, "  name1 name2 text\" tail\n");
+        assert_eq!(parse_name(&mut vm), "name1");
+        assert_eq!(parse_name(&mut vm), "name2");
+        assert_eq!(parse_delimited(&mut vm, b'"'), "text");
+        assert_eq!(parse_delimited(&mut vm, b'"'), " tail\n");
+        assert_eq!(parse_name(&mut vm), "");
+        assert_eq!(parse_delimited(&mut vm, b'"'), "");
+
+        set_parse_area(&mut vm, "a\\nb\\\"\\x7e\" rest\n");
+        assert_eq!(parse_escaped(&mut vm).unwrap(), b"a\nb\"~");
+        assert_eq!(parse_name(&mut vm), "rest");
+
+        set_parse_area(&mut vm, "trailing\\");
+        assert!(parse_escaped(&mut vm).is_err());
+        assert_eq!(parse_name(&mut vm), "");
+
+    }
+
 }
@@ -0,0 +1,501 @@
+//! C API
+//!
+//! The library is also built as a C dynamic library (cdylib) exporting the
+//! functions below, which are declared for C in include/oxforth.h. The header
+//! is generated from the declarations at the end of this file by header():
+//!
+//! ```text
+//! cargo run --example c_header > include/oxforth.h
+//! ```
+//!
+//! A Forth
+//! system is created by oxforth_create() and handed back by every other call
+//! as an opaque pointer, until it's destroyed by oxforth_destroy().
+//!
+//! Functions that can fail return OXFORTH_ERROR, and describe what went wrong
+//! in a message fetched by oxforth_last_error(). The message belongs to the
+//! calling thread, and lasts until the next call on that thread that fails.
+
+use std::cell::RefCell;
+use std::ffi::{c_char, c_int, c_void, CStr, CString};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::ptr::null;
+use std::slice;
+use std::str;
+
+use super::forth::Forth;
+use super::vm::{Outcome, VirtualMachineError, VM};
+
+/// The call succeeded
+pub const OXFORTH_OK: c_int = 0;
+
+/// The program asked to stop (e.g. via BYE)
+pub const OXFORTH_HALTED: c_int = 1;
+
+/// The call failed, as described by oxforth_last_error()
+pub const OXFORTH_ERROR: c_int = -1;
+
+/// Function run when a native word registered via the C API is executed. It's
+/// given the Forth system executing it, and the context it was registered
+/// with, and returns OXFORTH_OK unless it failed. While it runs, the system
+/// may only be used through the pointer it's given, not one kept from
+/// elsewhere (e.g. in the context), which the executing call still holds.
+pub type NativeCallback = extern "C" fn(forth: *mut Forth, context: *mut c_void) -> c_int;
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = RefCell::new(None);
+}
+
+/// Record why a call failed, returning OXFORTH_ERROR
+fn fail(msg: &str) -> c_int {
+    let msg: CString = CString::new(msg.replace('\0', "")).unwrap();
+    LAST_ERROR.with(|last_error| *last_error.borrow_mut() = Some(msg));
+    return OXFORTH_ERROR;
+}
+
+/// Create a Forth system, with the core words defined
+#[no_mangle]
+pub extern "C" fn oxforth_create() -> *mut Forth {
+    return Box::into_raw(Box::new(Forth::new()));
+}
+
+/// Destroy a Forth system
+///
+/// # Safety
+///
+/// `forth` must be NULL, or have been created by oxforth_create() and not yet
+/// destroyed.
+#[no_mangle]
+pub unsafe extern "C" fn oxforth_destroy(forth: *mut Forth) {
+    if !forth.is_null() {
+        drop(Box::from_raw(forth));
+    }
+}
+
+/// Interpret `length` bytes of Forth source. Returns OXFORTH_HALTED if the
+/// program halted, storing its exit code (0 if none was given) in `code`
+/// unless it's NULL.
+///
+/// # Safety
+///
+/// `forth` must be a live Forth system, and `source` must point to `length`
+/// readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn oxforth_eval(forth: *mut Forth, source: *const c_char, length: usize, code: *mut c_int) -> c_int {
+    if source.is_null() {
+        return fail("source is NULL");
+    }
+    let source: &[u8] = slice::from_raw_parts(source as *const u8, length);
+    let source: &str = match str::from_utf8(source) {
+        Result::Ok(source) => source,
+        Result::Err(_) => return fail("source isn't valid UTF-8"),
+    };
+    match catch_unwind(AssertUnwindSafe(|| (*forth).eval(source))) {
+        Result::Ok(Result::Ok(Outcome::HALTED(halted))) => {
+            if !code.is_null() {
+                *code = halted.unwrap_or(0);
+            }
+            return OXFORTH_HALTED;
+        },
+        Result::Ok(Result::Ok(_)) => {
+            return OXFORTH_OK;
+        },
+        Result::Ok(Result::Err(err)) => {
+            return fail(&err.msg);
+        },
+        Result::Err(_) => {
+            (*forth).vm.reset();
+            return fail("panicked while interpreting source");
+        },
+    }
+}
+
+/// Push a cell onto the data stack
+///
+/// # Safety
+///
+/// `forth` must be a live Forth system.
+#[no_mangle]
+pub unsafe extern "C" fn oxforth_push(forth: *mut Forth, n: i64) {
+    (*forth).push(n);
+}
+
+/// Pop a cell off the data stack into `n`
+///
+/// # Safety
+///
+/// `forth` must be a live Forth system, and `n` must be writable.
+#[no_mangle]
+pub unsafe extern "C" fn oxforth_pop(forth: *mut Forth, n: *mut i64) -> c_int {
+    match (*forth).pop::<i64>() {
+        Result::Ok(cell) => {
+            *n = cell;
+            return OXFORTH_OK;
+        },
+        Result::Err(err) => {
+            return fail(&err.msg);
+        },
+    }
+}
+
+/// Number of cells on the data stack
+///
+/// # Safety
+///
+/// `forth` must be a live Forth system.
+#[no_mangle]
+pub unsafe extern "C" fn oxforth_depth(forth: *const Forth) -> usize {
+    return (*forth).vm.data_stack.len();
+}
+
+/// Define a word named `name` which calls `callback` with `context`. It takes
+/// `inputs` cells off the data stack and leaves `outputs` cells in their place,
+/// which is checked each time it's executed.
+///
+/// # Safety
+///
+/// `forth` must be a live Forth system, and `name` a NUL-terminated string.
+/// `context` must remain valid for as long as the word may be executed, and
+/// `callback` may only use the Forth system it's given.
+#[no_mangle]
+pub unsafe extern "C" fn oxforth_define_native(
+    forth: *mut Forth,
+    name: *const c_char,
+    inputs: usize,
+    outputs: usize,
+    callback: NativeCallback,
+    context: *mut c_void,
+) -> c_int {
+    if name.is_null() {
+        return fail("name is NULL");
+    }
+    let name: String = match CStr::from_ptr(name).to_str() {
+        Result::Ok(name) => name.to_string(),
+        Result::Err(_) => return fail("name isn't valid UTF-8"),
+    };
+    let word: String = name.clone();
+    (*forth).vm.define_native(&name, inputs, outputs, move |vm: &mut VM| {
+        // The callback reaches the system only through this pointer, derived
+        // from the VM executing the word rather than the caller's pointer.
+        // Forth is a transparent wrapper of its VM.
+        let vm: *mut VM = vm;
+        let status: c_int = callback(vm.cast::<Forth>(), context);
+        if status != OXFORTH_OK {
+            return Result::Err(
+                VirtualMachineError {
+                    msg: format!("{} failed: {}", word, status),
+                    ..VirtualMachineError::default()
+                }
+            );
+        }
+        return Result::Ok(());
+    });
+    return OXFORTH_OK;
+}
+
+/// Why the last call on this thread that failed did so, or NULL if none has.
+/// The message lasts until the next call on this thread that fails.
+#[no_mangle]
+pub extern "C" fn oxforth_last_error() -> *const c_char {
+    return LAST_ERROR.with(|last_error| {
+        return match last_error.borrow().as_ref() {
+            Some(msg) => msg.as_ptr(),
+            None => null(),
+        };
+    });
+}
+
+/// Spelling of a Rust type in C, as declared by the header
+pub trait CType {
+    const C: &'static str;
+}
+
+macro_rules! c_type {
+    ($($rust:ty => $c:literal,)*) => {
+        $(
+            impl CType for $rust {
+                const C: &'static str = $c;
+            }
+        )*
+    };
+}
+
+c_type! {
+    () => "void",
+    c_int => "int",
+    i64 => "int64_t",
+    usize => "size_t",
+    *mut c_int => "int *",
+    *mut i64 => "int64_t *",
+    *mut c_void => "void *",
+    *const c_char => "const char *",
+    *mut Forth => "oxforth *",
+    *const Forth => "const oxforth *",
+    NativeCallback => "oxforth_native",
+}
+
+/// C declaration of a name with the given C type (e.g. "const char *source")
+fn c_declaration(c_type: &str, name: &str) -> String {
+    if c_type.ends_with('*') {
+        return format!("{}{}", c_type, name);
+    }
+    return format!("{} {}", c_type, name);
+}
+
+/// Declaration of a function type in C, given its name and the names of its
+/// parameters
+pub trait CSignature {
+    fn declare(name: &str, parameters: &[&str]) -> String;
+}
+
+macro_rules! c_signature {
+    ($($parameter:ident),*) => {
+        impl<R: CType, $($parameter: CType),*> CSignature for extern "C" fn($($parameter),*) -> R {
+            fn declare(name: &str, parameters: &[&str]) -> String {
+                return c_function(name, parameters, &[$($parameter::C),*], R::C);
+            }
+        }
+        impl<R: CType, $($parameter: CType),*> CSignature for unsafe extern "C" fn($($parameter),*) -> R {
+            fn declare(name: &str, parameters: &[&str]) -> String {
+                return c_function(name, parameters, &[$($parameter::C),*], R::C);
+            }
+        }
+    };
+}
+
+c_signature!();
+c_signature!(A);
+c_signature!(A, B);
+c_signature!(A, B, C);
+c_signature!(A, B, C, D);
+c_signature!(A, B, C, D, E);
+c_signature!(A, B, C, D, E, F);
+
+fn c_function(name: &str, parameters: &[&str], types: &[&str], result: &str) -> String {
+    assert_eq!(parameters.len(), types.len(), "{} has {} parameters", name, types.len());
+    let parameters: Vec<String> = types.iter().zip(parameters)
+        .map(|(c_type, parameter)| c_declaration(c_type, parameter))
+        .collect();
+    let parameters: String = if parameters.is_empty() { String::from("void") } else { parameters.join(", ") };
+    return c_declaration(result, &format!("{}({})", name, parameters));
+}
+
+/// Type left to be inferred, one per parameter
+macro_rules! inferred {
+    ($parameter:ident) => { _ };
+}
+
+/// C prototype of an exported function, given the names of its parameters.
+/// The types come from the function itself.
+macro_rules! c_prototype {
+    ($function:ident($($parameter:ident),*)) => {{
+        fn declare<F: CSignature>(_: F, name: &str, parameters: &[&str]) -> String {
+            return c_wrap(&format!("{};", F::declare(name, parameters)));
+        }
+        declare(
+            $function as unsafe extern "C" fn($(inferred!($parameter)),*) -> _,
+            stringify!($function),
+            &[$(stringify!($parameter)),*],
+        )
+    }};
+}
+
+/// C declaration wrapped to fit within 100 columns, with parameters that
+/// don't fit lined up with the first
+fn c_wrap(declaration: &str) -> String {
+    let indent: String = " ".repeat(declaration.find('(').map_or(0, |open| open + 1));
+    let mut lines: Vec<String> = Vec::new();
+    for parameter in declaration.split_inclusive(", ") {
+        match lines.last_mut() {
+            Some(line) if line.len() + parameter.trim_end().len() <= 100 => line.push_str(parameter),
+            Some(line) => {
+                line.truncate(line.trim_end().len());
+                lines.push(format!("{}{}", indent, parameter));
+            },
+            None => lines.push(String::from(parameter)),
+        }
+    }
+    return lines.join("\n");
+}
+
+/// C comment of the given text, wrapped to fit within 80 columns
+fn c_comment(text: &str) -> String {
+    let mut lines: Vec<String> = vec![String::new()];
+    for word in text.split_whitespace() {
+        let line: &mut String = lines.last_mut().unwrap();
+        if !line.is_empty() && line.len() + 1 + word.len() > 75 {
+            lines.push(String::from(word));
+        } else {
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(word);
+        }
+    }
+    return format!("/* {} */\n", lines.join("\n * "));
+}
+
+/// Text of the C header declaring the C API (include/oxforth.h)
+pub fn header() -> String {
+    let declarations: Vec<(&str, String)> = vec![
+        ("The call succeeded", format!("#define OXFORTH_OK {}", OXFORTH_OK)),
+        ("The program asked to stop (e.g. via BYE)", format!("#define OXFORTH_HALTED {}", OXFORTH_HALTED)),
+        ("The call failed, as described by oxforth_last_error()", format!("#define OXFORTH_ERROR ({})", OXFORTH_ERROR)),
+        ("A Forth system", String::from("typedef struct oxforth oxforth;")),
+        (
+            "Function run when a native word is executed. It's given the Forth system executing it, and the \
+             context it was registered with, and returns OXFORTH_OK unless it failed. While it runs, the \
+             system may only be used through the pointer it's given, not one kept from elsewhere (e.g. in the \
+             context).",
+            format!("typedef {};", NativeCallback::declare("(*oxforth_native)", &["forth", "context"])),
+        ),
+        ("Create a Forth system, with the core words defined", c_prototype!(oxforth_create())),
+        ("Destroy a Forth system. Does nothing if forth is NULL.", c_prototype!(oxforth_destroy(forth))),
+        (
+            "Interpret length bytes of Forth source. Returns OXFORTH_HALTED if the program halted, storing \
+             its exit code (0 if none was given) in code unless it's NULL.",
+            c_prototype!(oxforth_eval(forth, source, length, code)),
+        ),
+        ("Push a cell onto the data stack", c_prototype!(oxforth_push(forth, n))),
+        ("Pop a cell off the data stack into n", c_prototype!(oxforth_pop(forth, n))),
+        ("Number of cells on the data stack", c_prototype!(oxforth_depth(forth))),
+        (
+            "Define a word named name which calls callback with context. It takes inputs cells off the data \
+             stack and leaves outputs cells in their place, which is checked each time it's executed. \
+             context must remain valid for as long as the word may be executed.",
+            c_prototype!(oxforth_define_native(forth, name, inputs, outputs, callback, context)),
+        ),
+        (
+            "Why the last call on this thread that failed did so, or NULL if none has. The message lasts \
+             until the next call on this thread that fails.",
+            c_prototype!(oxforth_last_error()),
+        ),
+    ];
+
+    let mut header: String = String::from(HEADER_PREAMBLE);
+    for (comment, declaration) in declarations {
+        header.push('\n');
+        header.push_str(&c_comment(comment));
+        header.push_str(&declaration);
+        header.push('\n');
+    }
+    header.push_str(HEADER_POSTAMBLE);
+    return header;
+}
+
+const HEADER_PREAMBLE: &str = "\
+/*  OxForth - C API
+ *
+ *  Declarations of the functions exported by the OxForth C dynamic library
+ *  (liboxforth), as defined in src/ffi.rs, from which this file is generated:
+ *
+ *      cargo run --example c_header > include/oxforth.h
+ *
+ *  A Forth system is created by oxforth_create() and passed to every other
+ *  call until it's destroyed by oxforth_destroy(). Functions that can fail
+ *  return OXFORTH_ERROR, and describe what went wrong in a message fetched by
+ *  oxforth_last_error().
+ */
+
+#ifndef OXFORTH_H
+#define OXFORTH_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern \"C\" {
+#endif
+";
+
+const HEADER_POSTAMBLE: &str = "
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* OXFORTH_H */
+";
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    extern "C" fn double(forth: *mut Forth, context: *mut c_void) -> c_int {
+        let mut n: i64 = 0;
+        unsafe {
+            *(context as *mut i64) += 1;
+            if oxforth_pop(forth, &mut n) != OXFORTH_OK {
+                return 7;
+            }
+            oxforth_push(forth, n * 2);
+        }
+        return OXFORTH_OK;
+    }
+
+    fn last_error() -> String {
+        return unsafe { CStr::from_ptr(oxforth_last_error()) }.to_str().unwrap().to_string();
+    }
+
+    #[test]
+    fn ffi_test_eval() {
+        unsafe {
+            let forth: *mut Forth = oxforth_create();
+            let mut n: i64 = 0;
+            let mut code: c_int = -1;
+            let source: &str = "2 3 +\n: SQUARE DUP * ; 4 SQUARE";
+            assert_eq!(oxforth_eval(forth, source.as_ptr() as *const c_char, source.len(), &mut code), OXFORTH_OK);
+            assert_eq!(oxforth_depth(forth), 2);
+            assert_eq!(oxforth_pop(forth, &mut n), OXFORTH_OK);
+            assert_eq!(n, 16);
+            oxforth_push(forth, 9);
+            assert_eq!(oxforth_eval(forth, "+ (BYE)".as_ptr() as *const c_char, 7, &mut code), OXFORTH_HALTED);
+            assert_eq!(code, 14);
+
+            // case:  errors
+            assert_eq!(oxforth_pop(forth, &mut n), OXFORTH_ERROR);
+            assert_eq!(last_error(), "stack underflow");
+            assert_eq!(oxforth_eval(forth, b"\xff".as_ptr() as *const c_char, 1, &mut code), OXFORTH_ERROR);
+            assert_eq!(last_error(), "source isn't valid UTF-8");
+            assert_eq!(oxforth_eval(forth, "1 +".as_ptr() as *const c_char, 3, std::ptr::null_mut()), OXFORTH_ERROR);
+            assert_eq!(last_error(), "stack underflow");
+            oxforth_destroy(forth);
+        }
+    }
+
+    #[test]
+    fn ffi_test_define_native() {
+        unsafe {
+            let forth: *mut Forth = oxforth_create();
+            let mut calls: i64 = 0;
+            let context: *mut c_void = &mut calls as *mut i64 as *mut c_void;
+            let name: CString = CString::new("DOUBLE").unwrap();
+            assert_eq!(oxforth_define_native(forth, name.as_ptr(), 1, 1, double, context), OXFORTH_OK);
+            let source: &str = ": QUADRUPLE DOUBLE DOUBLE ; 5 QUADRUPLE";
+            assert_eq!(oxforth_eval(forth, source.as_ptr() as *const c_char, source.len(), std::ptr::null_mut()), OXFORTH_OK);
+            assert_eq!((*forth).pop::<i64>().unwrap(), 20);
+            assert_eq!(calls, 2);
+
+            // the stack effect is checked before the callback is run
+            assert_eq!(oxforth_eval(forth, "DOUBLE".as_ptr() as *const c_char, 6, std::ptr::null_mut()), OXFORTH_ERROR);
+            assert_eq!(last_error(), "stack underflow");
+            assert_eq!(calls, 2);
+            oxforth_destroy(forth);
+        }
+    }
+
+    #[test]
+    fn ffi_test_header() {
+        let header: String = header();
+        assert!(header.contains("\nsize_t oxforth_depth(const oxforth *forth);\n"));
+        assert!(header.contains("\nconst char *oxforth_last_error(void);\n"));
+        assert!(header.contains("\ntypedef int (*oxforth_native)(oxforth *forth, void *context);\n"));
+        assert!(header.contains("size_t outputs,\n                          oxforth_native callback, void *context);\n"));
+        assert!(header.contains("\n#define OXFORTH_ERROR (-1)\n"));
+
+        // comments are wrapped to fit
+        assert!(header.lines().all(|line| line.len() <= 100));
+        assert!(header.lines().filter(|line| line.starts_with(" * ")).all(|line| line.len() <= 80));
+    }
+
+}
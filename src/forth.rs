@@ -0,0 +1,269 @@
+//! Embedding OxForth
+//!
+//! Forth is the entry point for applications embedding the language. It owns
+//! a VM with the core words defined, and takes care of scanning, parsing and
+//! executing source on its behalf. The VM itself remains available for
+//! anything the facade doesn't cover (e.g. choosing where output goes).
+//!
+//! ```
+//! use oxforth::Forth;
+//!
+//! let mut forth: Forth = Forth::new();
+//! forth.define("SQUARE", "DUP *").unwrap();
+//! forth.push(7_i64);
+//! forth.call("SQUARE").unwrap();
+//! assert_eq!(forth.pop::<i64>().unwrap(), 49);
+//! ```
+
+use super::compiler::parser::Operation;
+use super::vm::interpreter::{evaluate, execute};
+use super::vm::{Data, Outcome, VirtualMachineError, VM};
+
+/// A value that can be passed between the host and a Forth program via the
+/// stacks. Numbers and flags travel on the data stack, and floats on the
+/// floating-point stack.
+pub trait Value: Sized {
+
+    /// Push the value onto its stack
+    fn push(self, vm: &mut VM);
+
+    /// Pop a value off its stack
+    fn pop(vm: &mut VM) -> Result<Self, VirtualMachineError>;
+
+}
+
+impl Value for i64 {
+    fn push(self, vm: &mut VM) {
+        vm.data_stack.push(Data::NUMBER(self));
+    }
+    fn pop(vm: &mut VM) -> Result<Self, VirtualMachineError> {
+        return vm.pop();
+    }
+}
+
+impl Value for bool {
+    fn push(self, vm: &mut VM) {
+        vm.data_stack.push(Data::NUMBER(self as i64));  // todo: bool bits
+    }
+    fn pop(vm: &mut VM) -> Result<Self, VirtualMachineError> {
+        return Result::Ok(vm.pop()? != 0);
+    }
+}
+
+impl Value for f64 {
+    fn push(self, vm: &mut VM) {
+        vm.float_stack.push(self);
+    }
+    fn pop(vm: &mut VM) -> Result<Self, VirtualMachineError> {
+        return vm.float_stack.pop().ok_or_else(|| {
+            VirtualMachineError {
+                msg: String::from("floating-point stack underflow"),
+                ..VirtualMachineError::default()
+            }
+        });
+    }
+}
+
+/// A Forth system, ready to interpret source. It's laid out exactly like its
+/// VM, so the C API can hand native callbacks the VM they're given as a Forth.
+#[repr(transparent)]
+pub struct Forth {
+
+    /// VM the source is interpreted by
+    pub vm: VM,
+
+}
+
+impl Default for Forth {
+    fn default() -> Self {
+        return Forth::new();
+    }
+}
+
+impl Forth {
+
+    /// Create a Forth system with the core words defined
+    pub fn new() -> Forth {
+        let mut vm: VM = VM::default();
+        vm.define_core_words();
+        return Forth { vm };
+    }
+
+    /// Interpret Forth source, a line at a time. Stops early if the program
+    /// halts (e.g. via BYE). An error abandons the rest of the source, and any
+    /// definition in progress, unless it's that one of the VM's limits was
+    /// exceeded. The VM is then left intact, so the line that was interrupted
+    /// may be finished (e.g. by execute()) once the limit is raised.
+    pub fn eval(&mut self, source: &str) -> Result<Outcome, VirtualMachineError> {
+        for line in source.lines() {
+            let outcome: Outcome = evaluate(&mut self.vm, &format!("{}\n", line)).map_err(|err| self.recover(err))?;
+            if let Outcome::HALTED(code) = outcome {
+                return Result::Ok(Outcome::HALTED(code));
+            }
+        }
+        return Result::Ok(Outcome::COMPLETED);
+    }
+
+    /// Push a value onto its stack (e.g. a number onto the data stack)
+    pub fn push<T: Value>(&mut self, value: T) {
+        value.push(&mut self.vm);
+    }
+
+    /// Pop a value off its stack (e.g. a float off the floating-point stack)
+    pub fn pop<T: Value>(&mut self) -> Result<T, VirtualMachineError> {
+        return T::pop(&mut self.vm);
+    }
+
+    /// Execute the word with the given name, which takes its arguments from
+    /// the stacks and leaves its results there
+    pub fn call(&mut self, word: &str) -> Result<Outcome, VirtualMachineError> {
+        let xt: usize = self.vm.find(word).ok_or_else(|| {
+            VirtualMachineError {
+                msg: format!("undefined word: {}", word),
+                ..VirtualMachineError::default()
+            }
+        })?;
+        self.vm.operations.push_back(Operation::CALL(xt));
+        return execute(&mut self.vm).map_err(|err| self.recover(err));
+    }
+
+    /// Define a word as a colon definition of the given source (e.g. "DUP *"),
+    /// returning its execution token
+    pub fn define(&mut self, name: &str, source: &str) -> Result<usize, VirtualMachineError> {
+        if name.is_empty() || name.contains(char::is_whitespace) {
+            return Result::Err(
+                VirtualMachineError {
+                    msg: format!("invalid name: {:?}", name),
+                    ..VirtualMachineError::default()
+                }
+            );
+        }
+
+        // The definition is the next one made, whichever wordlist it's placed in
+        let xt: usize = self.vm.definitions.len();
+        self.eval(&format!(": {}\n{}\n;", name, source))?;
+        if self.vm.compiling.is_some() {
+            self.vm.reset();
+            return Result::Err(
+                VirtualMachineError {
+                    msg: format!("unterminated definition of {}", name),
+                    ..VirtualMachineError::default()
+                }
+            );
+        }
+        return Result::Ok(xt);
+    }
+
+    /// Abandon what the VM was doing after an error, unless the error is that
+    /// one of its limits was exceeded, in which case it may be resumed
+    fn recover(&mut self, err: VirtualMachineError) -> VirtualMachineError {
+        if err.limit.is_none() {
+            self.vm.reset();
+        }
+        return err;
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::super::vm::Limit;
+    use super::*;
+
+    #[test]
+    fn forth_test_eval() {
+        let mut forth: Forth = Forth::new();
+        assert_eq!(forth.eval("1 2 +\n3 *").unwrap(), Outcome::COMPLETED);
+        assert_eq!(forth.pop::<i64>().unwrap(), 9);
+
+        // definitions may span lines
+        assert_eq!(forth.eval(": SIX\n2 3\n* ;\nSIX").unwrap(), Outcome::COMPLETED);
+        assert_eq!(forth.pop::<i64>().unwrap(), 6);
+
+        // errors abandon the rest of the source, and any definition in progress
+        assert!(forth.eval("1 FOO 2").unwrap_err().msg.starts_with("undefined word"));
+        assert_eq!(forth.pop::<i64>().unwrap(), 1);
+        assert!(forth.eval(": BAR 1 FOO").is_err());
+        assert!(forth.vm.compiling.is_none());
+        assert!(forth.vm.find("BAR").is_none());
+
+        // halting stops early
+        assert_eq!(forth.eval("1\n7 (BYE)\n2").unwrap(), Outcome::HALTED(Some(7)));
+        assert_eq!(forth.pop::<i64>().unwrap(), 1);
+        assert!(forth.vm.data_stack.is_empty());
+    }
+
+    #[test]
+    fn forth_test_values() {
+        let mut forth: Forth = Forth::new();
+        forth.push(3_i64);
+        forth.push(true);
+        forth.push(2.5_f64);
+        assert!(forth.eval("+ 2E F*").is_ok());
+        assert_eq!(forth.pop::<f64>().unwrap(), 5.0);
+        assert_eq!(forth.pop::<i64>().unwrap(), 4);
+        assert!(forth.eval("1 2 <").is_ok());
+        assert!(forth.pop::<bool>().unwrap());
+
+        // case:  underflow
+        assert_eq!(forth.pop::<i64>().unwrap_err().msg, "stack underflow");
+        assert_eq!(forth.pop::<f64>().unwrap_err().msg, "floating-point stack underflow");
+    }
+
+    #[test]
+    fn forth_test_call_define() {
+        let mut forth: Forth = Forth::new();
+        let xt: usize = forth.define("CUBE", "DUP DUP\n* *").unwrap();
+        assert_eq!(forth.vm.find("CUBE"), Some(xt));
+        forth.push(3_i64);
+        assert_eq!(forth.call("CUBE").unwrap(), Outcome::COMPLETED);
+        assert_eq!(forth.pop::<i64>().unwrap(), 27);
+        forth.push(2_i64);
+        assert!(forth.call("DUP").is_ok());
+        assert_eq!(forth.vm.data_stack, vec![Data::NUMBER(2), Data::NUMBER(2)]);
+        forth.vm.data_stack.clear();
+
+        // case:  errors
+        assert_eq!(forth.call("NOPE").unwrap_err().msg, "undefined word: NOPE");
+        assert_eq!(forth.call("CUBE").unwrap_err().msg, "stack underflow");
+        assert!(forth.vm.return_stack.is_empty());
+        assert!(forth.define("BAD", "1 IF").is_err());
+        assert!(forth.vm.find("BAD").is_none());
+        assert!(forth.vm.compiling.is_none());
+        assert_eq!(forth.call("BYE").unwrap(), Outcome::HALTED(None));
+
+        // case:  names must be a single word
+        assert_eq!(forth.define("", "1").unwrap_err().msg, "invalid name: \"\"");
+        assert_eq!(forth.define("TWO WORDS", "1").unwrap_err().msg, "invalid name: \"TWO WORDS\"");
+        assert_eq!(forth.define("LINE\nBREAK", "1").unwrap_err().msg, "invalid name: \"LINE\\nBREAK\"");
+
+        // definitions outside the search order are defined all the same
+        assert!(forth.eval("WORDLIST SET-CURRENT").is_ok());
+        let xt: usize = forth.define("HIDDEN", "5").unwrap();
+        assert_eq!(forth.vm.definitions[xt].name, "HIDDEN");
+        assert!(forth.vm.find("HIDDEN").is_none());
+    }
+
+    #[test]
+    fn forth_test_limits() {
+        let mut forth: Forth = Forth::new();
+        forth.define("THREE", "1 2 3").unwrap();
+
+        // execution may be resumed once the limit is raised
+        forth.vm.limits.operations = Some(forth.vm._ops_applied + 2);
+        assert_eq!(forth.eval("THREE").unwrap_err().limit, Some(Limit::OPERATIONS));
+        assert_eq!(forth.vm.return_stack.len(), 1);
+        forth.vm.limits.operations = None;
+        assert_eq!(execute(&mut forth.vm).unwrap(), Outcome::COMPLETED);
+        assert_eq!(forth.vm.data_stack, vec![Data::NUMBER(1), Data::NUMBER(2), Data::NUMBER(3)]);
+        forth.vm.data_stack.clear();
+
+        forth.vm.limits.operations = Some(forth.vm._ops_applied + 2);
+        assert_eq!(forth.call("THREE").unwrap_err().limit, Some(Limit::OPERATIONS));
+        forth.vm.limits.operations = None;
+        assert_eq!(execute(&mut forth.vm).unwrap(), Outcome::COMPLETED);
+        assert_eq!(forth.vm.data_stack, vec![Data::NUMBER(1), Data::NUMBER(2), Data::NUMBER(3)]);
+    }
+
+}
@@ -15,6 +15,14 @@
 
 //! OxForth - library
 
+// Returns are always explicit in this codebase
+#![allow(clippy::needless_return)]
+
 pub mod compiler;
+pub mod ffi;
+pub mod forth;
 pub mod misc;
 pub mod vm;
+pub mod wasm;
+
+pub use forth::{Forth, Value};
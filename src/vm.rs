@@ -1,29 +1,197 @@
 //! Virtual Machine
 
 use std::collections::{HashMap, VecDeque};
+use std::path::PathBuf;
+use std::rc::Rc;
 
+use super::compiler::CompilerError;
 use super::compiler::scanner::Token;
 use super::compiler::parser::Operation;
 
+use dictionary::{Dictionary, FORTH_WORDLIST};
+use files::Files;
+use io::{InputSource, OutputSink};
+use memory::{DataSpace, DATA_SPACE_ADDR};
+use native::Native;
+
+pub mod dictionary;
+pub mod files;
 pub mod interpreter;
+pub mod io;
+pub mod memory;
+pub mod native;
 
-/// Data on the data stack is represented by these types. Casting to different
-/// types is done as needed depending on the executing word and whether it's
-/// even possible.
+/// Data on the data stack is represented by these types.
+///
+/// Strings aren't kept on the data stack. Like any other Forth, they're stored
+/// in data space and referred to by a pair of cells: the address of the first
+/// character and the number of characters ("c-addr u").
 #[derive(Clone, Debug, PartialEq)]
 pub enum Data {
-    STRING(String),
     NUMBER(i64),
 }
 
+/// A word, whether built into the VM or defined by a Forth program (e.g. via
+/// ":"). Its execution token ("xt") is its index in the VM's definitions.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Definition {
+
+    /// Name the definition was given
+    pub name: String,
+
+    /// Operations applied when the definition is executed. Colon definitions
+    /// end with EXIT once they're complete.
+    pub body: Vec<Operation>,
+
+    /// Whether the definition is executed rather than compiled while compiling
+    /// (e.g. IF)
+    pub immediate: bool,
+
+    /// Whether the body is compiled in place of a call to the definition, as
+    /// is done for words built into the VM
+    pub inline: bool,
+
+    /// Wordlist the definition's name was added to, if it's been added to one.
+    /// Quotations never are, and colon definitions aren't until they're complete.
+    pub wordlist: Option<usize>,
+
+    /// The data-space pointer when the definition was created, which is where
+    /// data space is rolled back to when the definition is forgotten
+    pub here: usize,
+
+}
+
+/// A definition whose compilation is suspended while a quotation within it is
+/// compiled (e.g. by "[:")
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Enclosing {
+
+    /// Execution token of the suspended definition. Quotations may also be
+    /// begun while interpreting, in which case there's none.
+    pub xt: Option<usize>,
+
+    /// Names of the suspended definition's locals, which aren't visible
+    /// within the quotation
+    pub locals: Vec<String>,
+
+}
+
+/// The dictionary state a marker rolls back to when it's executed, besides
+/// forgetting the definitions made since (see MARKER)
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Marker {
+
+    /// Execution token of the marker
+    pub xt: usize,
+
+    /// Wordlist new definitions were placed in ("compilation wordlist")
+    pub current: usize,
+
+    /// Number of wordlists that had been created
+    pub wordlists: usize,
+
+    /// Wordlists that were searched, first to last
+    pub search_order: Vec<usize>,
+
+}
+
+/// Control parameters of a DO loop ("loop-sys")
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LoopControl {
+
+    /// Current value of the loop index (as given by I)
+    pub index: i64,
+
+    /// The loop ends when the index crosses from limit - 1 to limit
+    pub limit: i64,
+
+    /// Index of the operation following the loop, where LEAVE continues
+    pub exit: usize,
+
+}
+
+/// Where execution resumes within a definition ("nest-sys"). The return
+/// stack holds one frame per definition being executed.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Frame {
+
+    /// Execution token of the definition being executed
+    pub xt: usize,
+
+    /// Index of the next operation of the definition's body to apply
+    pub ip: usize,
+
+    /// DO loops the definition is in the midst of, innermost last
+    pub loops: Vec<LoopControl>,
+
+    /// Values of the definition's locals, in order of declaration
+    pub locals: Vec<i64>,
+
+}
+
+/// A source file being interpreted (e.g. via INCLUDED), along with the input
+/// that was being interpreted before it, which is picked up again once the
+/// file has been interpreted
+#[derive(Debug, Default, PartialEq)]
+pub struct Source {
+
+    /// Path of the file
+    pub path: PathBuf,
+
+    /// Lines of the file not yet interpreted
+    pub lines: VecDeque<String>,
+
+    /// Number of the line being interpreted, counting from 1
+    pub line: usize,
+
+    /// Input buffer, offset, tokens, operations and return stack set aside
+    /// while the file is interpreted
+    pub input_buffer: String,
+    pub input_offset: usize,
+    pub tokens: VecDeque<Token>,
+    pub operations: VecDeque<Operation>,
+    pub return_stack: Vec<Frame>,
+
+}
+
 #[derive(Default)]
-pub struct VM<'vm> {
+pub struct VM {
 
     /// Number of operations applied to the VM instance
     pub _ops_applied: u64,
 
-    /// Words are mapped to an ordered collection of VM operations
-    pub dictionary: HashMap<&'vm str, Vec<Operation>>,
+    /// Wordlists, which map words to the execution token of their definition,
+    /// and the order they're searched in
+    pub dictionary: Dictionary,
+
+    /// Every definition, indexed by execution token, in the order they were made
+    pub definitions: Vec<Definition>,
+
+    /// Definitions before this execution token are built into the VM, and
+    /// can't be forgotten (e.g. by FORGET)
+    pub fence: usize,
+
+    /// Execution token of the definition being compiled, if any. The VM is in
+    /// "compilation state" while this is set.
+    pub compiling: Option<usize>,
+
+    /// Names of the locals declared by the definition being compiled, in order
+    /// of declaration (e.g. via "{:")
+    pub locals: Vec<String>,
+
+    /// Definitions suspended while compiling the quotations within them,
+    /// outermost first
+    pub enclosing: Vec<Enclosing>,
+
+    /// Definitions being executed, innermost last ("return stack")
+    pub return_stack: Vec<Frame>,
+
+    /// Text currently being interpreted ("input buffer")
+    pub input_buffer: String,
+
+    /// Offset of the first unscanned character of the input buffer (">IN").
+    /// Everything from here onward is the "parse area".
+    pub input_offset: usize,
 
     /// Tokens found by scanning the input buffer ("parse area").
     pub tokens: VecDeque<Token>,
@@ -34,53 +202,607 @@ pub struct VM<'vm> {
     /// The general stack ("data stack")
     pub data_stack: Vec<Data>,
 
+    /// Floats are kept apart from the data stack, on the "floating-point stack"
+    pub float_stack: Vec<f64>,
+
+    /// Cells of the data stack below this depth are out of reach of pop(),
+    /// being below the inputs of the native word being executed, if any
+    pub stack_base: usize,
+
+    /// Where text displayed by words like TYPE and "." is written. The host
+    /// decides where it ends up; the REPL writes it to stdout, for example.
+    pub output: Box<dyn OutputSink>,
+
+    /// Where text read by the program comes from
+    pub input: Box<dyn InputSource>,
+
+    /// Memory addressable by Forth programs ("data space")
+    pub memory: DataSpace,
+
+    /// Command-line arguments given to the program, the first being the program
+    /// itself (e.g. the source file being run), as given by ARG
+    pub arguments: Vec<String>,
+
+    /// Files opened by the program, and source files being interpreted
+    pub files: Files,
+
+    /// Source files being interpreted, innermost last
+    pub sources: Vec<Source>,
+
+    /// Limits on the resources the program may use
+    pub limits: Limits,
+
+    /// Words written in Rust by the host, indexed as by Operation::NATIVE
+    pub natives: Vec<Native>,
+
+    /// State saved by each marker that hasn't been forgotten, oldest first
+    pub markers: Vec<Marker>,
+
+    /// Text substituted for each name by SUBSTITUTE, as set by REPLACES. Names
+    /// are kept in uppercase, since substitution names are case-insensitive.
+    pub substitutions: HashMap<String, Vec<u8>>,
+
+}
+
+/// How applying operations to the VM finished, short of an error
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Outcome {
+
+    /// Every pending operation was applied
+    COMPLETED,
+
+    /// The program asked to stop (e.g. via BYE), with an exit code if it gave
+    /// one. Pending operations are discarded. What stopping means is up to the
+    /// host: the REPL exits the process, while an embedding application might
+    /// just drop the VM.
+    HALTED(Option<i32>),
+
+    /// The time slice given for execution ran out (e.g. via execute_for())
+    /// before every pending operation was applied. The VM is left intact, so
+    /// execution may be resumed later.
+    YIELDED,
+
 }
 
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct VirtualMachineError {
     /// `msg` describes what went wrong
-    pub msg: String
+    pub msg: String,
+
+    /// The resource limit that was exceeded, if that's what went wrong. The VM
+    /// is left intact in that case, so execution may be resumed (e.g. by
+    /// execute()) once the limit is raised.
+    pub limit: Option<Limit>,
+}
+
+/// Resources whose use by Forth programs may be limited by the host, so that
+/// programs it can't trust (e.g. ones submitted by users) can't run forever or
+/// exhaust the host's memory
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Limit {
+
+    /// Operations applied to the VM, as counted by `_ops_applied`
+    OPERATIONS,
+
+    /// Depth of the data stack
+    DATA_STACK,
+
+    /// Depth of the floating-point stack, which shares the data stack's limit
+    FLOAT_STACK,
+
+    /// Depth of the return stack
+    RETURN_STACK,
+
+    /// Size of user data space, in address units
+    DATA_SPACE,
+
+}
+
+impl Limit {
+
+    /// THROW code Forth-2012 assigns to exceeding the limit, if any
+    pub fn code(&self) -> Option<i64> {
+        return match self {
+            Limit::OPERATIONS => None,
+            Limit::DATA_STACK => Some(-3),
+            Limit::FLOAT_STACK => Some(-44),
+            Limit::RETURN_STACK => Some(-5),
+            Limit::DATA_SPACE => Some(-8),
+        };
+    }
+
+    /// Error reported when the limit is exceeded
+    pub fn exceeded(&self) -> VirtualMachineError {
+        let msg: &str = match self {
+            Limit::OPERATIONS => "operation limit exceeded",
+            Limit::DATA_STACK => "stack overflow",
+            Limit::FLOAT_STACK => "floating-point stack overflow",
+            Limit::RETURN_STACK => "return stack overflow",
+            Limit::DATA_SPACE => "data space overflow",
+        };
+        return VirtualMachineError {
+            msg: String::from(msg),
+            limit: Some(*self),
+        };
+    }
+
+}
+
+/// Limits on the resources a Forth program may use. Nothing is limited unless
+/// the host says otherwise.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Limits {
+
+    /// Most operations the VM may apply, counting those applied before the
+    /// limit was set. Raising the limit lets a program that exceeded it run on.
+    pub operations: Option<u64>,
+
+    /// Most cells the data stack may hold, and most floats the floating-point
+    /// stack may hold
+    pub data_stack: Option<usize>,
+
+    /// Most definitions that may be being executed at once
+    pub return_stack: Option<usize>,
+
+    /// Most address units of data space that may be allotted
+    pub data_space: Option<usize>,
+
+}
+
+/// Words that parse the input buffer at runtime (e.g. S\") may hit errors that
+/// would otherwise be reported by the compiler.
+impl From<CompilerError> for VirtualMachineError {
+    fn from(err: CompilerError) -> Self {
+        return VirtualMachineError {
+            msg: err.msg,
+            ..VirtualMachineError::default()
+        };
+    }
 }
 
 #[allow(non_snake_case)]
-impl<'vm> VM<'vm> {
+impl VM {
+
+    /// Add a definition, without making it available by name. Returns its
+    /// execution token.
+    pub fn create(&mut self, definition: Definition) -> usize {
+        self.definitions.push(
+            Definition {
+                here: self.memory.here(),
+                ..definition
+            }
+        );
+        return self.definitions.len() - 1;
+    }
+
+    /// Make a definition available by name in the compilation wordlist. Words
+    /// compiled against an earlier definition of the same name keep referring
+    /// to it, but a warning is displayed since that's easily overlooked.
+    pub fn reveal(&mut self, xt: usize) {
+        let name: String = self.definitions[xt].name.clone();
+        self.definitions[xt].wordlist = Some(self.dictionary.current);
+        if self.dictionary.insert(name.clone(), xt).is_some() {
+            // Warnings are best-effort; failing to display one isn't an error
+            let _ = self.output.write(format!("redefined {} ", name).as_bytes());
+        }
+    }
+
+    /// Add a definition, making it available by name in the compilation
+    /// wordlist. Returns its execution token.
+    pub fn define(&mut self, definition: Definition) -> usize {
+        let xt: usize = self.create(definition);
+        self.reveal(xt);
+        return xt;
+    }
+
+    /// Define a word written in Rust, which takes `inputs` cells off the data
+    /// stack and leaves `outputs` cells in their place. Returns its execution
+    /// token.
+    ///
+    /// ```
+    /// use oxforth::vm::{Data, VM};
+    /// use oxforth::vm::interpreter::evaluate;
+    ///
+    /// let mut vm: VM = VM::default();
+    /// vm.define_core_words();
+    /// vm.define_native("CUBE", 1, 1, |vm: &mut VM| {
+    ///     let n: i64 = vm.pop()?;
+    ///     vm.push(n * n * n);
+    ///     return Ok(());
+    /// });
+    /// evaluate(&mut vm, "3 CUBE\n").unwrap();
+    /// assert_eq!(vm.data_stack, vec![Data::NUMBER(27)]);
+    /// ```
+    pub fn define_native<F>(&mut self, name: &str, inputs: usize, outputs: usize, function: F) -> usize
+    where
+        F: Fn(&mut VM) -> Result<(), VirtualMachineError> + 'static,
+    {
+        self.natives.push(
+            Native {
+                name: String::from(name),
+                inputs,
+                outputs,
+                function: Rc::new(function),
+            }
+        );
+        return self.define(
+            Definition {
+                name: String::from(name),
+                body: vec![
+                    Operation::NATIVE(self.natives.len() - 1),
+                ],
+                inline: true,
+                ..Definition::default()
+            }
+        );
+    }
+
+    /// Push a number onto the data stack
+    pub fn push(&mut self, n: i64) {
+        self.data_stack.push(Data::NUMBER(n));
+    }
+
+    /// Pop a number off the data stack, which mustn't be empty (or, while a
+    /// native word is executed, be down to the cells below its inputs)
+    pub fn pop(&mut self) -> Result<i64, VirtualMachineError> {
+        let top: Option<Data> = if self.data_stack.len() > self.stack_base { self.data_stack.pop() } else { None };
+        match top {
+            Some(Data::NUMBER(n)) => {
+                return Result::Ok(n);
+            },
+            None => {
+                return Result::Err(
+                    VirtualMachineError {
+                        msg: String::from("stack underflow"),
+                        ..VirtualMachineError::default()
+                    }
+                );
+            },
+        }
+    }
+
+    /// Remove the given definition and every definition made after it, and
+    /// release the data space allotted since it was created (e.g. by FORGET)
+    pub fn forget(&mut self, xt: usize) -> Result<(), VirtualMachineError> {
+        if xt < self.fence {
+            return Result::Err(
+                VirtualMachineError {
+                    msg: format!("can't forget {}, which is built into the VM", self.definitions[xt].name),
+                    ..VirtualMachineError::default()
+                }
+            );
+        }
+        if self.compiling.is_some() || self.return_stack.iter().any(|frame| frame.xt >= xt) {
+            return Result::Err(
+                VirtualMachineError {
+                    msg: format!("can't forget {}, which is in use", self.definitions[xt].name),
+                    ..VirtualMachineError::default()
+                }
+            );
+        }
+        let here: usize = self.definitions[xt].here;
+        self.memory.allot(here as i64 - self.memory.here() as i64)?;
+        self.definitions.truncate(xt);
+        self.markers.retain(|marker| marker.xt < xt);
+        self.dictionary.rebuild(&self.definitions);
+        return Result::Ok(());
+    }
+
+    /// Reserve n address units of data space, or release them if n is
+    /// negative, as done by ALLOT. It's an error to exceed the data-space limit.
+    pub fn allot(&mut self, n: i64) -> Result<(), VirtualMachineError> {
+        if let Some(limit) = self.limits.data_space {
+            let size: i64 = self.memory.here() as i64 - DATA_SPACE_ADDR as i64;
+            if n > 0 && size.saturating_add(n) > limit as i64 {
+                return Result::Err(Limit::DATA_SPACE.exceeded());
+            }
+        }
+        return self.memory.allot(n);
+    }
+
+    /// Execution token of the definition with the given name, if it's found
+    /// in the search order
+    pub fn find(&self, name: &str) -> Option<usize> {
+        return self.dictionary.find(name);
+    }
+
+    /// Index of the local with the given name, if it's been declared by the
+    /// definition being compiled. Later declarations shadow earlier ones.
+    pub fn find_local(&self, name: &str) -> Option<usize> {
+        return self.locals.iter().rposition(|local| local == name);
+    }
+
+    /// Execution token of the definition being compiled. It's an error to use
+    /// compile-only words (e.g. IF) when nothing is being compiled.
+    pub fn current_definition(&self) -> Result<usize, VirtualMachineError> {
+        return self.compiling.ok_or_else(|| {
+            VirtualMachineError {
+                msg: String::from("interpreting a compile-only word"),
+                ..VirtualMachineError::default()
+            }
+        });
+    }
+
+    /// Append an operation to the definition being compiled, returning its
+    /// index within the definition's body.
+    pub fn compile(&mut self, operation: Operation) -> Result<usize, VirtualMachineError> {
+        let xt: usize = self.current_definition()?;
+        let body: &mut Vec<Operation> = &mut self.definitions[xt].body;
+        body.push(operation);
+        return Result::Ok(body.len() - 1);
+    }
+
+    /// Abandon whatever was being interpreted, executed, or compiled, as done
+    /// by QUIT after an error. The data stack is left as-is.
+    pub fn reset(&mut self) {
+        self.tokens.clear();
+        self.operations.clear();
+        self.return_stack.clear();
+        self.locals.clear();
+        self.stack_base = 0;
+        self.sources.clear();
+        self.files.including.clear();
+
+        // Unfinished definitions are the last ones made, the outermost first
+        let enclosing: Option<usize> = self.enclosing.drain(..).find_map(|enclosing| enclosing.xt);
+        if let Some(xt) = enclosing.or(self.compiling.take()) {
+            self.definitions.truncate(xt);
+        }
+    }
 
     /// Define words at runtime:  Implementation-defined
     pub fn define_core_words(&mut self) {
 
         macro_rules! define_single_op_word {
             ($word:expr, $operation:expr) => {{
-                self.dictionary.insert(
-                    $word,
-                    vec![
-                        $operation,
-                    ],
+                self.define(
+                    Definition {
+                        name: String::from($word),
+                        body: vec![
+                            $operation,
+                        ],
+                        immediate: false,
+                        inline: true,
+                        ..Definition::default()
+                    }
                 );
             }};
         }
 
         define_single_op_word!("NOP", Operation::NOP);
         define_single_op_word!("ABS", Operation::ABS);
+        define_single_op_word!("ACCEPT", Operation::ACCEPT);
+        define_single_op_word!("ACTION-OF", Operation::ACTION_OF);
         define_single_op_word!("+", Operation::ADD);
+        define_single_op_word!("ALLOT", Operation::ALLOT);
+        define_single_op_word!("ALSO", Operation::ALSO);
+        define_single_op_word!("ARG", Operation::ARG);
+        define_single_op_word!("ARGC", Operation::ARGC);
+        define_single_op_word!("BASE", Operation::BASE);
+        define_single_op_word!("BIN", Operation::BIN);
+        define_single_op_word!("BLANK", Operation::BLANK);
+        define_single_op_word!("{:", Operation::BRACE_COLON);
+        define_single_op_word!("[CHAR]", Operation::BRACKET_CHAR);
+        define_single_op_word!("[:", Operation::BRACKET_COLON);
+        define_single_op_word!("[']", Operation::BRACKET_TICK);
         define_single_op_word!("BYE", Operation::BYE);
+        define_single_op_word!("(BYE)", Operation::BYE_CODE);
+        define_single_op_word!(":", Operation::COLON);
+        define_single_op_word!("C@", Operation::C_FETCH);
+        define_single_op_word!("C\"", Operation::C_QUOTE);
+        define_single_op_word!("C!", Operation::C_STORE);
+        define_single_op_word!("CASE", Operation::CASE);
+        define_single_op_word!("CHAR", Operation::CHAR);
+        define_single_op_word!("CLOSE-FILE", Operation::CLOSE_FILE);
+        define_single_op_word!("CMOVE", Operation::CMOVE);
+        define_single_op_word!("CMOVE>", Operation::CMOVE_UP);
         define_single_op_word!("=", Operation::CMP_EQ);
         define_single_op_word!("<", Operation::CMP_LT);
         define_single_op_word!(">", Operation::CMP_GT);
         define_single_op_word!("<>", Operation::CMP_NE);
+        define_single_op_word!("COMPARE", Operation::COMPARE);
+        define_single_op_word!("COUNT", Operation::COUNT);
+        define_single_op_word!("CR", Operation::CR);
+        define_single_op_word!("CREATE-FILE", Operation::CREATE_FILE);
+        define_single_op_word!("D>F", Operation::D_TO_F);
+        define_single_op_word!("-TRAILING", Operation::DASH_TRAILING);
+        define_single_op_word!("DECIMAL", Operation::DECIMAL);
+        define_single_op_word!("DEFER", Operation::DEFER);
+        define_single_op_word!("DEFER@", Operation::DEFER_FETCH);
+        define_single_op_word!("DEFER!", Operation::DEFER_STORE);
+        define_single_op_word!("DEFINITIONS", Operation::DEFINITIONS);
+        define_single_op_word!("DELETE-FILE", Operation::DELETE_FILE);
+        define_single_op_word!("DF@", Operation::F_FETCH);
+        define_single_op_word!("DF!", Operation::F_STORE);
+        define_single_op_word!("DFALIGN", Operation::F_ALIGN);
+        define_single_op_word!("DFALIGNED", Operation::F_ALIGNED);
+        define_single_op_word!("DFLOAT+", Operation::FLOAT_PLUS);
+        define_single_op_word!("DFLOATS", Operation::FLOATS);
         define_single_op_word!("/", Operation::DIV);
+        define_single_op_word!("DO", Operation::DO);
+        define_single_op_word!(".", Operation::DOT);
+        define_single_op_word!(".\"", Operation::DOT_QUOTE);
+        define_single_op_word!(".R", Operation::DOT_R);
+        define_single_op_word!(".S", Operation::DOT_S);
         define_single_op_word!("DROP", Operation::DROP);
         define_single_op_word!("DUP", Operation::DUP);
+        define_single_op_word!("EKEY", Operation::EKEY);
+        define_single_op_word!("EKEY>CHAR", Operation::EKEY_TO_CHAR);
+        define_single_op_word!("ELSE", Operation::ELSE);
+        define_single_op_word!("EMIT", Operation::EMIT);
+        define_single_op_word!("ENDCASE", Operation::ENDCASE);
+        define_single_op_word!("ENDOF", Operation::ENDOF);
+        define_single_op_word!("EXECUTE", Operation::EXECUTE);
+        define_single_op_word!("EXIT", Operation::EXIT);
+        define_single_op_word!("FABS", Operation::F_ABS);
+        define_single_op_word!("FACOS", Operation::F_ACOS);
+        define_single_op_word!("FACOSH", Operation::F_ACOSH);
+        define_single_op_word!("F+", Operation::F_ADD);
+        define_single_op_word!("FALIGN", Operation::F_ALIGN);
+        define_single_op_word!("FALIGNED", Operation::F_ALIGNED);
+        define_single_op_word!("FALOG", Operation::F_ALOG);
+        define_single_op_word!("FASIN", Operation::F_ASIN);
+        define_single_op_word!("FASINH", Operation::F_ASINH);
+        define_single_op_word!("FATAN", Operation::F_ATAN);
+        define_single_op_word!("FATAN2", Operation::F_ATAN2);
+        define_single_op_word!("FATANH", Operation::F_ATANH);
+        define_single_op_word!("F<", Operation::F_CMP_LT);
+        define_single_op_word!("FCONSTANT", Operation::F_CONSTANT);
+        define_single_op_word!("FCOS", Operation::F_COS);
+        define_single_op_word!("FCOSH", Operation::F_COSH);
+        define_single_op_word!("FDEPTH", Operation::F_DEPTH);
+        define_single_op_word!("F/", Operation::F_DIV);
+        define_single_op_word!("F.", Operation::F_DOT);
+        define_single_op_word!("FDROP", Operation::F_DROP);
+        define_single_op_word!("FDUP", Operation::F_DUP);
+        define_single_op_word!("FE.", Operation::F_E_DOT);
+        define_single_op_word!("FEXP", Operation::F_EXP);
+        define_single_op_word!("FEXPM1", Operation::F_EXPM1);
+        define_single_op_word!("F@", Operation::F_FETCH);
+        define_single_op_word!("FLOOR", Operation::F_FLOOR);
+        define_single_op_word!("FLN", Operation::F_LN);
+        define_single_op_word!("FLNP1", Operation::F_LNP1);
+        define_single_op_word!("FLOG", Operation::F_LOG);
+        define_single_op_word!("FMAX", Operation::F_MAX);
+        define_single_op_word!("FMIN", Operation::F_MIN);
+        define_single_op_word!("F*", Operation::F_MUL);
+        define_single_op_word!("FNEGATE", Operation::F_NEGATE);
+        define_single_op_word!("FOVER", Operation::F_OVER);
+        define_single_op_word!("F**", Operation::F_POW);
+        define_single_op_word!("F~", Operation::F_PROXIMATE);
+        define_single_op_word!("FROT", Operation::F_ROT);
+        define_single_op_word!("FROUND", Operation::F_ROUND);
+        define_single_op_word!("FS.", Operation::F_S_DOT);
+        define_single_op_word!("FSIN", Operation::F_SIN);
+        define_single_op_word!("FSINCOS", Operation::F_SINCOS);
+        define_single_op_word!("FSINH", Operation::F_SINH);
+        define_single_op_word!("FSQRT", Operation::F_SQRT);
+        define_single_op_word!("F!", Operation::F_STORE);
+        define_single_op_word!("F-", Operation::F_SUB);
+        define_single_op_word!("FSWAP", Operation::F_SWAP);
+        define_single_op_word!("FTAN", Operation::F_TAN);
+        define_single_op_word!("FTANH", Operation::F_TANH);
+        define_single_op_word!("F>D", Operation::F_TO_D);
+        define_single_op_word!("F>S", Operation::F_TO_S);
+        define_single_op_word!("FTRUNC", Operation::F_TRUNC);
+        define_single_op_word!("FVARIABLE", Operation::F_VARIABLE);
+        define_single_op_word!("F0=", Operation::F_ZERO_EQ);
+        define_single_op_word!("F0<", Operation::F_ZERO_LT);
+        define_single_op_word!("@", Operation::FETCH);
+        define_single_op_word!("FILE-POSITION", Operation::FILE_POSITION);
+        define_single_op_word!("FILE-SIZE", Operation::FILE_SIZE);
+        define_single_op_word!("FLITERAL", Operation::FLITERAL);
+        define_single_op_word!("FLOAT+", Operation::FLOAT_PLUS);
+        define_single_op_word!("FLOATS", Operation::FLOATS);
+        define_single_op_word!("FORGET", Operation::FORGET);
+        define_single_op_word!("FORTH", Operation::ORDER_TOP(FORTH_WORDLIST));
+        define_single_op_word!("FORTH-WORDLIST", Operation::FORTH_WORDLIST);
+        define_single_op_word!("GET-CURRENT", Operation::GET_CURRENT);
+        define_single_op_word!("GET-ORDER", Operation::GET_ORDER);
+        define_single_op_word!("HERE", Operation::HERE);
+        define_single_op_word!("HEX", Operation::HEX);
+        define_single_op_word!("HOLD", Operation::HOLD);
+        define_single_op_word!("HOLDS", Operation::HOLDS);
+        define_single_op_word!("I", Operation::I);
+        define_single_op_word!("IF", Operation::IF);
+        define_single_op_word!("INCLUDE", Operation::INCLUDE);
+        define_single_op_word!("INCLUDED", Operation::INCLUDED);
+        define_single_op_word!("IS", Operation::IS);
+        define_single_op_word!("J", Operation::J);
+        define_single_op_word!("KEY", Operation::KEY);
+        define_single_op_word!("KEY?", Operation::KEY_QUESTION);
+        define_single_op_word!("LEAVE", Operation::LEAVE);
+        define_single_op_word!("LOCALS|", Operation::LOCALS_BAR);
+        define_single_op_word!("LOOP", Operation::LOOP);
+        define_single_op_word!("MARKER", Operation::MARKER);
         define_single_op_word!("MAX", Operation::MAX);
         define_single_op_word!("MIN", Operation::MIN);
         define_single_op_word!("MOD", Operation::MOD);
         define_single_op_word!("*", Operation::MUL);
         define_single_op_word!("NEGATE", Operation::NEGATE);
+        define_single_op_word!("NEXT-ARG", Operation::NEXT_ARG);
+        define_single_op_word!("OF", Operation::OF);
+        define_single_op_word!("#", Operation::NUM);
+        define_single_op_word!("#>", Operation::NUM_END);
+        define_single_op_word!("#S", Operation::NUM_S);
+        define_single_op_word!("<#", Operation::NUM_START);
+        define_single_op_word!("ONLY", Operation::ONLY);
+        define_single_op_word!("OPEN-FILE", Operation::OPEN_FILE);
+        define_single_op_word!("ORDER", Operation::ORDER);
+        define_single_op_word!("OVER", Operation::OVER);
+        define_single_op_word!("PAGE", Operation::PAGE);
+        define_single_op_word!("+LOOP", Operation::PLUS_LOOP);
+        define_single_op_word!("PREVIOUS", Operation::PREVIOUS);
+        define_single_op_word!("PRECISION", Operation::PRECISION);
+        define_single_op_word!("?DO", Operation::QUESTION_DO);
+        define_single_op_word!("?", Operation::QUESTION);
+        define_single_op_word!("R/O", Operation::R_O);
+        define_single_op_word!("R/W", Operation::R_W);
+        define_single_op_word!("READ-FILE", Operation::READ_FILE);
+        define_single_op_word!("READ-LINE", Operation::READ_LINE);
+        define_single_op_word!("RECURSE", Operation::RECURSE);
+        define_single_op_word!("RENAME-FILE", Operation::RENAME_FILE);
+        define_single_op_word!("REPLACES", Operation::REPLACES);
+        define_single_op_word!("REPOSITION-FILE", Operation::REPOSITION_FILE);
+        define_single_op_word!("REPRESENT", Operation::REPRESENT);
+        define_single_op_word!("REQUIRE", Operation::REQUIRE);
+        define_single_op_word!("REQUIRED", Operation::REQUIRED);
+        define_single_op_word!("ROT", Operation::ROT);
+        define_single_op_word!("S\\\"", Operation::S_BACKSLASH_QUOTE);
+        define_single_op_word!("S\"", Operation::S_QUOTE);
+        define_single_op_word!("S>D", Operation::S_TO_D);
+        define_single_op_word!("S>F", Operation::S_TO_F);
+        define_single_op_word!("SEARCH", Operation::SEARCH);
+        define_single_op_word!("SEARCH-WORDLIST", Operation::SEARCH_WORDLIST);
+        define_single_op_word!(";", Operation::SEMICOLON);
+        define_single_op_word!(";]", Operation::SEMICOLON_BRACKET);
+        define_single_op_word!("SET-CURRENT", Operation::SET_CURRENT);
+        define_single_op_word!("SET-ORDER", Operation::SET_ORDER);
+        define_single_op_word!("SET-PRECISION", Operation::SET_PRECISION);
+        define_single_op_word!("SFALIGN", Operation::SF_ALIGN);
+        define_single_op_word!("SFALIGNED", Operation::SF_ALIGNED);
+        define_single_op_word!("SF@", Operation::SF_FETCH);
+        define_single_op_word!("SF!", Operation::SF_STORE);
+        define_single_op_word!("SFLOAT+", Operation::SFLOAT_PLUS);
+        define_single_op_word!("SFLOATS", Operation::SFLOATS);
+        define_single_op_word!("SIGN", Operation::SIGN);
+        define_single_op_word!("/STRING", Operation::SLASH_STRING);
+        define_single_op_word!("SLITERAL", Operation::SLITERAL);
+        define_single_op_word!("SPACE", Operation::SPACE);
+        define_single_op_word!("SPACES", Operation::SPACES);
+        define_single_op_word!("!", Operation::STORE);
         define_single_op_word!("-", Operation::SUB);
+        define_single_op_word!("SUBSTITUTE", Operation::SUBSTITUTE);
+        define_single_op_word!("SWAP", Operation::SWAP);
+        define_single_op_word!("THEN", Operation::THEN);
+        define_single_op_word!("'", Operation::TICK);
+        define_single_op_word!("TO", Operation::TO);
+        define_single_op_word!(">FLOAT", Operation::TO_FLOAT);
+        define_single_op_word!("TYPE", Operation::TYPE);
+        define_single_op_word!("UNLOOP", Operation::UNLOOP);
+        define_single_op_word!("UNESCAPE", Operation::UNESCAPE);
+        define_single_op_word!("VOCABULARY", Operation::VOCABULARY);
+        define_single_op_word!("WORDLIST", Operation::WORDLIST);
+        define_single_op_word!("W/O", Operation::W_O);
+        define_single_op_word!("WRITE-FILE", Operation::WRITE_FILE);
+        define_single_op_word!("WRITE-LINE", Operation::WRITE_LINE);
         define_single_op_word!("0=", Operation::ZERO_EQ);
         define_single_op_word!("0<", Operation::ZERO_LT);
         define_single_op_word!("0>", Operation::ZERO_GT);
         define_single_op_word!("0<>", Operation::ZERO_NE);
 
+        // Words with compilation semantics of their own
+        for word in [
+            ";", ";]", "ACTION-OF", "{:", "[CHAR]", "[:", "[']", "C\"", "CASE", "DO", ".\"", "ELSE", "ENDCASE", "ENDOF",
+            "FLITERAL", "IF", "IS", "LOCALS|", "LOOP", "OF", "+LOOP", "?DO", "RECURSE", "S\\\"", "S\"", "SLITERAL", "THEN", "TO",
+        ] {
+            let xt: usize = self.find(word).unwrap();
+            self.definitions[xt].immediate = true;
+        }
+
+        self.fence = self.definitions.len();
+
     }
 
 }
@@ -95,22 +817,31 @@ mod tests {
     #[test]
     fn vm_test_dictionary() {
         let mut vm: VM = VM::default();
-        vm.dictionary.insert(
-            "word",
-            vec![
-                Operation::NOP,
-            ],
+        let xt: usize = vm.define(
+            Definition {
+                name: String::from("word"),
+                body: vec![
+                    Operation::NOP,
+                ],
+                ..Definition::default()
+            }
         );
+        assert_eq!(vm.find("word"), Some(xt));
+        assert_eq!(vm.find("WORD"), None);
     }
 
     #[test]
     fn vm_test_defining_words() {
         let mut vm: VM = VM::default();
-        vm.dictionary.insert(
-            "NOP_INC",
-            vec![
-                Operation::NOP_INC,
-            ],
+        vm.define(
+            Definition {
+                name: String::from("NOP_INC"),
+                body: vec![
+                    Operation::NOP_INC,
+                ],
+                inline: true,
+                ..Definition::default()
+            }
         );
         assert_eq!(vm._ops_applied, 0);
         assert!(scan("NOP_INC\n", &mut vm).is_ok());
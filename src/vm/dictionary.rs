@@ -0,0 +1,197 @@
+//! Dictionary
+//!
+//! Words are kept in wordlists, each mapping names to execution tokens. Words
+//! are found by searching the wordlists of the "search order" in turn, so a
+//! word defined in one wordlist shadows words of the same name in wordlists
+//! searched after it, rather than replacing them. New definitions are added to
+//! the "compilation wordlist".
+//!
+//! A wordlist is identified ("wid") by its index in the dictionary. The words
+//! built into the VM are kept in FORTH_WORDLIST.
+
+use std::collections::HashMap;
+
+use super::{Definition, VirtualMachineError};
+
+/// Identifier of the wordlist the words built into the VM are kept in
+pub const FORTH_WORDLIST: usize = 0;
+
+/// Most wordlists the search order may hold at once. Forth-2012 requires at
+/// least eight.
+pub const SEARCH_ORDER_SIZE: usize = 16;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Wordlist {
+
+    /// Name of the wordlist, if it was given one (e.g. by VOCABULARY)
+    pub name: Option<String>,
+
+    /// Words are mapped to the execution token of their definition
+    pub words: HashMap<String, usize>,
+
+}
+
+#[derive(Debug)]
+pub struct Dictionary {
+
+    /// Every wordlist, indexed by wordlist identifier
+    pub wordlists: Vec<Wordlist>,
+
+    /// Identifiers of the wordlists searched for words, the first searched
+    /// last (i.e. at the "top" of the search order)
+    pub search_order: Vec<usize>,
+
+    /// Identifier of the wordlist new definitions are added to
+    pub current: usize,
+
+}
+
+impl Default for Dictionary {
+    fn default() -> Self {
+        return Dictionary {
+            wordlists: vec![
+                Wordlist {
+                    name: Some(String::from("FORTH")),
+                    words: HashMap::new(),
+                },
+            ],
+            search_order: vec![FORTH_WORDLIST],
+            current: FORTH_WORDLIST,
+        };
+    }
+}
+
+impl Dictionary {
+
+    /// Execution token of the word with the given name, found by searching
+    /// the wordlists of the search order in turn
+    pub fn find(&self, name: &str) -> Option<usize> {
+        return self.search_order.iter().rev().find_map(|wid| {
+            self.wordlists[*wid].words.get(name).copied()
+        });
+    }
+
+    /// Execution token of the word with the given name in the given wordlist
+    pub fn search(&self, wid: usize, name: &str) -> Option<usize> {
+        return self.wordlists[wid].words.get(name).copied();
+    }
+
+    /// Add a word to the compilation wordlist, shadowing any word of the same
+    /// name in the wordlists searched after it. Returns the execution token of
+    /// the word of the same name it replaces in the compilation wordlist, if any.
+    pub fn insert(&mut self, name: String, xt: usize) -> Option<usize> {
+        return self.wordlists[self.current].words.insert(name, xt);
+    }
+
+    /// Rebuild every wordlist from the given definitions, as is needed after
+    /// some are forgotten. Later definitions shadow earlier ones of the same
+    /// name, just as when they were first defined.
+    pub fn rebuild(&mut self, definitions: &[Definition]) {
+        for wordlist in self.wordlists.iter_mut() {
+            wordlist.words.clear();
+        }
+        for (xt, definition) in definitions.iter().enumerate() {
+            if let Some(wordlist) = definition.wordlist.and_then(|wid| self.wordlists.get_mut(wid)) {
+                wordlist.words.insert(definition.name.clone(), xt);
+            }
+        }
+    }
+
+    /// Create a new, empty wordlist, returning its identifier
+    pub fn create(&mut self, name: Option<String>) -> usize {
+        self.wordlists.push(
+            Wordlist {
+                name,
+                words: HashMap::new(),
+            }
+        );
+        return self.wordlists.len() - 1;
+    }
+
+    /// Translate a wordlist identifier from the data stack, which must refer
+    /// to a wordlist that's been created
+    pub fn wordlist(&self, wid: i64) -> Result<usize, VirtualMachineError> {
+        if wid < 0 || wid as usize >= self.wordlists.len() {
+            return Result::Err(
+                VirtualMachineError {
+                    msg: format!("invalid wordlist: {}", wid),
+                    ..VirtualMachineError::default()
+                }
+            );
+        }
+        return Result::Ok(wid as usize);
+    }
+
+    /// Identifier of the wordlist searched first, as replaced by executing a
+    /// vocabulary (e.g. FORTH) and duplicated by ALSO
+    pub fn top(&mut self) -> Result<&mut usize, VirtualMachineError> {
+        return self.search_order.last_mut().ok_or_else(|| {
+            VirtualMachineError {
+                msg: String::from("search order underflow"),
+                ..VirtualMachineError::default()
+            }
+        });
+    }
+
+    /// Replace the search order with the given wordlists, the first searched
+    /// last
+    pub fn set_order(&mut self, search_order: Vec<usize>) -> Result<(), VirtualMachineError> {
+        if search_order.len() > SEARCH_ORDER_SIZE {
+            return Result::Err(
+                VirtualMachineError {
+                    msg: String::from("search order overflow"),
+                    ..VirtualMachineError::default()
+                }
+            );
+        }
+        self.search_order = search_order;
+        return Result::Ok(());
+    }
+
+    /// Name of the given wordlist as displayed by ORDER. Wordlists without a
+    /// name are displayed by identifier.
+    pub fn name(&self, wid: usize) -> String {
+        return match &self.wordlists[wid].name {
+            Some(name) => name.clone(),
+            None => wid.to_string(),
+        };
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dictionary_test_search_order() {
+        let mut dictionary: Dictionary = Dictionary::default();
+        dictionary.insert(String::from("word"), 1);
+        assert_eq!(dictionary.find("word"), Some(1));
+
+        // words in the wordlist searched first shadow the others
+        let wid: usize = dictionary.create(None);
+        dictionary.current = wid;
+        dictionary.insert(String::from("word"), 2);
+        assert_eq!(dictionary.find("word"), Some(1));
+        assert!(dictionary.set_order(vec![FORTH_WORDLIST, wid]).is_ok());
+        assert_eq!(dictionary.find("word"), Some(2));
+        assert_eq!(dictionary.search(FORTH_WORDLIST, "word"), Some(1));
+        assert_eq!(dictionary.search(wid, "word"), Some(2));
+        assert_eq!(dictionary.name(FORTH_WORDLIST), "FORTH");
+        assert_eq!(dictionary.name(wid), "1");
+
+        // nothing is found with an empty search order
+        assert!(dictionary.set_order(vec![]).is_ok());
+        assert_eq!(dictionary.find("word"), None);
+        assert!(dictionary.top().is_err());
+
+        // case:  wordlists must exist, and the search order must fit
+        assert!(dictionary.wordlist(wid as i64).is_ok());
+        assert!(dictionary.wordlist(2).is_err());
+        assert!(dictionary.wordlist(-1).is_err());
+        assert!(dictionary.set_order(vec![FORTH_WORDLIST; SEARCH_ORDER_SIZE]).is_ok());
+        assert!(dictionary.set_order(vec![FORTH_WORDLIST; SEARCH_ORDER_SIZE + 1]).is_err());
+    }
+
+}
@@ -0,0 +1,430 @@
+//! Files
+//!
+//! Forth programs refer to the files they've opened (e.g. via OPEN-FILE) by a
+//! file identifier ("fileid"), which is handed out by the VM. Identifiers are
+//! never reused while the VM lives, so a stale one can't refer to a file that
+//! was opened later. Zero is never an identifier.
+//!
+//! File words report failure by returning an I/O result code ("ior") rather
+//! than raising an error. Zero means success; otherwise the code is the THROW
+//! code Forth-2012 assigns to the failure.
+//!
+//! The VM also keeps track of the source files being interpreted (e.g. via
+//! INCLUDED), so that paths given by an included file are found relative to
+//! it, and of every file that's been included, so REQUIRED includes a file
+//! only once.
+//!
+//! Every file a Forth program touches is checked against the VM's policy
+//! first. A host running programs it can't trust (e.g. snippets submitted by
+//! users) can restrict them to reading or writing files within particular
+//! directories, or deny them file access altogether. Violations fail like any
+//! other file operation, with IOR_FAILURE. Files are checked again once
+//! they're open, so a path can't be switched (e.g. for a symbolic link) after
+//! it's been checked, and files are only ever created where nothing exists
+//! yet, so a dangling symbolic link can't lead a new file out of a directory.
+
+use std::collections::{HashMap, HashSet};
+use std::fs::{canonicalize, remove_file, rename, File, OpenOptions};
+use std::io::{self, ErrorKind, Read};
+use std::path::{Path, PathBuf};
+
+/// File access method ("fam") for reading, as given by R/O
+pub const READ_ONLY: i64 = 1;
+
+/// File access method for writing, as given by W/O
+pub const WRITE_ONLY: i64 = 2;
+
+/// File access method for reading and writing, as given by R/W
+pub const READ_WRITE: i64 = READ_ONLY | WRITE_ONLY;
+
+/// Bit set in a file access method by BIN. Files are always accessed as
+/// binary, so it's ignored.
+pub const BINARY: i64 = 4;
+
+/// I/O result code of a failure to find a file ("non-existent file")
+pub const IOR_NOT_FOUND: i64 = -38;
+
+/// I/O result code of any other failure ("file I/O exception")
+pub const IOR_FAILURE: i64 = -37;
+
+/// Most source files that may be being interpreted at once. Files including
+/// themselves would otherwise recurse until the process runs out of stack.
+pub const INCLUDE_DEPTH: usize = 32;
+
+/// Translate the error of a failed file operation into an I/O result code
+pub fn ior(err: &io::Error) -> i64 {
+    return match err.kind() {
+        ErrorKind::NotFound => IOR_NOT_FOUND,
+        _ => IOR_FAILURE,
+    };
+}
+
+/// How much access a Forth program has to a file, from least to most
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+pub enum Access {
+
+    /// The file can't be opened, included, or otherwise touched
+    NONE,
+
+    /// The file can be opened for reading, and included
+    READ,
+
+    /// The file can also be written, created, renamed, and deleted
+    READ_WRITE,
+
+}
+
+/// Which files a Forth program may access ("capability policy")
+#[derive(Clone, Debug, PartialEq)]
+pub struct Policy {
+
+    /// Access to files outside of the directories given access
+    pub access: Access,
+
+    /// Canonical paths of directories, each with the access given to the
+    /// files within them (at any depth). The most specific directory applies.
+    directories: Vec<(PathBuf, Access)>,
+
+}
+
+/// Unless the host says otherwise, file access is unrestricted
+impl Default for Policy {
+    fn default() -> Self {
+        return Policy {
+            access: Access::READ_WRITE,
+            directories: Vec::new(),
+        };
+    }
+}
+
+impl Policy {
+
+    /// No file may be accessed, except within directories given access later
+    pub fn sandboxed() -> Self {
+        return Policy {
+            access: Access::NONE,
+            directories: Vec::new(),
+        };
+    }
+
+    /// Any file may be read, but not written
+    pub fn read_only() -> Self {
+        return Policy {
+            access: Access::READ,
+            directories: Vec::new(),
+        };
+    }
+
+    /// Give the files within a directory the given access. The directory must
+    /// exist.
+    pub fn allow(&mut self, directory: &Path, access: Access) -> io::Result<()> {
+        self.directories.push((canonicalize(directory)?, access));
+        return Result::Ok(());
+    }
+
+    /// Access given to the file at the given path. Paths are compared once
+    /// symbolic links and ".." are resolved, so they can't be used to escape
+    /// a directory.
+    pub fn access(&self, path: &Path) -> Access {
+        let path: PathBuf = match absolute(path) {
+            Some(path) => path,
+            None => return self.access,
+        };
+        return self.directories.iter()
+            .filter(|(directory, _)| path.starts_with(directory))
+            .max_by_key(|(directory, _)| directory.components().count())
+            .map_or(self.access, |(_, access)| *access);
+    }
+
+    /// Check that the file at the given path may be accessed as requested
+    pub fn check(&self, path: &Path, access: Access) -> io::Result<()> {
+        if self.access(path) < access {
+            return Result::Err(denied());
+        }
+        return Result::Ok(());
+    }
+
+    /// Check that a file opened by the given path may be accessed as
+    /// requested, now that the path leads to it, and that it still does
+    fn verify(&self, path: &Path, file: &File, access: Access) -> io::Result<()> {
+        let path: PathBuf = canonicalize(path)?;
+        self.check(&path, access)?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::MetadataExt;
+            let (opened, found) = (file.metadata()?, path.metadata()?);
+            if (opened.dev(), opened.ino()) != (found.dev(), found.ino()) {
+                return Result::Err(denied());
+            }
+        }
+        #[cfg(not(unix))]
+        let _ = file;
+        return Result::Ok(());
+    }
+
+    /// Open the file at the given path if it may be accessed as requested,
+    /// creating it if `create` is set (or truncating it, if it exists)
+    fn open(&self, path: &Path, options: &OpenOptions, access: Access, create: bool) -> io::Result<File> {
+        self.check(path, access)?;
+        if create {
+            // Nothing may be in the way of a new file, not even a symbolic link
+            match options.clone().create_new(true).open(path) {
+                Result::Ok(file) => {
+                    return match self.verify(path, &file, access) {
+                        Result::Ok(_) => Result::Ok(file),
+                        Result::Err(err) => {
+                            drop(file);
+                            let _ = remove_file(path);
+                            Result::Err(err)
+                        },
+                    };
+                },
+                Result::Err(err) if err.kind() == ErrorKind::AlreadyExists => (),
+                Result::Err(err) => return Result::Err(err),
+            }
+        }
+        let file: File = options.open(path)?;
+        self.verify(path, &file, access)?;
+        if create {
+            file.set_len(0)?;
+        }
+        return Result::Ok(file);
+    }
+
+}
+
+fn denied() -> io::Error {
+    return io::Error::new(ErrorKind::PermissionDenied, "access denied by sandbox");
+}
+
+/// Canonical path of a file, which need not exist as long as its directory
+/// does (e.g. a file about to be created)
+fn absolute(path: &Path) -> Option<PathBuf> {
+    if let Result::Ok(path) = canonicalize(path) {
+        return Some(path);
+    }
+    let directory: &Path = match path.parent()? {
+        parent if parent.as_os_str().is_empty() => Path::new("."),
+        parent => parent,
+    };
+    return Some(canonicalize(directory).ok()?.join(path.file_name()?));
+}
+
+#[derive(Debug, Default)]
+pub struct Files {
+
+    /// Which files may be accessed
+    pub policy: Policy,
+
+    /// Files opened by the program, by file identifier
+    open: HashMap<i64, File>,
+
+    /// Identifier of the last file opened
+    last_id: i64,
+
+    /// Source files being interpreted, innermost last ("include stack")
+    pub including: Vec<PathBuf>,
+
+    /// Canonical path of every source file that's been included
+    pub included: HashSet<PathBuf>,
+
+}
+
+impl Files {
+
+    /// Open the file at the given path with the given access method, creating
+    /// it (or truncating it, if it exists) if `create` is set. Returns the new
+    /// file's identifier.
+    pub fn open(&mut self, path: &Path, fam: i64, create: bool) -> io::Result<i64> {
+        let fam: i64 = fam & !BINARY;
+        if !(READ_ONLY..=READ_WRITE).contains(&fam) {
+            return Result::Err(io::Error::new(ErrorKind::InvalidInput, "invalid file access method"));
+        }
+        let access: Access = match fam & WRITE_ONLY != 0 || create {
+            true => Access::READ_WRITE,
+            false => Access::READ,
+        };
+        let mut options: OpenOptions = OpenOptions::new();
+        options.read(fam & READ_ONLY != 0).write(fam & WRITE_ONLY != 0 || create);
+        let file: File = self.policy.open(path, &options, access, create)?;
+        self.last_id += 1;
+        self.open.insert(self.last_id, file);
+        return Result::Ok(self.last_id);
+    }
+
+    /// The open file with the given identifier
+    pub fn get(&mut self, fileid: i64) -> io::Result<&mut File> {
+        return self.open.get_mut(&fileid).ok_or_else(|| {
+            io::Error::new(ErrorKind::InvalidInput, format!("invalid fileid: {}", fileid))
+        });
+    }
+
+    /// Close the file with the given identifier
+    pub fn close(&mut self, fileid: i64) -> io::Result<()> {
+        self.get(fileid)?;
+        self.open.remove(&fileid);
+        return Result::Ok(());
+    }
+
+    /// Delete the file at the given path
+    pub fn delete(&self, path: &Path) -> io::Result<()> {
+        self.policy.check(path, Access::READ_WRITE)?;
+        return remove_file(path);
+    }
+
+    /// Rename the file at the given path
+    pub fn rename(&self, from: &Path, to: &Path) -> io::Result<()> {
+        self.policy.check(from, Access::READ_WRITE)?;
+        self.policy.check(to, Access::READ_WRITE)?;
+        return rename(from, to);
+    }
+
+    /// Read the source file at the given path (e.g. for INCLUDED)
+    pub fn read(&self, path: &Path) -> io::Result<String> {
+        let mut file: File = self.policy.open(path, OpenOptions::new().read(true), Access::READ, false)?;
+        let mut source: String = String::new();
+        file.read_to_string(&mut source)?;
+        return Result::Ok(source);
+    }
+
+    /// Where a source file named by the program is found. Relative paths are
+    /// relative to the directory of the source file being interpreted, if
+    /// any, or else the working directory.
+    pub fn resolve(&self, name: &str) -> PathBuf {
+        let path: &Path = Path::new(name);
+        return match self.including.last().and_then(|including| including.parent()) {
+            Some(directory) if path.is_relative() => directory.join(path),
+            _ => path.to_path_buf(),
+        };
+    }
+
+    /// Record that the given source file has been included
+    pub fn record(&mut self, path: &Path) {
+        let path: PathBuf = canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        self.included.insert(path);
+    }
+
+    /// Whether the given source file has been included
+    pub fn is_included(&self, path: &Path) -> bool {
+        let path: PathBuf = canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        return self.included.contains(&path);
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::env::temp_dir;
+    use std::fs::{create_dir_all, remove_dir_all};
+    use std::io::Write;
+
+    #[test]
+    fn files_test_open_close() {
+        let directory: PathBuf = temp_dir().join(format!("oxforth-files-test-{}", std::process::id()));
+        create_dir_all(&directory).unwrap();
+        let path: PathBuf = directory.join("data.txt");
+        let mut files: Files = Files::default();
+
+        // case:  files must exist unless they're created
+        assert_eq!(files.open(&path, READ_ONLY, false).map_err(|err| ior(&err)), Err(IOR_NOT_FOUND));
+        let fileid: i64 = files.open(&path, WRITE_ONLY | BINARY, true).unwrap();
+        assert!(files.get(fileid).unwrap().write_all(b"data").is_ok());
+        assert!(files.close(fileid).is_ok());
+
+        // identifiers aren't reused
+        let reopened: i64 = files.open(&path, READ_WRITE, false).unwrap();
+        assert_ne!(reopened, fileid);
+        assert_eq!(files.get(reopened).unwrap().metadata().unwrap().len(), 4);
+        assert!(files.get(fileid).is_err());
+        assert!(files.close(fileid).is_err());
+        assert!(files.get(0).is_err());
+        assert!(files.open(&path, 0, false).is_err());
+
+        // creating a file that exists truncates it
+        let created: i64 = files.open(&path, READ_WRITE, true).unwrap();
+        assert_eq!(files.get(created).unwrap().metadata().unwrap().len(), 0);
+
+        remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn files_test_resolve() {
+        let mut files: Files = Files::default();
+        assert_eq!(files.resolve("lib.fs"), PathBuf::from("lib.fs"));
+        files.including.push(PathBuf::from("src/main.fs"));
+        assert_eq!(files.resolve("lib.fs"), PathBuf::from("src/lib.fs"));
+        assert_eq!(files.resolve("/lib.fs"), PathBuf::from("/lib.fs"));
+        files.including.push(PathBuf::from("main.fs"));
+        assert_eq!(files.resolve("lib.fs"), PathBuf::from("lib.fs"));
+
+        assert!(!files.is_included(Path::new("src/lib.fs")));
+        files.record(Path::new("src/lib.fs"));
+        assert!(files.is_included(Path::new("src/lib.fs")));
+    }
+
+    #[test]
+    fn files_test_policy() {
+        let directory: PathBuf = temp_dir().join(format!("oxforth-policy-test-{}", std::process::id()));
+        create_dir_all(directory.join("public/private")).unwrap();
+        let public: PathBuf = directory.join("public/data.txt");
+        let private: PathBuf = directory.join("public/private/data.txt");
+        let outside: PathBuf = directory.join("data.txt");
+        let mut files: Files = Files::default();
+
+        // access is unrestricted by default
+        assert!(files.open(&outside, WRITE_ONLY, true).is_ok());
+        assert!(files.open(&public, WRITE_ONLY, true).is_ok());
+        assert!(files.open(&private, WRITE_ONLY, true).is_ok());
+
+        // the most specific directory applies
+        files.policy = Policy::sandboxed();
+        assert!(files.policy.allow(&directory.join("public"), Access::READ_WRITE).is_ok());
+        assert!(files.policy.allow(&directory.join("public/private"), Access::READ).is_ok());
+        assert!(files.policy.allow(&directory.join("missing"), Access::READ).is_err());
+        assert_eq!(files.policy.access(&outside), Access::NONE);
+        assert_eq!(files.policy.access(&public), Access::READ_WRITE);
+        assert_eq!(files.policy.access(&directory.join("public/new.txt")), Access::READ_WRITE);
+        assert_eq!(files.policy.access(&private), Access::READ);
+        assert_eq!(files.policy.access(&directory.join("public/../data.txt")), Access::NONE);
+
+        // violations fail like any other file operation
+        assert_eq!(files.open(&outside, READ_ONLY, false).map_err(|err| ior(&err)), Err(IOR_FAILURE));
+        assert!(files.read(&outside).is_err());
+        assert!(files.open(&private, READ_ONLY, false).is_ok());
+        assert!(files.read(&private).is_ok());
+        assert!(files.open(&private, READ_WRITE, false).is_err());
+        assert!(files.rename(&public, &private).is_err());
+        assert!(files.delete(&private).is_err());
+        assert!(files.rename(&public, &directory.join("public/renamed.txt")).is_ok());
+        assert!(files.delete(&directory.join("public/renamed.txt")).is_ok());
+
+        files.policy = Policy::read_only();
+        assert!(files.read(&outside).is_ok());
+        assert!(files.delete(&outside).is_err());
+
+        // symbolic links are checked where they lead, even if nothing's there yet
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::symlink;
+            files.policy = Policy::sandboxed();
+            assert!(files.policy.allow(&directory.join("public"), Access::READ_WRITE).is_ok());
+            let escape: PathBuf = directory.join("public/escape.txt");
+            symlink(directory.join("escaped.txt"), &escape).unwrap();
+            assert!(files.open(&escape, WRITE_ONLY, true).is_err());
+            assert!(!directory.join("escaped.txt").exists());
+            remove_file(&escape).unwrap();
+            symlink(&outside, &escape).unwrap();
+            assert!(files.open(&escape, WRITE_ONLY, true).is_err());
+            assert!(files.read(&escape).is_err());
+            assert!(outside.metadata().is_ok());
+            assert!(files.open(&public, WRITE_ONLY, true).is_ok());
+            assert!(files.open(&directory.join("public/new.txt"), WRITE_ONLY, true).is_ok());
+        }
+
+        remove_dir_all(&directory).unwrap();
+    }
+
+}
@@ -1,526 +1,4778 @@
 //! Interpretation
 
 use std::cmp;
-use std::process;
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::{Duration, Instant};
 
-use super::{Data, VM};
+use super::{Data, Definition, Enclosing, Frame, Limit, LoopControl, Marker, Outcome, Source, VM};
 use super::VirtualMachineError;
+use super::dictionary::FORTH_WORDLIST;
+use super::native::Native;
+use super::files::{ior, BINARY, INCLUDE_DEPTH, READ_ONLY, READ_WRITE, WRITE_ONLY};
+use super::memory::{BASE_ADDR, FLOAT_SIZE, MAX_PRECISION, PRECISION_ADDR, SFLOAT_SIZE, STRING_BUFFER_SIZE};
 
 use super::super::compiler::parser::Operation;
+use super::super::compiler::parser::parse;
+use super::super::compiler::scanner::{parse_delimited, parse_escaped, parse_float, parse_name, scan, scan_remaining};
 
-/// Common cast from STRING DataType to an i64, with error-checking
-fn int_from_stack<'vm>(vm: &'vm mut VM) -> Result<i64, VirtualMachineError> {
+/// Keyboard events that aren't characters (e.g. cursor keys, which send an
+/// escape sequence) are reported by EKEY as the bytes following the escape
+/// character, packed into a cell with this bit set.
+pub const EKEY_SEQUENCE: i64 = 1 << 62;
+
+/// Escape character, which begins the sequences sent by special keys
+const ESC: u8 = 0x1b;
+
+/// Most spaces displayed at once as padding (e.g. by SPACES or .R), so that a
+/// program can't tie up the VM displaying them
+const MAX_PADDING: usize = 1 << 16;
+
+/// Destination of a branch that's been compiled, but not yet resolved (e.g.
+/// the branch compiled by IF, until its THEN is compiled)
+const UNRESOLVED: usize = usize::MAX;
+
+/// Pop a number off the stack, with error-checking
+fn int_from_stack(vm: &mut VM) -> Result<i64, VirtualMachineError> {
     let o: Option<Data> = vm.data_stack.pop();
     if o.is_none() {
         return Result::Err(
             VirtualMachineError {
                 msg: String::from("stack underflow"),
+                ..VirtualMachineError::default()
             }
         );
     }
-    let d: Data = o.unwrap();
-    if let Data::NUMBER(n) = d {
-        return Result::Ok(n);
-    }
-    return Result::Err(
-        VirtualMachineError {
-            msg: String::from("not a number"),
-        }
-    );
+    let Data::NUMBER(n) = o.unwrap();
+    return Result::Ok(n);
 }
 
-/// Pop two numbers off the stack, cast to i64, and return them.
-fn two_ints_from_stack<'vm>(vm: &'vm mut VM) -> Result<(i64, i64), VirtualMachineError> {
+/// Pop two numbers off the stack and return them in the order they were pushed.
+fn two_ints_from_stack(vm: &mut VM) -> Result<(i64, i64), VirtualMachineError> {
     let x1: Option<Data> = vm.data_stack.pop();
     let x2: Option<Data> = vm.data_stack.pop();
     if x1.is_none() || x2.is_none() {
         return Result::Err(
             VirtualMachineError {
                 msg: String::from("stack underflow"),
+                ..VirtualMachineError::default()
             }
         );
     }
-    let x1: Data = x1.unwrap();
-    let x2: Data = x2.unwrap();
-    if let (Data::NUMBER(x1), Data::NUMBER(x2)) = (x1, x2) {
-        return Result::Ok((x2, x1));
+    let Data::NUMBER(x1) = x1.unwrap();
+    let Data::NUMBER(x2) = x2.unwrap();
+    return Result::Ok((x2, x1));
+}
+
+/// Pop three numbers off the stack and return them in the order they were pushed.
+fn three_ints_from_stack(vm: &mut VM) -> Result<(i64, i64, i64), VirtualMachineError> {
+    if vm.data_stack.len() < 3 {
+        return Result::Err(
+            VirtualMachineError {
+                msg: String::from("stack underflow"),
+                ..VirtualMachineError::default()
+            }
+        );
     }
-    return Result::Err(
-        VirtualMachineError {
-            msg: String::from("refusing cast: string->number"),
+    let x3: i64 = int_from_stack(vm)?;
+    let (x1, x2): (i64, i64) = two_ints_from_stack(vm)?;
+    return Result::Ok((x1, x2, x3));
+}
+
+/// Pop four numbers off the stack and return them in the order they were pushed.
+fn four_ints_from_stack(vm: &mut VM) -> Result<(i64, i64, i64, i64), VirtualMachineError> {
+    if vm.data_stack.len() < 4 {
+        return Result::Err(
+            VirtualMachineError {
+                msg: String::from("stack underflow"),
+                ..VirtualMachineError::default()
+            }
+        );
+    }
+    let (x3, x4): (i64, i64) = two_ints_from_stack(vm)?;
+    let (x1, x2): (i64, i64) = two_ints_from_stack(vm)?;
+    return Result::Ok((x1, x2, x3, x4));
+}
+
+/// Pop an unsigned double-cell number off the stack. The most significant cell
+/// is on top.
+fn double_from_stack(vm: &mut VM) -> Result<u128, VirtualMachineError> {
+    let (low, high): (i64, i64) = two_ints_from_stack(vm)?;
+    return Result::Ok(((high as u64 as u128) << 64) | low as u64 as u128);
+}
+
+/// Push an unsigned double-cell number onto the stack.
+fn double_to_stack(vm: &mut VM, ud: u128) {
+    vm.data_stack.push(Data::NUMBER(ud as u64 as i64));
+    vm.data_stack.push(Data::NUMBER((ud >> 64) as u64 as i64));
+}
+
+/// Common error for operations on floats missing from the floating-point stack
+fn float_stack_underflow() -> VirtualMachineError {
+    return VirtualMachineError {
+        msg: String::from("floating-point stack underflow"),
+        ..VirtualMachineError::default()
+    };
+}
+
+/// Pop a float off the floating-point stack, with error-checking
+fn float_from_stack(vm: &mut VM) -> Result<f64, VirtualMachineError> {
+    return vm.float_stack.pop().ok_or_else(float_stack_underflow);
+}
+
+/// Pop two floats off the floating-point stack and return them in the order
+/// they were pushed.
+fn two_floats_from_stack(vm: &mut VM) -> Result<(f64, f64), VirtualMachineError> {
+    if vm.float_stack.len() < 2 {
+        return Result::Err(float_stack_underflow());
+    }
+    let r2: f64 = vm.float_stack.pop().unwrap();
+    let r1: f64 = vm.float_stack.pop().unwrap();
+    return Result::Ok((r1, r2));
+}
+
+/// Pop three floats off the floating-point stack and return them in the order
+/// they were pushed.
+fn three_floats_from_stack(vm: &mut VM) -> Result<(f64, f64, f64), VirtualMachineError> {
+    if vm.float_stack.len() < 3 {
+        return Result::Err(float_stack_underflow());
+    }
+    let r3: f64 = vm.float_stack.pop().unwrap();
+    let (r1, r2): (f64, f64) = two_floats_from_stack(vm)?;
+    return Result::Ok((r1, r2, r3));
+}
+
+/// ( F: r1 -- r2 ) where r2 is the given function of r1
+fn float_function(vm: &mut VM, function: fn(f64) -> f64) -> Result<(), VirtualMachineError> {
+    let r1: f64 = float_from_stack(vm)?;
+    vm.float_stack.push(function(r1));
+    return Result::Ok(());
+}
+
+/// ( F: r1 r2 -- r3 ) where r3 is the given function of r1 and r2
+fn float_operator(vm: &mut VM, operator: fn(f64, f64) -> f64) -> Result<(), VirtualMachineError> {
+    let (r1, r2): (f64, f64) = two_floats_from_stack(vm)?;
+    vm.float_stack.push(operator(r1, r2));
+    return Result::Ok(());
+}
+
+/// The `digits` most significant decimal digits of the magnitude of a finite
+/// float, and the exponent n such that the magnitude is 0.digits times ten to
+/// the power n, as given by REPRESENT
+fn represent(r: f64, digits: usize) -> (String, i64) {
+    let scientific: String = format!("{:.*e}", cmp::max(digits, 1) - 1, r.abs());
+    let (significand, exponent): (&str, &str) = scientific.split_once('e').unwrap();
+    let mut significand: String = significand.replace('.', "");
+    significand.truncate(digits);
+    return (significand, exponent.parse::<i64>().unwrap() + 1);
+}
+
+/// Text of a float as displayed by F., FS. and FE. Floats are displayed with
+/// PRECISION significant digits, in fixed-point notation if `notation` is
+/// None, or otherwise with an exponent that's a multiple of the given number
+/// (i.e. 1 for scientific notation, or 3 for engineering notation).
+fn float_to_string(vm: &VM, r: f64, notation: Option<i64>) -> Result<String, VirtualMachineError> {
+    if !r.is_finite() {
+        return Result::Ok(r.to_string());
+    }
+    let (mut digits, n): (String, i64) = represent(r, vm.memory.precision()?);
+    let sign: &str = if r < 0.0 { "-" } else { "" };
+    return Result::Ok(match notation {
+        None => {
+            let digits: &str = match digits.trim_end_matches('0') {
+                "" => "0",
+                trimmed => trimmed,
+            };
+            let length: i64 = digits.len() as i64;
+            let n: i64 = if r == 0.0 { 1 } else { n };
+            if n <= 0 {
+                format!("{}0.{}{}", sign, "0".repeat(-n as usize), digits)
+            } else if n >= length {
+                format!("{}{}{}.", sign, digits, "0".repeat((n - length) as usize))
+            } else {
+                format!("{}{}.{}", sign, &digits[..n as usize], &digits[n as usize..])
+            }
+        },
+        Some(multiple) => {
+            let exponent: i64 = if r == 0.0 { 0 } else { n - 1 };
+            let shift: usize = exponent.rem_euclid(multiple) as usize;
+            while digits.len() < shift + 1 {
+                digits.push('0');
+            }
+            let (integer, fraction): (&str, &str) = digits.split_at(shift + 1);
+            format!("{}{}.{}E{}", sign, integer, fraction, exponent - shift as i64)
+        },
+    });
+}
+
+/// The first address at or after `addr` that's a multiple of `size`. Like the
+/// rest of address arithmetic, this wraps around.
+fn aligned(addr: i64, size: usize) -> i64 {
+    let size: i64 = size as i64;
+    return addr.wrapping_add(size - 1).div_euclid(size).wrapping_mul(size);
+}
+
+/// Translate a number less than the radix into its digit: 0-9, then A-Z.
+fn digit(n: u32) -> u8 {
+    return char::from_digit(n, 36).unwrap().to_ascii_uppercase() as u8;
+}
+
+/// Text of a signed number in the current BASE, as displayed by "."
+fn number_to_string(vm: &VM, n: i64) -> Result<String, VirtualMachineError> {
+    let base: u128 = vm.memory.base()? as u128;
+    let mut u: u128 = (n as i128).unsigned_abs();
+    let mut digits: Vec<u8> = Vec::new();
+    loop {
+        digits.push(digit((u % base) as u32));
+        u /= base;
+        if u == 0 {
+            break;
         }
-    );
+    }
+    if n < 0 {
+        digits.push(b'-');
+    }
+    digits.reverse();
+    return Result::Ok(String::from_utf8(digits).unwrap());
 }
 
-pub fn execute(vm: &mut VM) -> Result<(), VirtualMachineError> {
-    while let Some(operation) = vm.operations.pop_front() {
-        match operation {
+/// ( ud1 -- ud2 ) as done by #
+fn hold_digit(vm: &mut VM, ud: u128) -> Result<u128, VirtualMachineError> {
+    let base: u128 = vm.memory.base()? as u128;
+    vm.memory.hold(&[digit((ud % base) as u32)])?;
+    return Result::Ok(ud / base);
+}
 
-            // Non-operational / internal test ops
-            Operation::NOP => {
-                continue;
-            },
-            Operation::NOP_INC => (),
+/// Write text to the VM's output sink
+fn display(vm: &mut VM, text: &[u8]) -> Result<(), VirtualMachineError> {
+    if let Err(err) = vm.output.write(text) {
+        return Result::Err(
+            VirtualMachineError {
+                msg: format!("output error: {}", err),
+                ..VirtualMachineError::default()
+            }
+        );
+    }
+    return Result::Ok(());
+}
 
-            // Core words that happen to be VM operations, in alphabetical order.
-            Operation::ABS => {
-                let n: i64 = int_from_stack(vm)?;
-                let n: i64 = n.abs();
-                vm.data_stack.push(Data::NUMBER(n));
-            },
-            Operation::ADD => {
-                let (n1, n2): (i64, i64) = two_ints_from_stack(vm)?;
-                let n3: i64 = n1 + n2;
-                vm.data_stack.push(
-                    Data::NUMBER(n3),
-                );
-            },
-            Operation::BYE => {
-                println!("It's time to say goodbye~");
-                process::exit(0);
-            },
-            Operation::CMP_EQ => {
-                let (n1, n2): (i64, i64) = two_ints_from_stack(vm)?;
-                let flag: bool = n1 == n2;
-                vm.data_stack.push(
-                    Data::NUMBER(flag as i64),  // todo: bool bits
-                );
-            },
-            Operation::CMP_GT => {
-                let (n1, n2): (i64, i64) = two_ints_from_stack(vm)?;
-                let flag: bool = n1 > n2;
-                vm.data_stack.push(
-                    Data::NUMBER(flag as i64),  // todo: bool bits
-                );
-            },
-            Operation::CMP_LT => {
-                let (n1, n2): (i64, i64) = two_ints_from_stack(vm)?;
-                let flag: bool = n1 < n2;
-                vm.data_stack.push(
-                    Data::NUMBER(flag as i64),  // todo: bool bits
-                );
-            },
-            Operation::CMP_NE => {
-                let (n1, n2): (i64, i64) = two_ints_from_stack(vm)?;
-                let flag: bool = n1 != n2;
-                vm.data_stack.push(
-                    Data::NUMBER(flag as i64),  // todo: bool bits
-                );
-            },
-            Operation::DIV => {
-                let (n1, n2): (i64, i64) = two_ints_from_stack(vm)?;
-                if n2 == 0 {
-                    return Result::Err(
-                        VirtualMachineError {
-                            msg: String::from("divisor cannot be zero"),
-                        }
-                    );
+/// Display `n` spaces (up to MAX_PADDING of them), a chunk at a time
+fn display_spaces(vm: &mut VM, n: i64) -> Result<(), VirtualMachineError> {
+    const SPACES: [u8; 256] = [b' '; 256];
+    let mut remaining: usize = cmp::min(cmp::max(n, 0) as usize, MAX_PADDING);
+    while remaining > 0 {
+        let chunk: usize = cmp::min(remaining, SPACES.len());
+        display(vm, &SPACES[..chunk])?;
+        remaining -= chunk;
+    }
+    return Result::Ok(());
+}
+
+/// Common translation of I/O errors raised by the VM's input source
+fn input_error(err: std::io::Error) -> VirtualMachineError {
+    return VirtualMachineError {
+        msg: format!("input error: {}", err),
+        ..VirtualMachineError::default()
+    };
+}
+
+/// Read one character from the VM's input source, as done by KEY
+fn read_key(vm: &mut VM) -> Result<u8, VirtualMachineError> {
+    match vm.input.read_key().map_err(input_error)? {
+        Some(char) => {
+            return Result::Ok(char);
+        },
+        None => {
+            return Result::Err(
+                VirtualMachineError {
+                    msg: String::from("end of input"),
+                    ..VirtualMachineError::default()
                 }
-                let n3: i64 = n1 / n2; // no floating-point yet
-                vm.data_stack.push(
-                    Data::NUMBER(n3),
-                );
-            },
-            Operation::DROP => {
-                let x: Option<Data> = vm.data_stack.pop();
-                if x.is_none() {
-                    return Result::Err(
-                        VirtualMachineError {
-                            msg: String::from("stack underflow"),
-                        }
-                    );
+            );
+        },
+    }
+}
+
+/// Read one keyboard event from the VM's input source, as done by EKEY. An
+/// escape character immediately followed by "[" or "O" begins a control
+/// sequence, which is read through its final character.
+fn read_event(vm: &mut VM) -> Result<i64, VirtualMachineError> {
+    let char: u8 = read_key(vm)?;
+    if char != ESC || !vm.input.key_available().map_err(input_error)? {
+        return Result::Ok(char as i64);
+    }
+    let mut sequence: Vec<u8> = vec![read_key(vm)?];
+    if sequence[0] == b'[' || sequence[0] == b'O' {
+        while sequence.len() < 7 && vm.input.key_available().map_err(input_error)? {
+            let char: u8 = read_key(vm)?;
+            sequence.push(char);
+            if (0x40..=0x7e).contains(&char) {
+                break;
+            }
+        }
+    }
+    let mut event: i64 = EKEY_SEQUENCE;
+    for (i, char) in sequence.iter().enumerate() {
+        event |= (*char as i64) << (8 * i);
+    }
+    return Result::Ok(event);
+}
+
+/// Parse a name from the parse area, which must not be empty
+fn expect_name(vm: &mut VM) -> Result<String, VirtualMachineError> {
+    let name: String = parse_name(vm);
+    if name.is_empty() {
+        return Result::Err(
+            VirtualMachineError {
+                msg: String::from("expected a name"),
+                ..VirtualMachineError::default()
+            }
+        );
+    }
+    return Result::Ok(name);
+}
+
+/// Parse a name from the parse area and find its execution token, as done by '
+fn parse_xt(vm: &mut VM) -> Result<usize, VirtualMachineError> {
+    let name: String = expect_name(vm)?;
+    return vm.find(&name).ok_or_else(|| {
+        VirtualMachineError {
+            msg: format!("undefined word: {}", name),
+            ..VirtualMachineError::default()
+        }
+    });
+}
+
+/// Check that an execution token taken from the data stack identifies a definition
+fn to_xt(vm: &VM, xt: i64) -> Result<usize, VirtualMachineError> {
+    match usize::try_from(xt) {
+        Ok(xt) if xt < vm.definitions.len() => {
+            return Result::Ok(xt);
+        },
+        _ => {
+            return Result::Err(
+                VirtualMachineError {
+                    msg: format!("invalid execution token: {}", xt),
+                    ..VirtualMachineError::default()
                 }
-            },
-            Operation::DUP => {
-                let x: Option<&Data> = vm.data_stack.last();
-                if x.is_none() {
-                    return Result::Err(
-                        VirtualMachineError {
-                            msg: String::from("stack underflow"),
-                        }
-                    );
+            );
+        },
+    }
+}
+
+/// The execution token the deferred word `xt` is set to execute, if any
+fn deferred_action(vm: &mut VM, xt: usize) -> Result<&mut Option<usize>, VirtualMachineError> {
+    let definition: Option<&Definition> = vm.definitions.get(xt);
+    if !matches!(definition.and_then(|definition| definition.body.first()), Some(Operation::DEFERRED(_))) {
+        return Result::Err(
+            VirtualMachineError {
+                msg: format!("not a deferred word: {}", definition.map_or("", |definition| definition.name.as_str())),
+                ..VirtualMachineError::default()
+            }
+        );
+    }
+    let Some(Operation::DEFERRED(action)) = vm.definitions[xt].body.first_mut() else { unreachable!() };
+    return Result::Ok(action);
+}
+
+/// Like deferred_action(), but it's an error for the deferred word not to be set
+fn deferred_action_set(vm: &mut VM, xt: usize) -> Result<usize, VirtualMachineError> {
+    match *deferred_action(vm, xt)? {
+        Some(action) => {
+            return Result::Ok(action);
+        },
+        None => {
+            return Result::Err(
+                VirtualMachineError {
+                    msg: format!("uninitialized deferred word: {}", vm.definitions[xt].name),
+                    ..VirtualMachineError::default()
                 }
-                let x: &Data = x.unwrap();
-                let x2: Data = x.clone();
-                vm.data_stack.push(x2);
-            },
-            Operation::MAX => {
-                let (n1, n2): (i64, i64) = two_ints_from_stack(vm)?;
-                let n3: i64 = cmp::max(n1, n2);
-                vm.data_stack.push(
-                    Data::NUMBER(n3),
-                );
-            },
-            Operation::MIN => {
-                let (n1, n2): (i64, i64) = two_ints_from_stack(vm)?;
-                let n3: i64 = cmp::min(n1, n2);
-                vm.data_stack.push(
-                    Data::NUMBER(n3),
-                );
+            );
+        },
+    }
+}
+
+/// Push a string from the parse area into a transient region of data space.
+fn push_transient_string(vm: &mut VM, string: &[u8]) -> Result<(), VirtualMachineError> {
+    let addr: usize = vm.memory.transient(string)?;
+    vm.data_stack.push(Data::NUMBER(addr as i64));
+    vm.data_stack.push(Data::NUMBER(string.len() as i64));
+    return Result::Ok(());
+}
+
+/// Push a command-line argument as a string. Arguments that don't fit in a
+/// transient region are allotted in data space instead.
+fn push_argument(vm: &mut VM, argument: String) -> Result<(), VirtualMachineError> {
+    let argument: Vec<u8> = argument.into_bytes();
+    if argument.len() <= STRING_BUFFER_SIZE {
+        return push_transient_string(vm, &argument);
+    }
+    let addr: i64 = store_string(vm, &argument)?;
+    vm.data_stack.push(Data::NUMBER(addr));
+    vm.data_stack.push(Data::NUMBER(argument.len() as i64));
+    return Result::Ok(());
+}
+
+/// Pop a string naming a file off the stack
+fn file_name_from_stack(vm: &mut VM) -> Result<String, VirtualMachineError> {
+    let (addr, length): (i64, i64) = two_ints_from_stack(vm)?;
+    let name: &[u8] = vm.memory.slice(addr, length)?;
+    return Result::Ok(String::from_utf8_lossy(name).into_owned());
+}
+
+/// Push the I/O result code of a file operation, which is zero if it succeeded
+fn push_ior<T>(vm: &mut VM, result: &io::Result<T>) {
+    let ior: i64 = match result {
+        Result::Ok(_) => 0,
+        Result::Err(err) => ior(err),
+    };
+    vm.data_stack.push(Data::NUMBER(ior));
+}
+
+/// ( c-addr u fam -- fileid ior ) as done by OPEN-FILE, or by CREATE-FILE if
+/// `create` is set
+fn open_file(vm: &mut VM, create: bool) -> Result<(), VirtualMachineError> {
+    let fam: i64 = int_from_stack(vm)?;
+    let name: String = file_name_from_stack(vm)?;
+    let result: io::Result<i64> = vm.files.open(Path::new(&name), fam, create);
+    vm.data_stack.push(Data::NUMBER(*result.as_ref().unwrap_or(&0)));
+    push_ior(vm, &result);
+    return Result::Ok(());
+}
+
+/// Read the next line of at most `length` characters from a file, as done by
+/// READ-LINE. The line terminator ("\n" or "\r\n") is consumed, but not
+/// returned. A longer line is returned `length` characters at a time. Returns
+/// None at the end of the file.
+fn read_line(file: &mut File, length: usize) -> io::Result<Option<Vec<u8>>> {
+    let position: u64 = file.stream_position()?;
+    let mut bytes: Vec<u8> = Vec::new();
+    (&mut *file).take(length as u64 + 2).read_to_end(&mut bytes)?;
+    if bytes.is_empty() {
+        return Result::Ok(None);
+    }
+    let (line, consumed): (&[u8], usize) = match bytes.iter().position(|char| *char == b'\n') {
+        Some(end) => {
+            let line: &[u8] = bytes[..end].strip_suffix(b"\r").unwrap_or(&bytes[..end]);
+            match line.len() <= length {
+                true => (line, end + 1),
+                false => (&bytes[..length], length),
+            }
+        },
+        None => {
+            let end: usize = cmp::min(bytes.len(), length);
+            (&bytes[..end], end)
+        },
+    };
+    file.seek(SeekFrom::Start(position + consumed as u64))?;
+    return Result::Ok(Some(line.to_vec()));
+}
+
+/// Replace each "%name%" in `text` with the text set for that name by REPLACES.
+/// "%%" becomes a single '%', and unknown names are passed through unchanged.
+/// Returns the result and the number of substitutions made.
+fn substitute(vm: &VM, text: &[u8]) -> (Vec<u8>, i64) {
+    let mut result: Vec<u8> = Vec::new();
+    let mut substitutions: i64 = 0;
+    let mut i: usize = 0;
+    while i < text.len() {
+        if text[i] != b'%' {
+            result.push(text[i]);
+            i += 1;
+            continue;
+        }
+        let name_length: Option<usize> = text[i + 1..].iter().position(|char| *char == b'%');
+        match name_length {
+            Some(0) => {
+                result.push(b'%');
+                i += 2;
             },
-            Operation::MOD => {
-                let (n1, n2): (i64, i64) = two_ints_from_stack(vm)?;
-                if n2 == 0 {
-                    return Result::Err(
-                        VirtualMachineError {
-                            msg: String::from("divisor cannot be zero"),
-                        }
-                    );
+            Some(length) => {
+                let name: String = String::from_utf8_lossy(&text[i + 1..i + 1 + length]).to_uppercase();
+                match vm.substitutions.get(&name) {
+                    Some(replacement) => {
+                        result.extend(replacement);
+                        substitutions += 1;
+                        i += length + 2;
+                    },
+                    None => {
+                        result.extend(&text[i..i + length + 2]);
+                        i += length + 2;
+                    },
                 }
-                let n3: i64 = n1.rem_euclid(n2);
-                vm.data_stack.push(
-                    Data::NUMBER(n3),
-                );
-            },
-            Operation::MUL => {
-                let (n1, n2): (i64, i64) = two_ints_from_stack(vm)?;
-                let n3: i64 = n1 * n2;
-                vm.data_stack.push(
-                    Data::NUMBER(n3),
-                );
             },
-            Operation::NEGATE => {
-                let n: i64 = int_from_stack(vm)?;
-                let n: i64 = n * -1;
-                vm.data_stack.push(Data::NUMBER(n));
-            },
-            Operation::SUB => {
-                let (n1, n2): (i64, i64) = two_ints_from_stack(vm)?;
-                let n3: i64 = n1 - n2;
-                vm.data_stack.push(
-                    Data::NUMBER(n3),
-                );
-            },
-            Operation::ZERO_EQ => {
-                let n: i64 = int_from_stack(vm)?;
-                let flag: bool = n == 0;
-                vm.data_stack.push(Data::NUMBER(flag as i64));  // todo: bool bits
+            None => {
+                result.extend(&text[i..]);
+                break;
             },
-            Operation::ZERO_GT => {
-                let n: i64 = int_from_stack(vm)?;
-                let flag: bool = n > 0;
-                vm.data_stack.push(Data::NUMBER(flag as i64));  // todo: bool bits
+        }
+    }
+    return (result, substitutions);
+}
+
+/// The next operation to apply: the next one of the innermost definition being
+/// executed, if any, or else the next one given by the outer interpreter.
+fn next_operation(vm: &mut VM) -> Option<Operation> {
+    if let Some(frame) = vm.return_stack.last_mut() {
+        let body: &[Operation] = &vm.definitions[frame.xt].body;
+        let operation: Operation = body.get(frame.ip).copied().unwrap_or(Operation::EXIT);
+        frame.ip += 1;
+        return Some(operation);
+    }
+    return vm.operations.pop_front();
+}
+
+/// Put back an operation taken by next_operation(), so that it's the next one
+/// applied
+fn unfetch(vm: &mut VM, operation: Operation) {
+    match vm.return_stack.last_mut() {
+        Some(frame) => frame.ip -= 1,
+        None => vm.operations.push_front(operation),
+    }
+}
+
+/// Put back an operation that exceeded one of the VM's limits, so that it's
+/// applied again if execution resumes
+fn retry(vm: &mut VM, operation: Operation, err: VirtualMachineError) -> VirtualMachineError {
+    if err.limit.is_some() {
+        unfetch(vm, operation);
+    }
+    return err;
+}
+
+/// Errors after input has been parsed can't be retried, even if a limit was
+/// exceeded, since the input is gone
+fn unretriable(err: VirtualMachineError) -> VirtualMachineError {
+    return VirtualMachineError {
+        limit: None,
+        ..err
+    };
+}
+
+/// Check that the stacks are within the VM's limits. They're checked between
+/// operations, so a program that exceeds a limit can be resumed from the next
+/// operation once the limit is raised.
+fn check_limits(vm: &VM) -> Result<(), VirtualMachineError> {
+    if let Some(limit) = vm.limits.data_stack {
+        if vm.data_stack.len() > limit {
+            return Result::Err(Limit::DATA_STACK.exceeded());
+        }
+        if vm.float_stack.len() > limit {
+            return Result::Err(Limit::FLOAT_STACK.exceeded());
+        }
+    }
+    if let Some(limit) = vm.limits.return_stack {
+        if vm.return_stack.len() > limit {
+            return Result::Err(Limit::RETURN_STACK.exceeded());
+        }
+    }
+    return Result::Ok(());
+}
+
+/// Begin executing the definition with the given execution token
+fn call(vm: &mut VM, xt: usize) -> Result<(), VirtualMachineError> {
+    if xt >= vm.definitions.len() {
+        return Result::Err(
+            VirtualMachineError {
+                msg: format!("invalid execution token: {}", xt),
+                ..VirtualMachineError::default()
+            }
+        );
+    }
+    vm.return_stack.push(Frame { xt, ..Frame::default() });
+    return Result::Ok(());
+}
+
+/// Begin executing the definition with the given execution token in place of
+/// the innermost definition being executed, reusing its frame.
+fn tail_call(vm: &mut VM, xt: usize) -> Result<(), VirtualMachineError> {
+    match vm.return_stack.last_mut() {
+        Some(frame) if xt < vm.definitions.len() => {
+            *frame = Frame { xt, ..Frame::default() };
+            return Result::Ok(());
+        },
+        _ => {
+            return call(vm, xt);
+        },
+    }
+}
+
+/// Continue executing the innermost definition at the given index of its body
+fn branch(vm: &mut VM, ip: usize) -> Result<(), VirtualMachineError> {
+    match vm.return_stack.last_mut() {
+        Some(frame) => {
+            frame.ip = ip;
+            return Result::Ok(());
+        },
+        None => {
+            return Result::Err(
+                VirtualMachineError {
+                    msg: String::from("branch outside of a definition"),
+                    ..VirtualMachineError::default()
+                }
+            );
+        },
+    }
+}
+
+/// Control parameters of the loops the innermost definition is in the midst of
+fn loops(vm: &mut VM) -> Result<&mut Vec<LoopControl>, VirtualMachineError> {
+    match vm.return_stack.last_mut() {
+        Some(frame) if !frame.loops.is_empty() => {
+            return Result::Ok(&mut frame.loops);
+        },
+        _ => {
+            return Result::Err(
+                VirtualMachineError {
+                    msg: String::from("loop control parameters unavailable"),
+                    ..VirtualMachineError::default()
+                }
+            );
+        },
+    }
+}
+
+/// Add n to the index of the innermost loop, as done by +LOOP. The loop repeats
+/// from `start` unless the index crossed the boundary between limit - 1 and
+/// limit, in which case the loop ends.
+fn loop_step(vm: &mut VM, n: i64, start: usize) -> Result<(), VirtualMachineError> {
+    let loops: &mut Vec<LoopControl> = loops(vm)?;
+    let control: &mut LoopControl = loops.last_mut().unwrap();
+    let before: i64 = control.index.wrapping_sub(control.limit);
+    let after: i64 = before.wrapping_add(n);
+    control.index = control.index.wrapping_add(n);
+    if (before ^ after) < 0 && (after ^ n) >= 0 {
+        loops.pop();
+        return Result::Ok(());
+    }
+    return branch(vm, start);
+}
+
+/// Whether the operation is a branch that's yet to be resolved
+fn is_unresolved(operation: &Operation) -> bool {
+    return matches!(
+        operation,
+        Operation::BRANCH(UNRESOLVED)
+            | Operation::BRANCH_IF_ZERO(UNRESOLVED)
+            | Operation::LOOP_ENTER(UNRESOLVED)
+            | Operation::LOOP_ENTER_IF_NE(UNRESOLVED)
+    );
+}
+
+/// Compile the end of the DO loop begun at `orig`, as done by LOOP and +LOOP.
+/// `next` gives the operation that repeats the loop from a given index.
+fn resolve_loop(vm: &mut VM, orig: i64, next: fn(usize) -> Operation) -> Result<(), VirtualMachineError> {
+    let xt: usize = vm.current_definition()?;
+    let body: &[Operation] = &vm.definitions[xt].body;
+    let do_sys: Option<usize> = usize::try_from(orig).ok().filter(|orig| {
+        matches!(
+            body.get(*orig),
+            Some(Operation::LOOP_ENTER(UNRESOLVED)) | Some(Operation::LOOP_ENTER_IF_NE(UNRESOLVED))
+        )
+    });
+    let orig: usize = match do_sys {
+        Some(orig) => orig,
+        None => {
+            return Result::Err(
+                VirtualMachineError {
+                    msg: String::from("unbalanced control structure"),
+                    ..VirtualMachineError::default()
+                }
+            );
+        },
+    };
+    vm.compile(next(orig + 1))?;
+    let body: &mut Vec<Operation> = &mut vm.definitions[xt].body;
+    let exit: usize = body.len();
+    match &mut body[orig] {
+        Operation::LOOP_ENTER(dest) | Operation::LOOP_ENTER_IF_NE(dest) => *dest = exit,
+        _ => unreachable!(),
+    }
+    return Result::Ok(());
+}
+
+/// Finish compiling the definition being compiled, as done by ; and ;]
+fn end_definition(vm: &mut VM) -> Result<usize, VirtualMachineError> {
+    let xt: usize = vm.current_definition()?;
+    vm.compile(Operation::EXIT)?;
+    let definition: &mut Definition = &mut vm.definitions[xt];
+    if definition.body.iter().any(is_unresolved) {
+        return Result::Err(
+            VirtualMachineError {
+                msg: String::from("unbalanced control structure"),
+                ..VirtualMachineError::default()
+            }
+        );
+    }
+    eliminate_tail_calls(&mut definition.body);
+    vm.compiling = None;
+    vm.locals.clear();
+    return Result::Ok(xt);
+}
+
+/// Values of the locals of the innermost definition being executed
+fn frame_locals(vm: &mut VM) -> Result<&mut Vec<i64>, VirtualMachineError> {
+    match vm.return_stack.last_mut() {
+        Some(frame) => {
+            return Result::Ok(&mut frame.locals);
+        },
+        None => {
+            return Result::Err(
+                VirtualMachineError {
+                    msg: String::from("locals outside of a definition"),
+                    ..VirtualMachineError::default()
+                }
+            );
+        },
+    }
+}
+
+/// Declare locals of the definition being compiled, the first `initialized`
+/// of which take their values from the stack at run-time.
+fn declare_locals(vm: &mut VM, names: Vec<String>, initialized: usize) -> Result<(), VirtualMachineError> {
+    vm.current_definition()?;
+    if names.is_empty() {
+        return Result::Ok(());
+    }
+    vm.compile(Operation::LOCALS(initialized, names.len() - initialized))?;
+    vm.locals.extend(names);
+    return Result::Ok(());
+}
+
+/// Point the unresolved branch compiled at `orig` (e.g. by IF) to the end of
+/// the definition being compiled.
+fn resolve(vm: &mut VM, orig: i64) -> Result<(), VirtualMachineError> {
+    let xt: usize = vm.current_definition()?;
+    let body: &mut Vec<Operation> = &mut vm.definitions[xt].body;
+    let target: usize = body.len();
+    match usize::try_from(orig).ok().and_then(|orig| body.get_mut(orig)) {
+        Some(Operation::BRANCH(dest)) | Some(Operation::BRANCH_IF_ZERO(dest)) if *dest == UNRESOLVED => {
+            *dest = target;
+            return Result::Ok(());
+        },
+        _ => {
+            return Result::Err(
+                VirtualMachineError {
+                    msg: String::from("unbalanced control structure"),
+                    ..VirtualMachineError::default()
+                }
+            );
+        },
+    }
+}
+
+/// Copy a string into newly allotted data space, returning its address.
+fn store_string(vm: &mut VM, string: &[u8]) -> Result<i64, VirtualMachineError> {
+    let addr: i64 = vm.memory.here() as i64;
+    vm.allot(string.len() as i64).map_err(unretriable)?;
+    vm.memory.write(addr, string)?;
+    return Result::Ok(addr);
+}
+
+/// Copy a string into data space and compile its run-time semantics
+/// ( -- c-addr u ) into the definition being compiled.
+fn compile_string(vm: &mut VM, string: &[u8]) -> Result<(), VirtualMachineError> {
+    let addr: i64 = store_string(vm, string)?;
+    vm.compile(Operation::LITERAL(addr))?;
+    vm.compile(Operation::LITERAL(string.len() as i64))?;
+    return Result::Ok(());
+}
+
+/// Whether execution starting at `ip` returns before doing anything else,
+/// following any unconditional branches along the way.
+fn returns_from(body: &[Operation], mut ip: usize) -> bool {
+    for _ in 0..body.len() {
+        match body.get(ip) {
+            Some(Operation::BRANCH(dest)) => ip = *dest,
+            Some(Operation::EXIT) | None => return true,
+            Some(_) => return false,
+        }
+    }
+    return false;
+}
+
+/// Replace each call that would be immediately followed by a return with a
+/// tail call, which reuses the caller's frame rather than adding another.
+/// Recursion in tail position then runs in constant return stack space.
+fn eliminate_tail_calls(body: &mut [Operation]) {
+    for ip in 0..body.len() {
+        if let Operation::CALL(xt) = body[ip] {
+            if returns_from(body, ip + 1) {
+                body[ip] = Operation::TAIL_CALL(xt);
+            }
+        }
+    }
+}
+
+/// How long execution may run before yielding, so that a long computation can
+/// be interleaved with other work (e.g. running other VMs, or keeping a user
+/// interface responsive)
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Slice {
+
+    /// Yield after applying the given number of operations
+    OPERATIONS(u64),
+
+    /// Yield once the given amount of time has passed. There's no clock to
+    /// read on WebAssembly (wasm32), so it's unavailable there.
+    #[cfg(not(target_arch = "wasm32"))]
+    DURATION(Duration),
+
+}
+
+/// Operations applied between checks of the time, since checking the time is
+/// relatively slow
+#[cfg(not(target_arch = "wasm32"))]
+const CLOCK_INTERVAL: u64 = 256;
+
+/// The point at which execution yields, as given by a Slice when execution
+/// begins
+#[derive(Clone, Copy, Debug, Default)]
+struct Until {
+
+    /// Yield once this many operations have been applied to the VM (as
+    /// counted by `_ops_applied`)
+    operations: Option<u64>,
+
+    /// Yield once this time has been reached
+    #[cfg(not(target_arch = "wasm32"))]
+    deadline: Option<Instant>,
+
+}
+
+impl Until {
+
+    fn from(vm: &VM, slice: Slice) -> Until {
+        return match slice {
+            Slice::OPERATIONS(n) => Until {
+                operations: Some(vm._ops_applied.saturating_add(n)),
+                #[cfg(not(target_arch = "wasm32"))]
+                deadline: None,
             },
-            Operation::ZERO_LT => {
+            #[cfg(not(target_arch = "wasm32"))]
+            Slice::DURATION(duration) => Until {
+                operations: None,
+                deadline: Some(Instant::now() + duration),
+            },
+        };
+    }
+
+    /// Whether the deadline has been reached, given the number of operations
+    /// applied so far. The time is only checked every so often.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn out_of_time(&self, applied: u64) -> bool {
+        return applied % CLOCK_INTERVAL == 0 && matches!(self.deadline, Some(deadline) if Instant::now() >= deadline);
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn out_of_time(&self, _applied: u64) -> bool {
+        return false;
+    }
+
+}
+
+/// Apply pending operations to the VM until there are none left or one of
+/// them halts the VM, interpreting any source files included along the way
+/// (e.g. by INCLUDED). Definitions being executed and files being included
+/// are abandoned if an error occurs or the VM halts, unless the error is that
+/// one of the VM's limits was exceeded. Execution may then be resumed by
+/// calling this again, once the limit is raised.
+pub fn execute(vm: &mut VM) -> Result<Outcome, VirtualMachineError> {
+    return run(vm, Until::default(), false);
+}
+
+/// Like execute(), but yielding once the given time slice runs out, even in
+/// the midst of an included file. Execution may be resumed by calling this
+/// (or execute()) again.
+pub fn execute_for(vm: &mut VM, slice: Slice) -> Result<Outcome, VirtualMachineError> {
+    let until: Until = Until::from(vm, slice);
+    return run(vm, until, false);
+}
+
+/// Interpret a line of Forth source, one word at a time, stopping early if the
+/// VM halts. The line must end with whitespace (e.g. a newline). After an
+/// error, the rest of the line is left uninterpreted; it's up to the caller
+/// whether to reset() the VM.
+pub fn evaluate(vm: &mut VM, line: &str) -> Result<Outcome, VirtualMachineError> {
+    scan(line, vm)?;
+    return run(vm, Until::default(), true);
+}
+
+/// Like evaluate(), but yielding once the given time slice runs out. The rest
+/// of the line is interpreted by resume().
+pub fn evaluate_for(vm: &mut VM, line: &str, slice: Slice) -> Result<Outcome, VirtualMachineError> {
+    scan(line, vm)?;
+    let until: Until = Until::from(vm, slice);
+    return run(vm, until, true);
+}
+
+/// Continue interpreting a line of Forth source that yielded (e.g. via
+/// evaluate_for()), yielding again once the given time slice runs out
+pub fn resume(vm: &mut VM, slice: Slice) -> Result<Outcome, VirtualMachineError> {
+    let until: Until = Until::from(vm, slice);
+    return run(vm, until, true);
+}
+
+/// Interpret pending operations and included source files, and then the rest
+/// of the input buffer if `interpret_input` is set. Errors within source files
+/// are described along with where in the files they occurred.
+fn run(vm: &mut VM, until: Until, interpret_input: bool) -> Result<Outcome, VirtualMachineError> {
+    let mut result: Result<Outcome, VirtualMachineError> = interpret(vm, until, interpret_input);
+    if let Result::Err(err) = &mut result {
+        let locations: String = vm.sources.iter()
+            .map(|source| format!("{}:{}: ", source.path.display(), source.line))
+            .collect();
+        err.msg.insert_str(0, &locations);
+    }
+
+    let abandoned: bool = match &result {
+        Result::Ok(outcome) => matches!(outcome, Outcome::HALTED(_)),
+        Result::Err(err) => err.limit.is_none(),
+    };
+    if abandoned {
+        while !vm.sources.is_empty() {
+            end_include(vm);
+        }
+        vm.return_stack.clear();
+    }
+    if matches!(result, Result::Ok(Outcome::HALTED(_))) {
+        vm.operations.clear();
+    }
+    return result;
+}
+
+fn interpret(vm: &mut VM, until: Until, interpret_input: bool) -> Result<Outcome, VirtualMachineError> {
+    loop {
+        let outcome: Outcome = apply(vm, until)?;
+        if outcome != Outcome::COMPLETED {
+            return Result::Ok(outcome);
+        }
+        if vm.sources.is_empty() && !interpret_input {
+            return Result::Ok(Outcome::COMPLETED);
+        }
+        if vm.tokens.is_empty() {
+            scan_remaining(vm)?;
+        }
+        if !vm.tokens.is_empty() {
+            parse(vm)?;
+            continue;
+        }
+
+        // Once the parse area is used up, an included file moves on to its
+        // next line, and is finished after its last
+        let source: &mut Source = match vm.sources.last_mut() {
+            Some(source) => source,
+            None => return Result::Ok(Outcome::COMPLETED),
+        };
+        match source.lines.pop_front() {
+            Some(line) => {
+                source.line += 1;
+                scan(&format!("{}\n", line), vm)?;
+            },
+            None => end_include(vm),
+        }
+    }
+}
+
+/// Interpret the source file with the given name, as done by INCLUDED. Errors
+/// are described along with where in the file they occurred.
+pub fn include(vm: &mut VM, name: &str) -> Result<Outcome, VirtualMachineError> {
+    let (path, source): (PathBuf, String) = read_source(vm, name)?;
+    return include_source(vm, &path, &source);
+}
+
+/// Interpret source text as though it had been read from the given file. The
+/// input being interpreted beforehand is set aside until the source has been
+/// interpreted, then picked up where it left off. This is also how the host
+/// interprets files of its own choosing, which aren't subject to the VM's
+/// policy on file access.
+pub fn include_source(vm: &mut VM, path: &Path, source: &str) -> Result<Outcome, VirtualMachineError> {
+    begin_include(vm, path, source)?;
+    return execute(vm);
+}
+
+/// Like include(), but files that have already been included are skipped, as
+/// done by REQUIRED
+pub fn require(vm: &mut VM, name: &str) -> Result<Outcome, VirtualMachineError> {
+    if vm.files.is_included(&vm.files.resolve(name)) {
+        return Result::Ok(Outcome::COMPLETED);
+    }
+    return include(vm, name);
+}
+
+/// Read the source file with the given name, returning its path and contents
+fn read_source(vm: &VM, name: &str) -> Result<(PathBuf, String), VirtualMachineError> {
+    let path: PathBuf = vm.files.resolve(name);
+    let source: String = vm.files.read(&path).map_err(|err| {
+        VirtualMachineError {
+            msg: format!("{}: {}", path.display(), err),
+            ..VirtualMachineError::default()
+        }
+    })?;
+    return Result::Ok((path, source));
+}
+
+/// Set aside the input being interpreted, so that the given source text is
+/// interpreted next, as though it had been read from the given file
+fn begin_include(vm: &mut VM, path: &Path, source: &str) -> Result<(), VirtualMachineError> {
+    if vm.files.including.len() >= INCLUDE_DEPTH {
+        return Result::Err(
+            VirtualMachineError {
+                msg: format!("can't include {}, since includes are nested too deeply", path.display()),
+                ..VirtualMachineError::default()
+            }
+        );
+    }
+    vm.files.record(path);
+    vm.files.including.push(path.to_path_buf());
+    vm.sources.push(
+        Source {
+            path: path.to_path_buf(),
+            lines: source.lines().map(String::from).collect(),
+            line: 0,
+            input_buffer: std::mem::take(&mut vm.input_buffer),
+            input_offset: std::mem::take(&mut vm.input_offset),
+            tokens: std::mem::take(&mut vm.tokens),
+            operations: std::mem::take(&mut vm.operations),
+            return_stack: std::mem::take(&mut vm.return_stack),
+        }
+    );
+    return Result::Ok(());
+}
+
+/// Stop interpreting the innermost source file, picking up the input that was
+/// set aside for it where it left off
+fn end_include(vm: &mut VM) {
+    if let Some(source) = vm.sources.pop() {
+        vm.files.including.pop();
+        vm.input_buffer = source.input_buffer;
+        vm.input_offset = source.input_offset;
+        vm.tokens = source.tokens;
+        vm.operations = source.operations;
+        vm.return_stack = source.return_stack;
+    }
+}
+
+fn apply(vm: &mut VM, until: Until) -> Result<Outcome, VirtualMachineError> {
+
+    // Literals pushed by the parser count against the stack limits too
+    check_limits(vm)?;
+
+    let mut applied: u64 = 0;
+    while let Some(operation) = next_operation(vm) {
+        if matches!(vm.limits.operations, Some(limit) if vm._ops_applied >= limit) {
+            unfetch(vm, operation);
+            return Result::Err(Limit::OPERATIONS.exceeded());
+        }
+        let out_of_operations: bool = matches!(until.operations, Some(limit) if vm._ops_applied >= limit);
+        if out_of_operations || until.out_of_time(applied) {
+            unfetch(vm, operation);
+            return Result::Ok(Outcome::YIELDED);
+        }
+        applied += 1;
+        match operation {
+
+            // Non-operational / internal test ops
+            Operation::NOP => {
+                continue;
+            },
+            Operation::NOP_INC => (),
+
+            // Core words that happen to be VM operations, in alphabetical order.
+            Operation::ABS => {
                 let n: i64 = int_from_stack(vm)?;
-                let flag: bool = n < 0;
-                vm.data_stack.push(Data::NUMBER(flag as i64));  // todo: bool bits
+                let n: i64 = n.wrapping_abs();
+                vm.data_stack.push(Data::NUMBER(n));
             },
-            Operation::ZERO_NE => {
+            Operation::ACCEPT => {
+                let (addr, length): (i64, i64) = two_ints_from_stack(vm)?;
+                let line: String = vm.input.read_line().map_err(input_error)?.unwrap_or_default();
+                let line: &str = line.trim_end_matches(&['\r', '\n'][..]);
+                let received: usize = cmp::min(line.len(), cmp::max(length, 0) as usize);
+                vm.memory.write(addr, &line.as_bytes()[..received])?;
+                vm.data_stack.push(Data::NUMBER(received as i64));
+            },
+            Operation::ACTION_OF => {
+                let xt: usize = parse_xt(vm)?;
+                match vm.compiling {
+                    Some(_) => {
+                        deferred_action(vm, xt)?;
+                        vm.compile(Operation::LITERAL(xt as i64))?;
+                        vm.compile(Operation::DEFER_FETCH)?;
+                    },
+                    None => {
+                        let action: usize = deferred_action_set(vm, xt)?;
+                        vm.data_stack.push(Data::NUMBER(action as i64));
+                    },
+                }
+            },
+            Operation::ADD => {
+                let (n1, n2): (i64, i64) = two_ints_from_stack(vm)?;
+                let n3: i64 = n1.wrapping_add(n2);
+                vm.data_stack.push(
+                    Data::NUMBER(n3),
+                );
+            },
+            Operation::ALLOT => {
                 let n: i64 = int_from_stack(vm)?;
-                let flag: bool = n != 0;
-                vm.data_stack.push(Data::NUMBER(flag as i64));  // todo: bool bits
+                vm.allot(n).map_err(|err| {
+                    if err.limit.is_some() {
+                        vm.data_stack.push(Data::NUMBER(n));
+                    }
+                    return retry(vm, operation, err);
+                })?;
+            },
+            Operation::ALSO => {
+                let wid: usize = *vm.dictionary.top()?;
+                let mut search_order: Vec<usize> = vm.dictionary.search_order.clone();
+                search_order.push(wid);
+                vm.dictionary.set_order(search_order)?;
+            },
+            Operation::ARG => {
+                let n: i64 = int_from_stack(vm)?;
+                match usize::try_from(n).ok().and_then(|n| vm.arguments.get(n)) {
+                    Some(argument) => {
+                        let argument: String = argument.clone();
+                        push_argument(vm, argument)?;
+                    },
+                    None => {
+                        vm.data_stack.push(Data::NUMBER(0));
+                        vm.data_stack.push(Data::NUMBER(0));
+                    },
+                }
+            },
+            Operation::ARGC => {
+                vm.data_stack.push(Data::NUMBER(vm.arguments.len() as i64));
+            },
+            Operation::BASE => {
+                vm.data_stack.push(Data::NUMBER(BASE_ADDR as i64));
+            },
+            Operation::BIN => {
+                let fam: i64 = int_from_stack(vm)?;
+                vm.data_stack.push(Data::NUMBER(fam | BINARY));
+            },
+            Operation::BLANK => {
+                let (addr, length): (i64, i64) = two_ints_from_stack(vm)?;
+                vm.memory.fill(addr, cmp::max(length, 0), b' ')?;
+            },
+            Operation::BRACE_COLON => {
+                vm.current_definition()?;
+                let mut args: Vec<String> = Vec::new();
+                let mut values: Vec<String> = Vec::new();
+                let mut uninitialized: bool = false;
+                let mut outputs: bool = false;
+                loop {
+                    let name: String = parse_name(vm);
+                    match name.as_str() {
+                        "" => {
+                            return Result::Err(
+                                VirtualMachineError {
+                                    msg: String::from("expected :}"),
+                                    ..VirtualMachineError::default()
+                                }
+                            );
+                        },
+                        ":}" => break,
+                        "--" => outputs = true,
+                        _ if outputs => (),
+                        "|" if uninitialized => {
+                            return Result::Err(
+                                VirtualMachineError {
+                                    msg: String::from("unexpected | in locals"),
+                                    ..VirtualMachineError::default()
+                                }
+                            );
+                        },
+                        "|" => uninitialized = true,
+                        _ if uninitialized => values.push(name),
+                        _ => args.push(name),
+                    }
+                }
+                let initialized: usize = args.len();
+                args.extend(values);
+                declare_locals(vm, args, initialized)?;
+            },
+            Operation::BRACKET_CHAR | Operation::CHAR => {
+                let name: String = expect_name(vm)?;
+                let char: i64 = name.as_bytes()[0] as i64;
+                if operation == Operation::BRACKET_CHAR && vm.compiling.is_some() {
+                    vm.compile(Operation::LITERAL(char))?;
+                } else {
+                    vm.data_stack.push(Data::NUMBER(char));
+                }
+            },
+            Operation::CLOSE_FILE => {
+                let fileid: i64 = int_from_stack(vm)?;
+                let result: io::Result<()> = vm.files.close(fileid);
+                push_ior(vm, &result);
+            },
+            Operation::BRACKET_COLON => {
+                vm.enclosing.push(
+                    Enclosing {
+                        xt: vm.compiling,
+                        locals: std::mem::take(&mut vm.locals),
+                    }
+                );
+                vm.compiling = Some(vm.create(Definition::default()));
+            },
+            Operation::BRACKET_TICK => {
+                vm.current_definition()?;
+                let xt: usize = parse_xt(vm)?;
+                vm.compile(Operation::LITERAL(xt as i64))?;
+            },
+            Operation::BRANCH(ip) => {
+                branch(vm, ip)?;
+            },
+            Operation::BRANCH_IF_ZERO(ip) => {
+                let flag: i64 = int_from_stack(vm)?;
+                if flag == 0 {
+                    branch(vm, ip)?;
+                }
+            },
+            Operation::BYE => {
+                vm.operations.clear();
+                return Result::Ok(Outcome::HALTED(None));
+            },
+            Operation::BYE_CODE => {
+                let n: i64 = int_from_stack(vm)?;
+                let code: i32 = i32::try_from(n).map_err(|_| {
+                    VirtualMachineError {
+                        msg: format!("invalid exit code: {}", n),
+                        ..VirtualMachineError::default()
+                    }
+                })?;
+                vm.operations.clear();
+                return Result::Ok(Outcome::HALTED(Some(code)));
+            },
+            Operation::C_FETCH => {
+                let addr: i64 = int_from_stack(vm)?;
+                let char: u8 = vm.memory.fetch_byte(addr)?;
+                vm.data_stack.push(Data::NUMBER(char as i64));
+            },
+            Operation::C_QUOTE => {
+                let string: String = parse_delimited(vm, b'"');
+                if string.len() > u8::MAX as usize {
+                    return Result::Err(
+                        VirtualMachineError {
+                            msg: String::from("counted string exceeds 255 characters"),
+                            ..VirtualMachineError::default()
+                        }
+                    );
+                }
+                let mut counted: Vec<u8> = vec![string.len() as u8];
+                counted.extend(string.as_bytes());
+                if vm.compiling.is_some() {
+                    let addr: i64 = store_string(vm, &counted)?;
+                    vm.compile(Operation::LITERAL(addr))?;
+                } else {
+                    let addr: usize = vm.memory.transient(&counted)?;
+                    vm.data_stack.push(Data::NUMBER(addr as i64));
+                }
+            },
+            Operation::C_STORE => {
+                let (char, addr): (i64, i64) = two_ints_from_stack(vm)?;
+                vm.memory.store_byte(addr, char as u8)?;
+            },
+            Operation::CALL(xt) => {
+                call(vm, xt)?;
+            },
+            Operation::CASE => {
+                vm.current_definition()?;
+                vm.data_stack.push(Data::NUMBER(0));
+            },
+            Operation::CMOVE => {
+                let (from, to, length): (i64, i64, i64) = three_ints_from_stack(vm)?;
+                for i in 0..length {
+                    let char: u8 = vm.memory.fetch_byte(from + i)?;
+                    vm.memory.store_byte(to + i, char)?;
+                }
+            },
+            Operation::CMOVE_UP => {
+                let (from, to, length): (i64, i64, i64) = three_ints_from_stack(vm)?;
+                for i in (0..length).rev() {
+                    let char: u8 = vm.memory.fetch_byte(from + i)?;
+                    vm.memory.store_byte(to + i, char)?;
+                }
+            },
+            Operation::CMP_EQ => {
+                let (n1, n2): (i64, i64) = two_ints_from_stack(vm)?;
+                let flag: bool = n1 == n2;
+                vm.data_stack.push(
+                    Data::NUMBER(flag as i64),  // todo: bool bits
+                );
             },
+            Operation::CMP_GT => {
+                let (n1, n2): (i64, i64) = two_ints_from_stack(vm)?;
+                let flag: bool = n1 > n2;
+                vm.data_stack.push(
+                    Data::NUMBER(flag as i64),  // todo: bool bits
+                );
+            },
+            Operation::CMP_LT => {
+                let (n1, n2): (i64, i64) = two_ints_from_stack(vm)?;
+                let flag: bool = n1 < n2;
+                vm.data_stack.push(
+                    Data::NUMBER(flag as i64),  // todo: bool bits
+                );
+            },
+            Operation::CMP_NE => {
+                let (n1, n2): (i64, i64) = two_ints_from_stack(vm)?;
+                let flag: bool = n1 != n2;
+                vm.data_stack.push(
+                    Data::NUMBER(flag as i64),  // todo: bool bits
+                );
+            },
+            Operation::COLON => {
+                if vm.compiling.is_some() {
+                    return Result::Err(
+                        VirtualMachineError {
+                            msg: String::from("definitions can't be nested"),
+                            ..VirtualMachineError::default()
+                        }
+                    );
+                }
+                let name: String = expect_name(vm)?;
+                vm.compiling = Some(vm.create(Definition { name, ..Definition::default() }));
+                vm.locals.clear();
+            },
+            Operation::COMPARE => {
+                let (addr1, length1, addr2, length2): (i64, i64, i64, i64) = four_ints_from_stack(vm)?;
+                let string1: &[u8] = vm.memory.slice(addr1, length1)?;
+                let string2: &[u8] = vm.memory.slice(addr2, length2)?;
+                let n: i64 = match string1.cmp(string2) {
+                    cmp::Ordering::Less => -1,
+                    cmp::Ordering::Equal => 0,
+                    cmp::Ordering::Greater => 1,
+                };
+                vm.data_stack.push(Data::NUMBER(n));
+            },
+            Operation::COUNT => {
+                let addr: i64 = int_from_stack(vm)?;
+                let length: u8 = vm.memory.fetch_byte(addr)?;
+                vm.data_stack.push(Data::NUMBER(addr + 1));
+                vm.data_stack.push(Data::NUMBER(length as i64));
+            },
+            Operation::D_TO_F => {
+                let d: u128 = double_from_stack(vm)?;
+                vm.float_stack.push(d as i128 as f64);
+            },
+            Operation::DASH_TRAILING => {
+                let (addr, length): (i64, i64) = two_ints_from_stack(vm)?;
+                let string: &[u8] = vm.memory.slice(addr, length)?;
+                let trailing: usize = string.iter().rev().take_while(|char| **char == b' ').count();
+                vm.data_stack.push(Data::NUMBER(addr));
+                vm.data_stack.push(Data::NUMBER(length - trailing as i64));
+            },
+            Operation::CR => {
+                display(vm, b"\n")?;
+            },
+            Operation::CREATE_FILE => {
+                open_file(vm, true)?;
+            },
+            Operation::DECIMAL => {
+                vm.memory.store_cell(BASE_ADDR as i64, 10)?;
+            },
+            Operation::DEFER => {
+                let name: String = expect_name(vm)?;
+                vm.define(
+                    Definition {
+                        name,
+                        body: vec![Operation::DEFERRED(None)],
+                        ..Definition::default()
+                    }
+                );
+            },
+            Operation::DEFER_FETCH => {
+                let xt: i64 = int_from_stack(vm)?;
+                let xt: usize = to_xt(vm, xt)?;
+                let action: usize = deferred_action_set(vm, xt)?;
+                vm.data_stack.push(Data::NUMBER(action as i64));
+            },
+            Operation::DEFER_STORE => {
+                let (action, xt): (i64, i64) = two_ints_from_stack(vm)?;
+                let action: usize = to_xt(vm, action)?;
+                let xt: usize = to_xt(vm, xt)?;
+                *deferred_action(vm, xt)? = Some(action);
+            },
+            Operation::DEFERRED(action) => {
+                match action {
+                    Some(action) => {
+                        tail_call(vm, action)?;
+                    },
+                    None => {
+                        let name: &str = match vm.return_stack.last() {
+                            Some(frame) => &vm.definitions[frame.xt].name,
+                            None => "",
+                        };
+                        return Result::Err(
+                            VirtualMachineError {
+                                msg: format!("uninitialized deferred word: {}", name),
+                                ..VirtualMachineError::default()
+                            }
+                        );
+                    },
+                }
+            },
+            Operation::DEFINITIONS => {
+                vm.dictionary.current = *vm.dictionary.top()?;
+            },
+            Operation::DELETE_FILE => {
+                let name: String = file_name_from_stack(vm)?;
+                let result: io::Result<()> = vm.files.delete(Path::new(&name));
+                push_ior(vm, &result);
+            },
+            Operation::DIV => {
+                let (n1, n2): (i64, i64) = two_ints_from_stack(vm)?;
+                if n2 == 0 {
+                    return Result::Err(
+                        VirtualMachineError {
+                            msg: String::from("divisor cannot be zero"),
+                            ..VirtualMachineError::default()
+                        }
+                    );
+                }
+                let n3: i64 = n1.wrapping_div(n2); // floats are divided by F/
+                vm.data_stack.push(
+                    Data::NUMBER(n3),
+                );
+            },
+            Operation::DO => {
+                let orig: usize = vm.compile(Operation::LOOP_ENTER(UNRESOLVED))?;
+                vm.data_stack.push(Data::NUMBER(orig as i64));
+            },
+            Operation::DOT => {
+                let n: i64 = int_from_stack(vm)?;
+                let text: String = number_to_string(vm, n)?;
+                display(vm, format!("{} ", text).as_bytes())?;
+            },
+            Operation::DOT_QUOTE => {
+                let string: String = parse_delimited(vm, b'"');
+                if vm.compiling.is_some() {
+                    compile_string(vm, string.as_bytes())?;
+                    vm.compile(Operation::TYPE)?;
+                } else {
+                    display(vm, string.as_bytes())?;
+                }
+            },
+            Operation::DOT_R => {
+                let (n, width): (i64, i64) = two_ints_from_stack(vm)?;
+                let text: String = number_to_string(vm, n)?;
+                display_spaces(vm, width.saturating_sub(text.len() as i64))?;
+                display(vm, text.as_bytes())?;
+            },
+            Operation::DOT_S => {
+                let mut text: String = format!("<{}> ", vm.data_stack.len());
+                for cell in vm.data_stack.iter() {
+                    let Data::NUMBER(n) = cell;
+                    text.push_str(&number_to_string(vm, *n)?);
+                    text.push(' ');
+                }
+                display(vm, text.as_bytes())?;
+            },
+            Operation::DROP => {
+                let x: Option<Data> = vm.data_stack.pop();
+                if x.is_none() {
+                    return Result::Err(
+                        VirtualMachineError {
+                            msg: String::from("stack underflow"),
+                            ..VirtualMachineError::default()
+                        }
+                    );
+                }
+            },
+            Operation::DUP => {
+                let x: Option<&Data> = vm.data_stack.last();
+                if x.is_none() {
+                    return Result::Err(
+                        VirtualMachineError {
+                            msg: String::from("stack underflow"),
+                            ..VirtualMachineError::default()
+                        }
+                    );
+                }
+                let x: &Data = x.unwrap();
+                let x2: Data = x.clone();
+                vm.data_stack.push(x2);
+            },
+            Operation::ELSE => {
+                let orig: i64 = int_from_stack(vm)?;
+                let else_orig: usize = vm.compile(Operation::BRANCH(UNRESOLVED))?;
+                resolve(vm, orig)?;
+                vm.data_stack.push(Data::NUMBER(else_orig as i64));
+            },
+            Operation::EMIT => {
+                let x: i64 = int_from_stack(vm)?;
+                let char: char = char::from_u32(x as u32).unwrap_or(char::REPLACEMENT_CHARACTER);
+                display(vm, char.to_string().as_bytes())?;
+            },
+            Operation::EKEY => {
+                let event: i64 = read_event(vm)?;
+                vm.data_stack.push(Data::NUMBER(event));
+            },
+            Operation::EKEY_TO_CHAR => {
+                let event: i64 = int_from_stack(vm)?;
+                let flag: bool = (0..=0xff).contains(&event);
+                vm.data_stack.push(Data::NUMBER(event));
+                vm.data_stack.push(Data::NUMBER(flag as i64));  // todo: bool bits
+            },
+            Operation::ENDCASE => {
+                let count: i64 = int_from_stack(vm)?;
+                if count < 0 || count as usize > vm.data_stack.len() {
+                    return Result::Err(
+                        VirtualMachineError {
+                            msg: String::from("unbalanced control structure"),
+                            ..VirtualMachineError::default()
+                        }
+                    );
+                }
+                vm.compile(Operation::DROP)?;
+                for _ in 0..count {
+                    let orig: i64 = int_from_stack(vm)?;
+                    resolve(vm, orig)?;
+                }
+            },
+            Operation::ENDOF => {
+                let (count, orig): (i64, i64) = two_ints_from_stack(vm)?;
+                let endof_orig: usize = vm.compile(Operation::BRANCH(UNRESOLVED))?;
+                resolve(vm, orig)?;
+                vm.data_stack.push(Data::NUMBER(endof_orig as i64));
+                vm.data_stack.push(Data::NUMBER(count + 1));
+            },
+            Operation::EXECUTE => {
+                let xt: i64 = int_from_stack(vm)?;
+                let xt: usize = to_xt(vm, xt)?;
+                call(vm, xt)?;
+            },
+            Operation::EXIT => {
+                if vm.return_stack.pop().is_none() {
+                    return Result::Err(
+                        VirtualMachineError {
+                            msg: String::from("EXIT outside of a definition"),
+                            ..VirtualMachineError::default()
+                        }
+                    );
+                }
+            },
+            Operation::F_ABS => {
+                float_function(vm, f64::abs)?;
+            },
+            Operation::F_ACOS => {
+                float_function(vm, f64::acos)?;
+            },
+            Operation::F_ACOSH => {
+                float_function(vm, f64::acosh)?;
+            },
+            Operation::F_ADD => {
+                float_operator(vm, |r1, r2| r1 + r2)?;
+            },
+            Operation::F_ALIGN => {
+                let here: i64 = vm.memory.here() as i64;
+                vm.allot(aligned(here, FLOAT_SIZE) - here).map_err(|err| retry(vm, operation, err))?;
+            },
+            Operation::F_ALIGNED => {
+                let addr: i64 = int_from_stack(vm)?;
+                vm.data_stack.push(Data::NUMBER(aligned(addr, FLOAT_SIZE)));
+            },
+            Operation::F_ALOG => {
+                float_function(vm, |r| 10_f64.powf(r))?;
+            },
+            Operation::F_ASIN => {
+                float_function(vm, f64::asin)?;
+            },
+            Operation::F_ASINH => {
+                float_function(vm, f64::asinh)?;
+            },
+            Operation::F_ATAN => {
+                float_function(vm, f64::atan)?;
+            },
+            Operation::F_ATAN2 => {
+                float_operator(vm, f64::atan2)?;
+            },
+            Operation::F_ATANH => {
+                float_function(vm, f64::atanh)?;
+            },
+            Operation::F_CMP_LT => {
+                let (r1, r2): (f64, f64) = two_floats_from_stack(vm)?;
+                vm.data_stack.push(Data::NUMBER((r1 < r2) as i64));  // todo: bool bits
+            },
+            Operation::F_CONSTANT => {
+                let name: String = expect_name(vm)?;
+                let r: f64 = float_from_stack(vm)?;
+                vm.define(
+                    Definition {
+                        name,
+                        body: vec![Operation::F_LITERAL(r)],
+                        ..Definition::default()
+                    }
+                );
+            },
+            Operation::F_COS => {
+                float_function(vm, f64::cos)?;
+            },
+            Operation::F_COSH => {
+                float_function(vm, f64::cosh)?;
+            },
+            Operation::F_DEPTH => {
+                vm.data_stack.push(Data::NUMBER(vm.float_stack.len() as i64));
+            },
+            Operation::F_DIV => {
+                float_operator(vm, |r1, r2| r1 / r2)?;
+            },
+            Operation::F_DOT => {
+                let r: f64 = float_from_stack(vm)?;
+                let text: String = float_to_string(vm, r, None)?;
+                display(vm, format!("{} ", text).as_bytes())?;
+            },
+            Operation::F_DROP => {
+                float_from_stack(vm)?;
+            },
+            Operation::F_DUP => {
+                let r: f64 = *vm.float_stack.last().ok_or_else(float_stack_underflow)?;
+                vm.float_stack.push(r);
+            },
+            Operation::F_E_DOT => {
+                let r: f64 = float_from_stack(vm)?;
+                let text: String = float_to_string(vm, r, Some(3))?;
+                display(vm, format!("{} ", text).as_bytes())?;
+            },
+            Operation::F_EXP => {
+                float_function(vm, f64::exp)?;
+            },
+            Operation::F_EXPM1 => {
+                float_function(vm, f64::exp_m1)?;
+            },
+            Operation::F_FETCH => {
+                let addr: i64 = int_from_stack(vm)?;
+                let r: f64 = vm.memory.fetch_float(addr)?;
+                vm.float_stack.push(r);
+            },
+            Operation::F_FLOOR => {
+                float_function(vm, f64::floor)?;
+            },
+            Operation::F_LITERAL(r) => {
+                vm.float_stack.push(r);
+            },
+            Operation::F_LN => {
+                float_function(vm, f64::ln)?;
+            },
+            Operation::F_LNP1 => {
+                float_function(vm, f64::ln_1p)?;
+            },
+            Operation::F_LOG => {
+                float_function(vm, f64::log10)?;
+            },
+            Operation::F_MAX => {
+                float_operator(vm, f64::max)?;
+            },
+            Operation::F_MIN => {
+                float_operator(vm, f64::min)?;
+            },
+            Operation::F_MUL => {
+                float_operator(vm, |r1, r2| r1 * r2)?;
+            },
+            Operation::F_NEGATE => {
+                float_function(vm, |r| -r)?;
+            },
+            Operation::F_OVER => {
+                let (r1, r2): (f64, f64) = two_floats_from_stack(vm)?;
+                vm.float_stack.extend([r1, r2, r1]);
+            },
+            Operation::F_POW => {
+                float_operator(vm, f64::powf)?;
+            },
+            Operation::F_PROXIMATE => {
+                let (r1, r2, r3): (f64, f64, f64) = three_floats_from_stack(vm)?;
+                let flag: bool = if r3 > 0.0 {
+                    (r1 - r2).abs() < r3
+                } else if r3 == 0.0 {
+                    r1.to_bits() == r2.to_bits()
+                } else {
+                    (r1 - r2).abs() < r3.abs() * (r1.abs() + r2.abs())
+                };
+                vm.data_stack.push(Data::NUMBER(flag as i64));  // todo: bool bits
+            },
+            Operation::F_ROT => {
+                let (r1, r2, r3): (f64, f64, f64) = three_floats_from_stack(vm)?;
+                vm.float_stack.extend([r2, r3, r1]);
+            },
+            Operation::F_ROUND => {
+                float_function(vm, |r| {
+                    if (r - r.trunc()).abs() == 0.5 {
+                        return 2.0 * (r / 2.0).round();
+                    }
+                    return r.round();
+                })?;
+            },
+            Operation::F_S_DOT => {
+                let r: f64 = float_from_stack(vm)?;
+                let text: String = float_to_string(vm, r, Some(1))?;
+                display(vm, format!("{} ", text).as_bytes())?;
+            },
+            Operation::F_SIN => {
+                float_function(vm, f64::sin)?;
+            },
+            Operation::F_SINCOS => {
+                let r: f64 = float_from_stack(vm)?;
+                vm.float_stack.extend([r.sin(), r.cos()]);
+            },
+            Operation::F_SINH => {
+                float_function(vm, f64::sinh)?;
+            },
+            Operation::F_SQRT => {
+                float_function(vm, f64::sqrt)?;
+            },
+            Operation::F_STORE => {
+                let addr: i64 = int_from_stack(vm)?;
+                let r: f64 = float_from_stack(vm)?;
+                vm.memory.store_float(addr, r)?;
+            },
+            Operation::F_SUB => {
+                float_operator(vm, |r1, r2| r1 - r2)?;
+            },
+            Operation::F_SWAP => {
+                let (r1, r2): (f64, f64) = two_floats_from_stack(vm)?;
+                vm.float_stack.extend([r2, r1]);
+            },
+            Operation::F_TAN => {
+                float_function(vm, f64::tan)?;
+            },
+            Operation::F_TANH => {
+                float_function(vm, f64::tanh)?;
+            },
+            Operation::F_TO_D => {
+                let r: f64 = float_from_stack(vm)?;
+                double_to_stack(vm, r.trunc() as i128 as u128);
+            },
+            Operation::F_TO_S => {
+                let r: f64 = float_from_stack(vm)?;
+                vm.data_stack.push(Data::NUMBER(r.trunc() as i64));
+            },
+            Operation::F_TRUNC => {
+                float_function(vm, f64::trunc)?;
+            },
+            Operation::F_VARIABLE => {
+                let name: String = expect_name(vm)?;
+                let addr: usize = vm.memory.here();
+                vm.define(
+                    Definition {
+                        name,
+                        body: vec![Operation::LITERAL(addr as i64)],
+                        ..Definition::default()
+                    }
+                );
+                vm.allot(FLOAT_SIZE as i64).map_err(unretriable)?;
+            },
+            Operation::F_ZERO_EQ => {
+                let r: f64 = float_from_stack(vm)?;
+                vm.data_stack.push(Data::NUMBER((r == 0.0) as i64));  // todo: bool bits
+            },
+            Operation::F_ZERO_LT => {
+                let r: f64 = float_from_stack(vm)?;
+                vm.data_stack.push(Data::NUMBER((r < 0.0) as i64));  // todo: bool bits
+            },
+            Operation::FETCH => {
+                let addr: i64 = int_from_stack(vm)?;
+                let x: i64 = vm.memory.fetch_cell(addr)?;
+                vm.data_stack.push(Data::NUMBER(x));
+            },
+            Operation::FILE_POSITION => {
+                let fileid: i64 = int_from_stack(vm)?;
+                let result: io::Result<u64> = vm.files.get(fileid).and_then(|file| file.stream_position());
+                double_to_stack(vm, *result.as_ref().unwrap_or(&0) as u128);
+                push_ior(vm, &result);
+            },
+            Operation::FILE_SIZE => {
+                let fileid: i64 = int_from_stack(vm)?;
+                let result: io::Result<u64> = vm.files.get(fileid).and_then(|file| file.metadata()).map(|metadata| metadata.len());
+                double_to_stack(vm, *result.as_ref().unwrap_or(&0) as u128);
+                push_ior(vm, &result);
+            },
+            Operation::FLITERAL => {
+                vm.current_definition()?;
+                let r: f64 = float_from_stack(vm)?;
+                vm.compile(Operation::F_LITERAL(r))?;
+            },
+            Operation::FLOAT_PLUS => {
+                let addr: i64 = int_from_stack(vm)?;
+                vm.data_stack.push(Data::NUMBER(addr.wrapping_add(FLOAT_SIZE as i64)));
+            },
+            Operation::FLOATS => {
+                let n: i64 = int_from_stack(vm)?;
+                vm.data_stack.push(Data::NUMBER(n.wrapping_mul(FLOAT_SIZE as i64)));
+            },
+            Operation::FORGET => {
+                let xt: usize = parse_xt(vm)?;
+                vm.forget(xt)?;
+            },
+            Operation::FORTH_WORDLIST => {
+                vm.data_stack.push(Data::NUMBER(FORTH_WORDLIST as i64));
+            },
+            Operation::GET_CURRENT => {
+                vm.data_stack.push(Data::NUMBER(vm.dictionary.current as i64));
+            },
+            Operation::GET_ORDER => {
+                for wid in vm.dictionary.search_order.iter() {
+                    vm.data_stack.push(Data::NUMBER(*wid as i64));
+                }
+                vm.data_stack.push(Data::NUMBER(vm.dictionary.search_order.len() as i64));
+            },
+            Operation::HERE => {
+                vm.data_stack.push(Data::NUMBER(vm.memory.here() as i64));
+            },
+            Operation::HEX => {
+                vm.memory.store_cell(BASE_ADDR as i64, 16)?;
+            },
+            Operation::HOLD => {
+                let char: i64 = int_from_stack(vm)?;
+                vm.memory.hold(&[char as u8])?;
+            },
+            Operation::HOLDS => {
+                let (addr, length): (i64, i64) = two_ints_from_stack(vm)?;
+                let string: Vec<u8> = vm.memory.slice(addr, length)?.to_vec();
+                vm.memory.hold(&string)?;
+            },
+            Operation::I => {
+                let index: i64 = loops(vm)?.last().unwrap().index;
+                vm.data_stack.push(Data::NUMBER(index));
+            },
+            Operation::IF => {
+                let orig: usize = vm.compile(Operation::BRANCH_IF_ZERO(UNRESOLVED))?;
+                vm.data_stack.push(Data::NUMBER(orig as i64));
+            },
+            Operation::INCLUDE => {
+                let name: String = expect_name(vm)?;
+                let (path, source): (PathBuf, String) = read_source(vm, &name)?;
+                begin_include(vm, &path, &source)?;
+            },
+            Operation::INCLUDED => {
+                let name: String = file_name_from_stack(vm)?;
+                let (path, source): (PathBuf, String) = read_source(vm, &name)?;
+                begin_include(vm, &path, &source)?;
+            },
+            Operation::IS => {
+                match vm.compiling {
+                    Some(_) => {
+                        let xt: usize = parse_xt(vm)?;
+                        deferred_action(vm, xt)?;
+                        vm.compile(Operation::LITERAL(xt as i64))?;
+                        vm.compile(Operation::DEFER_STORE)?;
+                    },
+                    None => {
+                        let action: i64 = int_from_stack(vm)?;
+                        let action: usize = to_xt(vm, action)?;
+                        let xt: usize = parse_xt(vm)?;
+                        *deferred_action(vm, xt)? = Some(action);
+                    },
+                }
+            },
+            Operation::J => {
+                let loops: &mut Vec<LoopControl> = loops(vm)?;
+                let index: Option<i64> = loops.iter().rev().nth(1).map(|control| control.index);
+                match index {
+                    Some(index) => {
+                        vm.data_stack.push(Data::NUMBER(index));
+                    },
+                    None => {
+                        return Result::Err(
+                            VirtualMachineError {
+                                msg: String::from("loop control parameters unavailable"),
+                                ..VirtualMachineError::default()
+                            }
+                        );
+                    },
+                }
+            },
+            Operation::KEY => {
+                vm.output.flush().map_err(input_error)?;
+                let char: u8 = read_key(vm)?;
+                vm.data_stack.push(Data::NUMBER(char as i64));
+            },
+            Operation::KEY_QUESTION => {
+                let flag: bool = vm.input.key_available().map_err(input_error)?;
+                vm.data_stack.push(Data::NUMBER(flag as i64));  // todo: bool bits
+            },
+            Operation::LEAVE => {
+                let control: LoopControl = loops(vm)?.pop().unwrap();
+                branch(vm, control.exit)?;
+            },
+            Operation::LITERAL(x) => {
+                vm.data_stack.push(Data::NUMBER(x));
+            },
+            Operation::LOCAL_FETCH(local) => {
+                let value: Option<i64> = frame_locals(vm)?.get(local).copied();
+                match value {
+                    Some(value) => {
+                        vm.data_stack.push(Data::NUMBER(value));
+                    },
+                    None => {
+                        return Result::Err(
+                            VirtualMachineError {
+                                msg: format!("invalid local: {}", local),
+                                ..VirtualMachineError::default()
+                            }
+                        );
+                    },
+                }
+            },
+            Operation::LOCAL_STORE(local) => {
+                let value: i64 = int_from_stack(vm)?;
+                match frame_locals(vm)?.get_mut(local) {
+                    Some(cell) => {
+                        *cell = value;
+                    },
+                    None => {
+                        return Result::Err(
+                            VirtualMachineError {
+                                msg: format!("invalid local: {}", local),
+                                ..VirtualMachineError::default()
+                            }
+                        );
+                    },
+                }
+            },
+            Operation::LOCALS(initialized, uninitialized) => {
+                if vm.data_stack.len() < initialized {
+                    return Result::Err(
+                        VirtualMachineError {
+                            msg: String::from("stack underflow"),
+                            ..VirtualMachineError::default()
+                        }
+                    );
+                }
+                let values: Vec<Data> = vm.data_stack.split_off(vm.data_stack.len() - initialized);
+                let locals: &mut Vec<i64> = frame_locals(vm)?;
+                for Data::NUMBER(value) in values {
+                    locals.push(value);
+                }
+                locals.resize(locals.len() + uninitialized, 0);
+            },
+            Operation::LOCALS_BAR => {
+                let mut names: Vec<String> = Vec::new();
+                loop {
+                    let name: String = parse_name(vm);
+                    match name.as_str() {
+                        "" => {
+                            return Result::Err(
+                                VirtualMachineError {
+                                    msg: String::from("expected |"),
+                                    ..VirtualMachineError::default()
+                                }
+                            );
+                        },
+                        "|" => break,
+                        _ => names.push(name),
+                    }
+                }
+                names.reverse();
+                let initialized: usize = names.len();
+                declare_locals(vm, names, initialized)?;
+            },
+            Operation::LOOP => {
+                let orig: i64 = int_from_stack(vm)?;
+                resolve_loop(vm, orig, Operation::LOOP_NEXT)?;
+            },
+            Operation::LOOP_ENTER(exit) | Operation::LOOP_ENTER_IF_NE(exit) => {
+                let (limit, index): (i64, i64) = two_ints_from_stack(vm)?;
+                if matches!(operation, Operation::LOOP_ENTER_IF_NE(_)) && limit == index {
+                    branch(vm, exit)?;
+                    continue;
+                }
+                match vm.return_stack.last_mut() {
+                    Some(frame) => {
+                        frame.loops.push(LoopControl { index, limit, exit });
+                    },
+                    None => {
+                        return Result::Err(
+                            VirtualMachineError {
+                                msg: String::from("loop outside of a definition"),
+                                ..VirtualMachineError::default()
+                            }
+                        );
+                    },
+                }
+            },
+            Operation::LOOP_NEXT(start) => {
+                loop_step(vm, 1, start)?;
+            },
+            Operation::LOOP_NEXT_BY(start) => {
+                let n: i64 = int_from_stack(vm)?;
+                loop_step(vm, n, start)?;
+            },
+            Operation::MARKER => {
+                let name: String = expect_name(vm)?;
+                let xt: usize = vm.definitions.len();
+                vm.define(
+                    Definition {
+                        name,
+                        body: vec![Operation::ROLL_BACK(xt)],
+                        ..Definition::default()
+                    }
+                );
+
+                vm.markers.push(
+                    Marker {
+                        xt,
+                        current: vm.dictionary.current,
+                        wordlists: vm.dictionary.wordlists.len(),
+                        search_order: vm.dictionary.search_order.clone(),
+                    }
+                );
+            },
+            Operation::MAX => {
+                let (n1, n2): (i64, i64) = two_ints_from_stack(vm)?;
+                let n3: i64 = cmp::max(n1, n2);
+                vm.data_stack.push(
+                    Data::NUMBER(n3),
+                );
+            },
+            Operation::MIN => {
+                let (n1, n2): (i64, i64) = two_ints_from_stack(vm)?;
+                let n3: i64 = cmp::min(n1, n2);
+                vm.data_stack.push(
+                    Data::NUMBER(n3),
+                );
+            },
+            Operation::MOD => {
+                let (n1, n2): (i64, i64) = two_ints_from_stack(vm)?;
+                if n2 == 0 {
+                    return Result::Err(
+                        VirtualMachineError {
+                            msg: String::from("divisor cannot be zero"),
+                            ..VirtualMachineError::default()
+                        }
+                    );
+                }
+                let n3: i64 = n1.wrapping_rem_euclid(n2);
+                vm.data_stack.push(
+                    Data::NUMBER(n3),
+                );
+            },
+            Operation::MUL => {
+                let (n1, n2): (i64, i64) = two_ints_from_stack(vm)?;
+                let n3: i64 = n1.wrapping_mul(n2);
+                vm.data_stack.push(
+                    Data::NUMBER(n3),
+                );
+            },
+            Operation::NATIVE(native) => {
+                let native: Native = vm.natives[native].clone();
+                native.call(vm)?;
+            },
+            Operation::NEGATE => {
+                let n: i64 = int_from_stack(vm)?;
+                let n: i64 = n.wrapping_neg();
+                vm.data_stack.push(Data::NUMBER(n));
+            },
+            Operation::NEXT_ARG => {
+                match vm.arguments.len() > 1 {
+                    true => {
+                        let argument: String = vm.arguments.remove(1);
+                        push_argument(vm, argument)?;
+                    },
+                    false => {
+                        vm.data_stack.push(Data::NUMBER(0));
+                        vm.data_stack.push(Data::NUMBER(0));
+                    },
+                }
+            },
+            Operation::NUM => {
+                let ud: u128 = double_from_stack(vm)?;
+                let ud: u128 = hold_digit(vm, ud)?;
+                double_to_stack(vm, ud);
+            },
+            Operation::NUM_END => {
+                double_from_stack(vm)?;
+                let (addr, length): (usize, usize) = vm.memory.held();
+                vm.data_stack.push(Data::NUMBER(addr as i64));
+                vm.data_stack.push(Data::NUMBER(length as i64));
+            },
+            Operation::NUM_S => {
+                let mut ud: u128 = double_from_stack(vm)?;
+                loop {
+                    ud = hold_digit(vm, ud)?;
+                    if ud == 0 {
+                        break;
+                    }
+                }
+                double_to_stack(vm, ud);
+            },
+            Operation::NUM_START => {
+                vm.memory.hold_start();
+            },
+            Operation::OF => {
+                vm.compile(Operation::OVER)?;
+                vm.compile(Operation::CMP_EQ)?;
+                let orig: usize = vm.compile(Operation::BRANCH_IF_ZERO(UNRESOLVED))?;
+                vm.compile(Operation::DROP)?;
+                vm.data_stack.push(Data::NUMBER(orig as i64));
+            },
+            Operation::ONLY => {
+                vm.dictionary.set_order(vec![FORTH_WORDLIST])?;
+            },
+            Operation::OPEN_FILE => {
+                open_file(vm, false)?;
+            },
+            Operation::ORDER => {
+                let mut text: String = String::new();
+                for wid in vm.dictionary.search_order.iter().rev() {
+                    text.push_str(&vm.dictionary.name(*wid));
+                    text.push(' ');
+                }
+                text.push(' ');
+                text.push_str(&vm.dictionary.name(vm.dictionary.current));
+                text.push(' ');
+                display(vm, text.as_bytes())?;
+            },
+            Operation::ORDER_TOP(wid) => {
+                *vm.dictionary.top()? = wid;
+            },
+            Operation::OVER => {
+                let (x1, x2): (i64, i64) = two_ints_from_stack(vm)?;
+                vm.data_stack.push(Data::NUMBER(x1));
+                vm.data_stack.push(Data::NUMBER(x2));
+                vm.data_stack.push(Data::NUMBER(x1));
+            },
+            Operation::PAGE => {
+                // ANSI escape sequences: erase the display, then move the cursor home
+                display(vm, b"\x1b[2J\x1b[H")?;
+            },
+            Operation::PLUS_LOOP => {
+                let orig: i64 = int_from_stack(vm)?;
+                resolve_loop(vm, orig, Operation::LOOP_NEXT_BY)?;
+            },
+            Operation::PREVIOUS => {
+                vm.dictionary.top()?;
+                vm.dictionary.search_order.pop();
+            },
+            Operation::PRECISION => {
+                let precision: usize = vm.memory.precision()?;
+                vm.data_stack.push(Data::NUMBER(precision as i64));
+            },
+            Operation::QUESTION => {
+                let addr: i64 = int_from_stack(vm)?;
+                let x: i64 = vm.memory.fetch_cell(addr)?;
+                let text: String = number_to_string(vm, x)?;
+                display(vm, format!("{} ", text).as_bytes())?;
+            },
+            Operation::QUESTION_DO => {
+                let orig: usize = vm.compile(Operation::LOOP_ENTER_IF_NE(UNRESOLVED))?;
+                vm.data_stack.push(Data::NUMBER(orig as i64));
+            },
+            Operation::R_O => {
+                vm.data_stack.push(Data::NUMBER(READ_ONLY));
+            },
+            Operation::R_W => {
+                vm.data_stack.push(Data::NUMBER(READ_WRITE));
+            },
+            Operation::READ_FILE => {
+                let (addr, length, fileid): (i64, i64, i64) = three_ints_from_stack(vm)?;
+                vm.memory.slice(addr, length)?;
+                let mut bytes: Vec<u8> = Vec::new();
+                let result: io::Result<usize> = vm.files.get(fileid).and_then(|file| {
+                    file.take(length as u64).read_to_end(&mut bytes)
+                });
+                vm.memory.write(addr, &bytes)?;
+                vm.data_stack.push(Data::NUMBER(bytes.len() as i64));
+                push_ior(vm, &result);
+            },
+            Operation::READ_LINE => {
+                let (addr, length, fileid): (i64, i64, i64) = three_ints_from_stack(vm)?;
+                vm.memory.slice(addr, length)?;
+                let result: io::Result<Option<Vec<u8>>> = vm.files.get(fileid).and_then(|file| {
+                    read_line(file, length as usize)
+                });
+                let line: Option<&Vec<u8>> = result.as_ref().ok().and_then(|line| line.as_ref());
+                if let Some(line) = line {
+                    vm.memory.write(addr, line)?;
+                }
+                vm.data_stack.push(Data::NUMBER(line.map_or(0, |line| line.len() as i64)));
+                vm.data_stack.push(Data::NUMBER(line.is_some() as i64));  // todo: bool bits
+                push_ior(vm, &result);
+            },
+            Operation::RECURSE => {
+                let xt: usize = vm.current_definition()?;
+                vm.compile(Operation::CALL(xt))?;
+            },
+            Operation::RENAME_FILE => {
+                let to: String = file_name_from_stack(vm)?;
+                let from: String = file_name_from_stack(vm)?;
+                let result: io::Result<()> = vm.files.rename(Path::new(&from), Path::new(&to));
+                push_ior(vm, &result);
+            },
+            Operation::REPLACES => {
+                let (text_addr, text_length, name_addr, name_length): (i64, i64, i64, i64) = four_ints_from_stack(vm)?;
+                let text: Vec<u8> = vm.memory.slice(text_addr, text_length)?.to_vec();
+                let name: String = String::from_utf8_lossy(vm.memory.slice(name_addr, name_length)?).to_uppercase();
+                vm.substitutions.insert(name, text);
+            },
+            Operation::REPOSITION_FILE => {
+                let fileid: i64 = int_from_stack(vm)?;
+                let ud: u128 = double_from_stack(vm)?;
+                let result: io::Result<u64> = vm.files.get(fileid).and_then(|file| file.seek(SeekFrom::Start(ud as u64)));
+                push_ior(vm, &result);
+            },
+            Operation::REPRESENT => {
+                let (addr, length): (i64, i64) = two_ints_from_stack(vm)?;
+                let r: f64 = float_from_stack(vm)?;
+                if !r.is_finite() {
+                    vm.data_stack.push(Data::NUMBER(0));
+                    vm.data_stack.push(Data::NUMBER(r.is_sign_negative() as i64));
+                    vm.data_stack.push(Data::NUMBER(false as i64));
+                } else {
+                    // Digits beyond the most a float has to give are zeros
+                    let length: i64 = cmp::max(length, 0);
+                    vm.memory.slice(addr, length)?;
+                    let (digits, n): (String, i64) = represent(r, cmp::min(length, MAX_PRECISION) as usize);
+                    vm.memory.write(addr, digits.as_bytes())?;
+                    vm.memory.fill(addr.wrapping_add(digits.len() as i64), length - digits.len() as i64, b'0')?;
+                    vm.data_stack.push(Data::NUMBER(n));
+                    vm.data_stack.push(Data::NUMBER((r < 0.0) as i64));  // todo: bool bits
+                    vm.data_stack.push(Data::NUMBER(true as i64));
+                }
+            },
+            Operation::REQUIRE => {
+                let name: String = expect_name(vm)?;
+                if !vm.files.is_included(&vm.files.resolve(&name)) {
+                    let (path, source): (PathBuf, String) = read_source(vm, &name)?;
+                    begin_include(vm, &path, &source)?;
+                }
+            },
+            Operation::REQUIRED => {
+                let name: String = file_name_from_stack(vm)?;
+                if !vm.files.is_included(&vm.files.resolve(&name)) {
+                    let (path, source): (PathBuf, String) = read_source(vm, &name)?;
+                    begin_include(vm, &path, &source)?;
+                }
+            },
+            Operation::ROLL_BACK(xt) => {
+                let marker: Marker = match vm.markers.iter().find(|marker| marker.xt == xt) {
+                    Some(marker) => marker.clone(),
+                    None => {
+                        return Result::Err(
+                            VirtualMachineError {
+                                msg: format!("not a marker: {}", xt),
+                                ..VirtualMachineError::default()
+                            }
+                        );
+                    },
+                };
+
+                // The marker's own frame ends with it
+                if matches!(vm.return_stack.last(), Some(frame) if frame.xt == xt) {
+                    vm.return_stack.pop();
+                }
+                vm.forget(xt)?;
+                vm.dictionary.wordlists.truncate(marker.wordlists);
+                vm.dictionary.current = marker.current;
+                vm.dictionary.set_order(marker.search_order)?;
+            },
+            Operation::ROT => {
+                let (x1, x2, x3): (i64, i64, i64) = three_ints_from_stack(vm)?;
+                vm.data_stack.push(Data::NUMBER(x2));
+                vm.data_stack.push(Data::NUMBER(x3));
+                vm.data_stack.push(Data::NUMBER(x1));
+            },
+            Operation::S_BACKSLASH_QUOTE => {
+                let string: Vec<u8> = parse_escaped(vm)?;
+                match vm.compiling {
+                    Some(_) => compile_string(vm, &string)?,
+                    None => push_transient_string(vm, &string)?,
+                }
+            },
+            Operation::S_QUOTE => {
+                let string: String = parse_delimited(vm, b'"');
+                match vm.compiling {
+                    Some(_) => compile_string(vm, string.as_bytes())?,
+                    None => push_transient_string(vm, string.as_bytes())?,
+                }
+            },
+            Operation::S_TO_D => {
+                let n: i64 = int_from_stack(vm)?;
+                vm.data_stack.push(Data::NUMBER(n));
+                vm.data_stack.push(Data::NUMBER(match n < 0 {
+                    true => -1,
+                    false => 0,
+                }));
+            },
+            Operation::S_TO_F => {
+                let n: i64 = int_from_stack(vm)?;
+                vm.float_stack.push(n as f64);
+            },
+            Operation::SEARCH => {
+                let (addr1, length1, addr2, length2): (i64, i64, i64, i64) = four_ints_from_stack(vm)?;
+                let string1: &[u8] = vm.memory.slice(addr1, length1)?;
+                let string2: &[u8] = vm.memory.slice(addr2, length2)?;
+                let offset: Option<usize> = match string2.is_empty() {
+                    true => Some(0),
+                    false => string1.windows(string2.len()).position(|window| window == string2),
+                };
+                match offset {
+                    Some(offset) => {
+                        vm.data_stack.push(Data::NUMBER(addr1 + offset as i64));
+                        vm.data_stack.push(Data::NUMBER(length1 - offset as i64));
+                        vm.data_stack.push(Data::NUMBER(true as i64));  // todo: bool bits
+                    },
+                    None => {
+                        vm.data_stack.push(Data::NUMBER(addr1));
+                        vm.data_stack.push(Data::NUMBER(length1));
+                        vm.data_stack.push(Data::NUMBER(false as i64));
+                    },
+                }
+            },
+            Operation::SEARCH_WORDLIST => {
+                let (addr, length, wid): (i64, i64, i64) = three_ints_from_stack(vm)?;
+                let wid: usize = vm.dictionary.wordlist(wid)?;
+                let name: String = String::from_utf8_lossy(vm.memory.slice(addr, length)?).into_owned();
+                match vm.dictionary.search(wid, &name) {
+                    Some(xt) => {
+                        vm.data_stack.push(Data::NUMBER(xt as i64));
+                        vm.data_stack.push(Data::NUMBER(match vm.definitions[xt].immediate {
+                            true => 1,
+                            false => -1,
+                        }));
+                    },
+                    None => {
+                        vm.data_stack.push(Data::NUMBER(0));
+                    },
+                }
+            },
+            Operation::SEMICOLON => {
+                if !vm.enclosing.is_empty() {
+                    return Result::Err(
+                        VirtualMachineError {
+                            msg: String::from("expected ;]"),
+                            ..VirtualMachineError::default()
+                        }
+                    );
+                }
+                let xt: usize = end_definition(vm)?;
+                vm.reveal(xt);
+            },
+            Operation::SEMICOLON_BRACKET => {
+                if vm.enclosing.is_empty() {
+                    return Result::Err(
+                        VirtualMachineError {
+                            msg: String::from("expected [: before ;]"),
+                            ..VirtualMachineError::default()
+                        }
+                    );
+                }
+                let xt: usize = end_definition(vm)?;
+                let enclosing: Enclosing = vm.enclosing.pop().unwrap();
+                vm.compiling = enclosing.xt;
+                vm.locals = enclosing.locals;
+                match vm.compiling {
+                    Some(_) => {
+                        vm.compile(Operation::LITERAL(xt as i64))?;
+                    },
+                    None => {
+                        vm.data_stack.push(Data::NUMBER(xt as i64));
+                    },
+                }
+            },
+            Operation::SET_CURRENT => {
+                let wid: i64 = int_from_stack(vm)?;
+                vm.dictionary.current = vm.dictionary.wordlist(wid)?;
+            },
+            Operation::SET_ORDER => {
+                // The stack is left as it was unless the search order is set
+                let depth: usize = vm.data_stack.len();
+                let count: Option<usize> = match vm.data_stack.last() {
+                    Some(Data::NUMBER(-1)) => Some(0),
+                    Some(Data::NUMBER(n)) if *n >= 0 && (*n as usize) < depth => Some(*n as usize),
+                    _ => None,
+                };
+                let count: usize = count.ok_or_else(|| {
+                    VirtualMachineError {
+                        msg: String::from("stack underflow"),
+                        ..VirtualMachineError::default()
+                    }
+                })?;
+                let wids: &[Data] = &vm.data_stack[depth - 1 - count..depth - 1];
+                let search_order: Vec<usize> = match vm.data_stack[depth - 1] {
+                    Data::NUMBER(-1) => vec![FORTH_WORDLIST],
+                    _ => wids.iter().map(|Data::NUMBER(wid)| vm.dictionary.wordlist(*wid)).collect::<Result<_, _>>()?,
+                };
+                vm.dictionary.set_order(search_order)?;
+                vm.data_stack.truncate(depth - 1 - count);
+            },
+            Operation::SET_PRECISION => {
+                let precision: i64 = int_from_stack(vm)?;
+                if !(1..=MAX_PRECISION).contains(&precision) {
+                    return Result::Err(
+                        VirtualMachineError {
+                            msg: format!("precision must be 1 through {}", MAX_PRECISION),
+                            ..VirtualMachineError::default()
+                        }
+                    );
+                }
+                vm.memory.store_cell(PRECISION_ADDR as i64, precision)?;
+            },
+            Operation::SF_ALIGN => {
+                let here: i64 = vm.memory.here() as i64;
+                vm.allot(aligned(here, SFLOAT_SIZE) - here).map_err(|err| retry(vm, operation, err))?;
+            },
+            Operation::SF_ALIGNED => {
+                let addr: i64 = int_from_stack(vm)?;
+                vm.data_stack.push(Data::NUMBER(aligned(addr, SFLOAT_SIZE)));
+            },
+            Operation::SF_FETCH => {
+                let addr: i64 = int_from_stack(vm)?;
+                let r: f64 = vm.memory.fetch_sfloat(addr)?;
+                vm.float_stack.push(r);
+            },
+            Operation::SF_STORE => {
+                let addr: i64 = int_from_stack(vm)?;
+                let r: f64 = float_from_stack(vm)?;
+                vm.memory.store_sfloat(addr, r)?;
+            },
+            Operation::SFLOAT_PLUS => {
+                let addr: i64 = int_from_stack(vm)?;
+                vm.data_stack.push(Data::NUMBER(addr.wrapping_add(SFLOAT_SIZE as i64)));
+            },
+            Operation::SFLOATS => {
+                let n: i64 = int_from_stack(vm)?;
+                vm.data_stack.push(Data::NUMBER(n.wrapping_mul(SFLOAT_SIZE as i64)));
+            },
+            Operation::SIGN => {
+                let n: i64 = int_from_stack(vm)?;
+                if n < 0 {
+                    vm.memory.hold(b"-")?;
+                }
+            },
+            Operation::SLASH_STRING => {
+                let (addr, length, n): (i64, i64, i64) = three_ints_from_stack(vm)?;
+                vm.data_stack.push(Data::NUMBER(addr.wrapping_add(n)));
+                vm.data_stack.push(Data::NUMBER(length.wrapping_sub(n)));
+            },
+            Operation::SLITERAL => {
+                vm.current_definition()?;
+                let (addr, length): (i64, i64) = two_ints_from_stack(vm)?;
+                let string: Vec<u8> = vm.memory.slice(addr, length)?.to_vec();
+                compile_string(vm, &string)?;
+            },
+            Operation::SPACE => {
+                display(vm, b" ")?;
+            },
+            Operation::SPACES => {
+                let n: i64 = int_from_stack(vm)?;
+                display_spaces(vm, n)?;
+            },
+            Operation::STORE => {
+                let (x, addr): (i64, i64) = two_ints_from_stack(vm)?;
+                vm.memory.store_cell(addr, x)?;
+            },
+            Operation::SUB => {
+                let (n1, n2): (i64, i64) = two_ints_from_stack(vm)?;
+                let n3: i64 = n1.wrapping_sub(n2);
+                vm.data_stack.push(
+                    Data::NUMBER(n3),
+                );
+            },
+            Operation::SUBSTITUTE => {
+                let (addr1, length1, addr2, length2): (i64, i64, i64, i64) = four_ints_from_stack(vm)?;
+                let text: Vec<u8> = vm.memory.slice(addr1, length1)?.to_vec();
+                let (result, substitutions): (Vec<u8>, i64) = substitute(vm, &text);
+                let fits: bool = result.len() as i64 <= length2;
+                let length3: usize = cmp::min(result.len(), cmp::max(length2, 0) as usize);
+                vm.memory.write(addr2, &result[..length3])?;
+                vm.data_stack.push(Data::NUMBER(addr2));
+                vm.data_stack.push(Data::NUMBER(length3 as i64));
+                vm.data_stack.push(Data::NUMBER(match fits {
+                    true => substitutions,
+                    false => -1,
+                }));
+            },
+            Operation::SWAP => {
+                let (x1, x2): (i64, i64) = two_ints_from_stack(vm)?;
+                vm.data_stack.push(Data::NUMBER(x2));
+                vm.data_stack.push(Data::NUMBER(x1));
+            },
+            Operation::TAIL_CALL(xt) => {
+                tail_call(vm, xt)?;
+            },
+            Operation::THEN => {
+                let orig: i64 = int_from_stack(vm)?;
+                resolve(vm, orig)?;
+            },
+            Operation::TICK => {
+                let xt: usize = parse_xt(vm)?;
+                vm.data_stack.push(Data::NUMBER(xt as i64));
+            },
+            Operation::TO => {
+                let name: String = expect_name(vm)?;
+                match vm.find_local(&name) {
+                    Some(local) => {
+                        vm.compile(Operation::LOCAL_STORE(local))?;
+                    },
+                    None => {
+                        return Result::Err(
+                            VirtualMachineError {
+                                msg: format!("not a local: {}", name),
+                                ..VirtualMachineError::default()
+                            }
+                        );
+                    },
+                }
+            },
+            Operation::TO_FLOAT => {
+                let (addr, length): (i64, i64) = two_ints_from_stack(vm)?;
+                let text: String = String::from_utf8_lossy(vm.memory.slice(addr, length)?).into_owned();
+                let r: Option<f64> = match text.trim_matches(' ') {
+                    "" => Some(0.0),
+                    text => parse_float(text, false),
+                };
+                match r {
+                    Some(r) => {
+                        vm.float_stack.push(r);
+                        vm.data_stack.push(Data::NUMBER(true as i64));  // todo: bool bits
+                    },
+                    None => {
+                        vm.data_stack.push(Data::NUMBER(false as i64));
+                    },
+                }
+            },
+            Operation::TYPE => {
+                let (addr, length): (i64, i64) = two_ints_from_stack(vm)?;
+                let string: Vec<u8> = vm.memory.slice(addr, length)?.to_vec();
+                display(vm, &string)?;
+            },
+            Operation::UNESCAPE => {
+                let (addr1, length1, addr2): (i64, i64, i64) = three_ints_from_stack(vm)?;
+                let mut result: Vec<u8> = Vec::new();
+                for char in vm.memory.slice(addr1, length1)? {
+                    if *char == b'%' {
+                        result.push(b'%');
+                    }
+                    result.push(*char);
+                }
+                vm.memory.write(addr2, &result)?;
+                vm.data_stack.push(Data::NUMBER(addr2));
+                vm.data_stack.push(Data::NUMBER(result.len() as i64));
+            },
+            Operation::UNLOOP => {
+                loops(vm)?.pop();
+            },
+            Operation::VOCABULARY => {
+                let name: String = expect_name(vm)?;
+                let wid: usize = vm.dictionary.create(Some(name.clone()));
+                vm.define(
+                    Definition {
+                        name,
+                        body: vec![Operation::ORDER_TOP(wid)],
+                        ..Definition::default()
+                    }
+                );
+            },
+            Operation::WORDLIST => {
+                let wid: usize = vm.dictionary.create(None);
+                vm.data_stack.push(Data::NUMBER(wid as i64));
+            },
+            Operation::W_O => {
+                vm.data_stack.push(Data::NUMBER(WRITE_ONLY));
+            },
+            Operation::WRITE_FILE => {
+                let (addr, length, fileid): (i64, i64, i64) = three_ints_from_stack(vm)?;
+                let bytes: Vec<u8> = vm.memory.slice(addr, length)?.to_vec();
+                let result: io::Result<()> = vm.files.get(fileid).and_then(|file| file.write_all(&bytes));
+                push_ior(vm, &result);
+            },
+            Operation::WRITE_LINE => {
+                let (addr, length, fileid): (i64, i64, i64) = three_ints_from_stack(vm)?;
+                let mut bytes: Vec<u8> = vm.memory.slice(addr, length)?.to_vec();
+                bytes.push(b'\n');
+                let result: io::Result<()> = vm.files.get(fileid).and_then(|file| file.write_all(&bytes));
+                push_ior(vm, &result);
+            },
+            Operation::ZERO_EQ => {
+                let n: i64 = int_from_stack(vm)?;
+                let flag: bool = n == 0;
+                vm.data_stack.push(Data::NUMBER(flag as i64));  // todo: bool bits
+            },
+            Operation::ZERO_GT => {
+                let n: i64 = int_from_stack(vm)?;
+                let flag: bool = n > 0;
+                vm.data_stack.push(Data::NUMBER(flag as i64));  // todo: bool bits
+            },
+            Operation::ZERO_LT => {
+                let n: i64 = int_from_stack(vm)?;
+                let flag: bool = n < 0;
+                vm.data_stack.push(Data::NUMBER(flag as i64));  // todo: bool bits
+            },
+            Operation::ZERO_NE => {
+                let n: i64 = int_from_stack(vm)?;
+                let flag: bool = n != 0;
+                vm.data_stack.push(Data::NUMBER(flag as i64));  // todo: bool bits
+            },
+
+        }
+        vm._ops_applied += 1;
+        check_limits(vm)?;
+    }
+    return Result::Ok(Outcome::COMPLETED);
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+
+    use std::cell::Cell;
+    use std::collections::VecDeque;
+    use std::rc::Rc;
+
+    use super::Data;
+
+    use super::super::io::{BufferSink, BufferSource, OutputSink, Script, ScriptedSource};
+    use super::super::files::{Access, Policy, IOR_FAILURE, IOR_NOT_FOUND};
+    use super::super::memory::DATA_SPACE_ADDR;
+
+    /// Value placed at the bottom of the stack by tests that expect it to be ignored
+    const BOTTOM_OF_STACK: i64 = 0x0BAD;
+
+    /// Interpret a line of Forth, one word at a time, as the REPL does.
+    /// Errors abandon the rest of the line, and any definition being compiled.
+    fn interpret(vm: &mut VM, line: &str) -> Result<Outcome, String> {
+        let result: Result<Outcome, String> = interpret_line(vm, line);
+        if result.is_err() {
+            vm.reset();
+        }
+        return result;
+    }
+
+    fn interpret_line(vm: &mut VM, line: &str) -> Result<Outcome, String> {
+        return evaluate(vm, line).map_err(|err| err.msg);
+    }
+
+    /// Collect the VM's output in memory, returning a handle to read it back
+    fn capture_output(vm: &mut VM) -> BufferSink {
+        let output: BufferSink = BufferSink::default();
+        vm.output = Box::new(output.clone());
+        return output;
+    }
+
+    /// Allot space for `text` in data space, copy it there, and return its address.
+    fn allot_string(vm: &mut VM, text: &str) -> i64 {
+        let addr: i64 = vm.memory.here() as i64;
+        vm.allot(text.len() as i64).unwrap();
+        vm.memory.write(addr, text.as_bytes()).unwrap();
+        return addr;
+    }
+
+    /// Pop a c-addr u pair off the stack and read the string it refers to.
+    fn string_from_stack(vm: &mut VM) -> String {
+        let (addr, length): (i64, i64) = two_ints_from_stack(vm).unwrap();
+        return String::from_utf8(vm.memory.slice(addr, length).unwrap().to_vec()).unwrap();
+    }
+
+    /// Helper macro to confirm operations that require data on the stack produce an
+    /// error if the stack doesn't contain enough data.
+    macro_rules! empty_stack_test_case {
+        ($vm:expr, $operation:expr) => {{
+            assert!($vm.data_stack.is_empty());
+            $vm.operations.push_back($operation);
+            assert!(execute(&mut $vm).is_err());
+        }};
+    }
+
+    /// Helper macro to test operations that take and return a single value.
+    macro_rules! single_value_op_test_case {
+        ($vm:expr, $value:expr, $operation:expr, $expected:expr) => {{
+            $vm.data_stack = vec![
+                Data::NUMBER(BOTTOM_OF_STACK),
+                Data::NUMBER($value),
+            ];
+            $vm.operations = VecDeque::from([$operation]);
+            assert!(execute(&mut $vm).is_ok());
+            assert_eq!(
+                $vm.data_stack,
+                vec![
+                    Data::NUMBER(BOTTOM_OF_STACK),
+                    Data::NUMBER($expected),
+                ]
+            );
+        }};
+    }
+
+    /// Helper macro to test operations that pop two values and push a single value.
+    macro_rules! two_in_one_out_op_test_case {
+        ($vm:expr, $value1:expr, $value2:expr, $operation:expr, $expected:expr) => {{
+            $vm.data_stack = vec![
+                Data::NUMBER(BOTTOM_OF_STACK),
+                Data::NUMBER($value1),
+                Data::NUMBER($value2),
+            ];
+            $vm.operations = VecDeque::from([$operation]);
+            assert!(execute(&mut $vm).is_ok());
+            assert_eq!(
+                $vm.data_stack,
+                vec![
+                    Data::NUMBER(BOTTOM_OF_STACK),
+                    Data::NUMBER($expected),
+                ]
+            );
+        }};
+    }
+
+    #[test]
+    fn base_interpret_test() {
+        let mut vm: VM = VM::default();
+        vm.operations.push_back(Operation::NOP);
+        vm.operations.push_back(Operation::NOP);
+        assert!(execute(&mut vm).is_ok());
+    }
+
+    #[test]
+    fn operation_test__single_value_ops() {
+        let mut vm: VM = VM::default();
+
+        // case:  stack underflow error on empty stack
+        empty_stack_test_case!(vm, Operation::ABS);
+        empty_stack_test_case!(vm, Operation::NEGATE);
+
+        single_value_op_test_case!(vm, 42, Operation::ABS, 42);
+        single_value_op_test_case!(vm, -42, Operation::ABS, 42);
+
+        single_value_op_test_case!(vm,  0, Operation::NEGATE,  0);
+        single_value_op_test_case!(vm,  1, Operation::NEGATE, -1);
+        single_value_op_test_case!(vm, -1, Operation::NEGATE,  1);
+
+    }
+
+    #[test]
+    fn operation_test__arithmetic() {
+        let mut vm: VM = VM::default();
+
+        empty_stack_test_case!(vm, Operation::ADD);
+        empty_stack_test_case!(vm, Operation::SUB);
+        empty_stack_test_case!(vm, Operation::MUL);
+        empty_stack_test_case!(vm, Operation::DIV);
+        empty_stack_test_case!(vm, Operation::MOD);
+
+        two_in_one_out_op_test_case!(vm,  0,  0, Operation::ADD,  0);
+        two_in_one_out_op_test_case!(vm,  0,  1, Operation::ADD,  1);
+        two_in_one_out_op_test_case!(vm,  1,  0, Operation::ADD,  1);
+        two_in_one_out_op_test_case!(vm,  1,  1, Operation::ADD,  2);
+        two_in_one_out_op_test_case!(vm,  0, -1, Operation::ADD, -1);
+        two_in_one_out_op_test_case!(vm, -1,  0, Operation::ADD, -1);
+        two_in_one_out_op_test_case!(vm, -1, -1, Operation::ADD, -2);
+
+        two_in_one_out_op_test_case!(vm,  0,  0, Operation::SUB,  0);
+        two_in_one_out_op_test_case!(vm,  0,  1, Operation::SUB, -1);
+        two_in_one_out_op_test_case!(vm,  1,  0, Operation::SUB,  1);
+        two_in_one_out_op_test_case!(vm,  1,  1, Operation::SUB,  0);
+        two_in_one_out_op_test_case!(vm,  0, -1, Operation::SUB,  1);
+        two_in_one_out_op_test_case!(vm, -1,  0, Operation::SUB, -1);
+        two_in_one_out_op_test_case!(vm, -1, -1, Operation::SUB,  0);
+
+        two_in_one_out_op_test_case!(vm,  0,  0, Operation::MUL,  0);
+        two_in_one_out_op_test_case!(vm,  0,  1, Operation::MUL,  0);
+        two_in_one_out_op_test_case!(vm,  1,  0, Operation::MUL,  0);
+        two_in_one_out_op_test_case!(vm,  1,  1, Operation::MUL,  1);
+        two_in_one_out_op_test_case!(vm,  0, -1, Operation::MUL,  0);
+        two_in_one_out_op_test_case!(vm, -1,  0, Operation::MUL,  0);
+        two_in_one_out_op_test_case!(vm, -1, -1, Operation::MUL,  1);
+        two_in_one_out_op_test_case!(vm,  1, -1, Operation::MUL, -1);
+        two_in_one_out_op_test_case!(vm, -1,  1, Operation::MUL, -1);
+
+        two_in_one_out_op_test_case!(vm,  0,  1, Operation::DIV,  0);
+        two_in_one_out_op_test_case!(vm,  1,  1, Operation::DIV,  1);
+        two_in_one_out_op_test_case!(vm,  0, -1, Operation::DIV,  0);
+        two_in_one_out_op_test_case!(vm, -1, -1, Operation::DIV,  1);
+        two_in_one_out_op_test_case!(vm,  1, -1, Operation::DIV, -1);
+        two_in_one_out_op_test_case!(vm, -1,  1, Operation::DIV, -1);
+
+        two_in_one_out_op_test_case!(vm,  0,  1, Operation::MOD,  0);
+        two_in_one_out_op_test_case!(vm,  1,  1, Operation::MOD,  0);
+        two_in_one_out_op_test_case!(vm,  2,  1, Operation::MOD,  0);
+        two_in_one_out_op_test_case!(vm,  1,  2, Operation::MOD,  1);
+        two_in_one_out_op_test_case!(vm, -1, -2, Operation::MOD,  1);
+        two_in_one_out_op_test_case!(vm,  1, -2, Operation::MOD,  1);
+        two_in_one_out_op_test_case!(vm, -1,  2, Operation::MOD,  1);
+
+    }
+
+    #[test]
+    fn operation_test__comparisons() {
+        let mut vm: VM = VM::default();
+
+        empty_stack_test_case!(vm, Operation::CMP_EQ);
+        empty_stack_test_case!(vm, Operation::CMP_GT);
+        empty_stack_test_case!(vm, Operation::CMP_LT);
+        empty_stack_test_case!(vm, Operation::CMP_NE);
+        empty_stack_test_case!(vm, Operation::MAX);
+        empty_stack_test_case!(vm, Operation::MIN);
+
+        two_in_one_out_op_test_case!(vm,  1,  1, Operation::CMP_EQ,  1);
+        two_in_one_out_op_test_case!(vm,  1,  0, Operation::CMP_EQ,  0);
+        two_in_one_out_op_test_case!(vm,  0,  1, Operation::CMP_EQ,  0);
+        two_in_one_out_op_test_case!(vm, -1, -1, Operation::CMP_EQ,  1);
+        two_in_one_out_op_test_case!(vm,  0, -1, Operation::CMP_EQ,  0);
+        two_in_one_out_op_test_case!(vm, -1,  0, Operation::CMP_EQ,  0);
+
+        two_in_one_out_op_test_case!(vm,  1,  1, Operation::CMP_GT,  0);
+        two_in_one_out_op_test_case!(vm,  1,  0, Operation::CMP_GT,  1);
+        two_in_one_out_op_test_case!(vm,  0,  1, Operation::CMP_GT,  0);
+        two_in_one_out_op_test_case!(vm, -1, -1, Operation::CMP_GT,  0);
+        two_in_one_out_op_test_case!(vm,  0, -1, Operation::CMP_GT,  1);
+        two_in_one_out_op_test_case!(vm, -1,  0, Operation::CMP_GT,  0);
+
+        two_in_one_out_op_test_case!(vm,  1,  1, Operation::CMP_LT,  0);
+        two_in_one_out_op_test_case!(vm,  1,  0, Operation::CMP_LT,  0);
+        two_in_one_out_op_test_case!(vm,  0,  1, Operation::CMP_LT,  1);
+        two_in_one_out_op_test_case!(vm, -1, -1, Operation::CMP_LT,  0);
+        two_in_one_out_op_test_case!(vm,  0, -1, Operation::CMP_LT,  0);
+        two_in_one_out_op_test_case!(vm, -1,  0, Operation::CMP_LT,  1);
+
+        two_in_one_out_op_test_case!(vm,  1,  1, Operation::CMP_NE,  0);
+        two_in_one_out_op_test_case!(vm,  1,  0, Operation::CMP_NE,  1);
+        two_in_one_out_op_test_case!(vm,  0,  1, Operation::CMP_NE,  1);
+        two_in_one_out_op_test_case!(vm, -1, -1, Operation::CMP_NE,  0);
+        two_in_one_out_op_test_case!(vm,  0, -1, Operation::CMP_NE,  1);
+        two_in_one_out_op_test_case!(vm, -1,  0, Operation::CMP_NE,  1);
+
+        two_in_one_out_op_test_case!(vm,  0,  0, Operation::MAX,  0);
+        two_in_one_out_op_test_case!(vm,  1,  0, Operation::MAX,  1);
+        two_in_one_out_op_test_case!(vm,  0,  1, Operation::MAX,  1);
+        two_in_one_out_op_test_case!(vm, -1, -1, Operation::MAX, -1);
+        two_in_one_out_op_test_case!(vm,  0, -1, Operation::MAX,  0);
+        two_in_one_out_op_test_case!(vm, -1,  0, Operation::MAX,  0);
+
+        two_in_one_out_op_test_case!(vm,  1,  1, Operation::MIN,  1);
+        two_in_one_out_op_test_case!(vm,  1,  0, Operation::MIN,  0);
+        two_in_one_out_op_test_case!(vm,  0,  1, Operation::MIN,  0);
+        two_in_one_out_op_test_case!(vm, -1, -1, Operation::MIN, -1);
+        two_in_one_out_op_test_case!(vm,  0, -1, Operation::MIN, -1);
+        two_in_one_out_op_test_case!(vm, -1,  0, Operation::MIN, -1);
+
+    }
+
+    #[test]
+    fn operation_test__drop() {
+        let mut vm: VM = VM::default();
+
+        // case:  stack underflow error on empty stack
+        empty_stack_test_case!(vm, Operation::DROP);
+
+        // case:  drop removes a single stack item from the top
+        vm.data_stack = vec![
+            Data::NUMBER(1),
+            Data::NUMBER(2),  // <- top of stack
+        ];
+        vm.operations = VecDeque::from([Operation::DROP]);
+        assert!(execute(&mut vm).is_ok());
+        assert_eq!(
+            vm.data_stack,
+            vec![
+                Data::NUMBER(1),
+            ]
+        );
+
+    }
+
+    #[test]
+    fn operation_test__dup() {
+        let mut vm: VM = VM::default();
+
+        // case:  stack underflow error on empty stack
+        empty_stack_test_case!(vm, Operation::DUP);
+
+        // case:  duplicates the top stack entry
+        vm.data_stack = vec![
+            Data::NUMBER(1),
+            Data::NUMBER(2),  // <- top of stack
+        ];
+        vm.operations = VecDeque::from([Operation::DUP]);
+        assert!(execute(&mut vm).is_ok());
+        assert_eq!(
+            vm.data_stack,
+            vec![
+                Data::NUMBER(1),
+                Data::NUMBER(2),
+                Data::NUMBER(2),  // <- top of stack
+            ]
+        );
+
+    }
+
+    #[test]
+    fn operation_test__zero_comparisons() {
+        let mut vm: VM = VM::default();
+
+        // case:  stack underflow error on empty stack
+        empty_stack_test_case!(vm, Operation::ZERO_EQ);
+        empty_stack_test_case!(vm, Operation::ZERO_GT);
+        empty_stack_test_case!(vm, Operation::ZERO_LT);
+        empty_stack_test_case!(vm, Operation::ZERO_NE);
+
+        // case:  normal comparisons
+        single_value_op_test_case!(vm, 1, Operation::ZERO_EQ, 0);
+        single_value_op_test_case!(vm, 0, Operation::ZERO_EQ, 1);
+        single_value_op_test_case!(vm, -1, Operation::ZERO_EQ, 0);
+
+        single_value_op_test_case!(vm, 1, Operation::ZERO_GT, 1);
+        single_value_op_test_case!(vm, 0, Operation::ZERO_GT, 0);
+        single_value_op_test_case!(vm, -1, Operation::ZERO_GT, 0);
+
+        single_value_op_test_case!(vm, 1, Operation::ZERO_LT, 0);
+        single_value_op_test_case!(vm, 0, Operation::ZERO_LT, 0);
+        single_value_op_test_case!(vm, -1, Operation::ZERO_LT, 1);
+
+        single_value_op_test_case!(vm, 1, Operation::ZERO_NE, 1);
+        single_value_op_test_case!(vm, 0, Operation::ZERO_NE, 0);
+        single_value_op_test_case!(vm, -1, Operation::ZERO_NE, 1);
+
+    }
+
+    #[test]
+    fn operation_test__string_literals() {
+        let mut vm: VM = VM::default();
+        vm.define_core_words();
+        let output: BufferSink = capture_output(&mut vm);
+
+        // case:  S" gives a c-addr u pair referring to data space
+        assert!(interpret(&mut vm, "S\" hello world\"\n").is_ok());
+        assert_eq!(string_from_stack(&mut vm), "hello world");
+
+        // case:  the parse area resumes after the closing quote
+        assert!(interpret(&mut vm, "1 S\" two\" 3\n").is_ok());
+        assert_eq!(vm.data_stack.pop(), Some(Data::NUMBER(3)));
+        assert_eq!(string_from_stack(&mut vm), "two");
+        assert_eq!(vm.data_stack, vec![Data::NUMBER(1)]);
+        vm.data_stack.clear();
+
+        // case:  two interpreted strings can be used at once
+        assert!(interpret(&mut vm, "S\" first\" S\" second\"\n").is_ok());
+        assert_eq!(string_from_stack(&mut vm), "second");
+        assert_eq!(string_from_stack(&mut vm), "first");
+
+        // case:  empty string
+        assert!(interpret(&mut vm, "S\" \"\n").is_ok());
+        assert_eq!(string_from_stack(&mut vm), "");
+
+        // case:  S\" translates escape sequences
+        assert!(interpret(&mut vm, "S\\\" a\\tb\\\"c\\x41\\\\\\m\"\n").is_ok());
+        assert_eq!(string_from_stack(&mut vm), "a\tb\"cA\\\r\n");
+        assert!(interpret(&mut vm, "S\\\" \\j\"\n").is_err());
+        assert!(interpret(&mut vm, "S\\\" \\x4\"\n").is_err());
+        assert!(interpret(&mut vm, "S\\\" unterminated\n").is_err());
+        vm.data_stack.clear();
+
+        // case:  C" gives a counted string, which COUNT converts to c-addr u
+        assert!(interpret(&mut vm, "C\" counted\"\n").is_ok());
+        let Data::NUMBER(addr) = vm.data_stack.last().unwrap().clone();
+        assert_eq!(vm.memory.fetch_byte(addr).unwrap(), 7);
+        assert!(interpret(&mut vm, "COUNT\n").is_ok());
+        assert_eq!(string_from_stack(&mut vm), "counted");
+
+        // case:  ." displays text and leaves nothing behind
+        assert!(interpret(&mut vm, ".\" hello\"\n").is_ok());
+        assert!(vm.data_stack.is_empty());
+        assert_eq!(output.contents(), "hello");
+        output.clear();
+
+        // case:  TYPE displays a c-addr u pair
+        assert!(interpret(&mut vm, "S\" hello\" TYPE C\" world\" COUNT TYPE\n").is_ok());
+        assert!(vm.data_stack.is_empty());
+        assert_eq!(output.contents(), "helloworld");
+        empty_stack_test_case!(vm, Operation::TYPE);
+        empty_stack_test_case!(vm, Operation::COUNT);
+
+    }
+
+    #[test]
+    fn operation_test__char() {
+        let mut vm: VM = VM::default();
+        vm.define_core_words();
+
+        assert!(interpret(&mut vm, "CHAR A CHAR bc [CHAR] 0\n").is_ok());
+        assert_eq!(
+            vm.data_stack,
+            vec![
+                Data::NUMBER(65),
+                Data::NUMBER(98),
+                Data::NUMBER(48),
+            ]
+        );
+
+        // case:  a name is required
+        vm.data_stack.clear();
+        assert!(interpret(&mut vm, "CHAR \n").is_err());
+        assert!(vm.data_stack.is_empty());
+    }
+
+    #[test]
+    fn operation_test__data_space() {
+        let mut vm: VM = VM::default();
+        vm.define_core_words();
+
+        assert!(interpret(&mut vm, "HERE\n").is_ok());
+        assert_eq!(vm.data_stack.pop(), Some(Data::NUMBER(DATA_SPACE_ADDR as i64)));
+
+        // case:  store and fetch cells and characters in allotted space
+        assert!(interpret(&mut vm, "16 ALLOT\n").is_ok());
+        let addr: i64 = DATA_SPACE_ADDR as i64;
+        assert!(interpret(&mut vm, &format!("7 NEGATE {} !\n", addr)).is_ok());
+        assert!(interpret(&mut vm, &format!("{} @\n", addr)).is_ok());
+        assert_eq!(vm.data_stack.pop(), Some(Data::NUMBER(-7)));
+        assert!(interpret(&mut vm, &format!("65 {} C! {} C@\n", addr + 8, addr + 8)).is_ok());
+        assert_eq!(vm.data_stack.pop(), Some(Data::NUMBER(65)));
+        assert!(interpret(&mut vm, &format!("{} 1 TYPE\n", addr + 8)).is_ok());
+
+        // case:  accesses outside of data space are errors
+        assert!(interpret(&mut vm, &format!("{} @\n", addr + 9)).is_err());
+        assert!(interpret(&mut vm, "0 1 NEGATE C!\n").is_err());
+        vm.data_stack.clear();
+
+        empty_stack_test_case!(vm, Operation::FETCH);
+        empty_stack_test_case!(vm, Operation::STORE);
+        empty_stack_test_case!(vm, Operation::C_FETCH);
+        empty_stack_test_case!(vm, Operation::C_STORE);
+        empty_stack_test_case!(vm, Operation::ALLOT);
+    }
+
+    #[test]
+    fn operation_test__compare_and_search() {
+        let mut vm: VM = VM::default();
+        vm.define_core_words();
+
+        macro_rules! compare_test_case {
+            ($string1:expr, $string2:expr, $expected:expr) => {{
+                let line: String = format!("S\" {}\" S\" {}\" COMPARE\n", $string1, $string2);
+                assert!(interpret(&mut vm, &line).is_ok());
+                assert_eq!(vm.data_stack, vec![Data::NUMBER($expected)]);
+                vm.data_stack.clear();
+            }};
+        }
+
+        compare_test_case!("abc", "abc", 0);
+        compare_test_case!("abc", "abd", -1);
+        compare_test_case!("abd", "abc", 1);
+        compare_test_case!("ab", "abc", -1);
+        compare_test_case!("abc", "ab", 1);
+        compare_test_case!("", "", 0);
+        compare_test_case!("ABC", "abc", -1);
+
+        // case:  found, the remainder of the string starts at the match
+        assert!(interpret(&mut vm, "S\" hello world\" S\" o w\" SEARCH\n").is_ok());
+        assert_eq!(vm.data_stack.pop(), Some(Data::NUMBER(1)));
+        assert_eq!(string_from_stack(&mut vm), "o world");
+
+        // case:  not found, the string is unchanged
+        assert!(interpret(&mut vm, "S\" hello world\" S\" xyz\" SEARCH\n").is_ok());
+        assert_eq!(vm.data_stack.pop(), Some(Data::NUMBER(0)));
+        assert_eq!(string_from_stack(&mut vm), "hello world");
+
+        // case:  an empty string is always found
+        assert!(interpret(&mut vm, "S\" hello\" S\" \" SEARCH\n").is_ok());
+        assert_eq!(vm.data_stack.pop(), Some(Data::NUMBER(1)));
+        assert_eq!(string_from_stack(&mut vm), "hello");
+
+        empty_stack_test_case!(vm, Operation::COMPARE);
+        empty_stack_test_case!(vm, Operation::SEARCH);
+    }
+
+    #[test]
+    fn operation_test__string_adjustment() {
+        let mut vm: VM = VM::default();
+        vm.define_core_words();
+
+        assert!(interpret(&mut vm, "S\" hello world\" 6 /STRING\n").is_ok());
+        assert_eq!(string_from_stack(&mut vm), "world");
+        assert!(interpret(&mut vm, "9223372036854775807 0 1 /STRING\n").is_ok());
+        assert_eq!(vm.data_stack, vec![Data::NUMBER(i64::MIN), Data::NUMBER(-1)]);
+        vm.data_stack.clear();
+
+        assert!(interpret(&mut vm, "S\" trailing   \" -TRAILING\n").is_ok());
+        assert_eq!(string_from_stack(&mut vm), "trailing");
+
+        assert!(interpret(&mut vm, "S\"    \" -TRAILING\n").is_ok());
+        assert_eq!(string_from_stack(&mut vm), "");
+
+        // case:  fill with spaces
+        let addr: i64 = allot_string(&mut vm, "abcdef");
+        assert!(interpret(&mut vm, &format!("{} 1 + 3 BLANK\n", addr)).is_ok());
+        assert_eq!(vm.memory.slice(addr, 6).unwrap(), b"a   ef");
+        assert!(interpret(&mut vm, &format!("{} 9223372036854775807 BLANK\n", addr)).is_err());
+
+        empty_stack_test_case!(vm, Operation::SLASH_STRING);
+        empty_stack_test_case!(vm, Operation::DASH_TRAILING);
+        empty_stack_test_case!(vm, Operation::BLANK);
+    }
+
+    #[test]
+    fn operation_test__cmove() {
+        let mut vm: VM = VM::default();
+        vm.define_core_words();
+
+        let addr: i64 = allot_string(&mut vm, "abcdef");
+
+        // case:  copies between distinct regions
+        let copy: i64 = allot_string(&mut vm, "......");
+        assert!(interpret(&mut vm, &format!("{} {} 6 CMOVE\n", addr, copy)).is_ok());
+        assert_eq!(vm.memory.slice(copy, 6).unwrap(), b"abcdef");
+
+        // case:  overlapping CMOVE propagates the lowest characters upward
+        assert!(interpret(&mut vm, &format!("{} {} 1 + 5 CMOVE\n", copy, copy)).is_ok());
+        assert_eq!(vm.memory.slice(copy, 6).unwrap(), b"aaaaaa");
+
+        // case:  overlapping CMOVE> shifts the string up by one
+        assert!(interpret(&mut vm, &format!("{} {} 1 + 5 CMOVE>\n", addr, addr)).is_ok());
+        assert_eq!(vm.memory.slice(addr, 6).unwrap(), b"aabcde");
+
+        // case:  copying nothing is fine
+        assert!(interpret(&mut vm, &format!("{} {} 0 CMOVE\n", addr, copy)).is_ok());
+        assert!(vm.data_stack.is_empty());
+
+        empty_stack_test_case!(vm, Operation::CMOVE);
+        empty_stack_test_case!(vm, Operation::CMOVE_UP);
+    }
+
+    #[test]
+    fn operation_test__substitution() {
+        let mut vm: VM = VM::default();
+        vm.define_core_words();
+
+        assert!(interpret(&mut vm, "S\" Forth\" S\" lang\" REPLACES\n").is_ok());
+        assert!(interpret(&mut vm, "S\" 2012\" S\" Year\" REPLACES\n").is_ok());
+        assert!(vm.data_stack.is_empty());
+
+        // case:  names are case-insensitive, %% is a literal %, unknown names pass through
+        let buffer: i64 = allot_string(&mut vm, &" ".repeat(64));
+        let line: String = format!("S\" %LANG% %year% 100%% %unknown% 50%\" {} 64 SUBSTITUTE\n", buffer);
+        assert!(interpret(&mut vm, &line).is_ok());
+        assert_eq!(vm.data_stack.pop(), Some(Data::NUMBER(2)));
+        assert_eq!(string_from_stack(&mut vm), "Forth 2012 100% %unknown% 50%");
+
+        // case:  the closing % of an unknown name doesn't open another
+        let line: String = format!("S\" %a%lang%\" {} 64 SUBSTITUTE\n", buffer);
+        assert!(interpret(&mut vm, &line).is_ok());
+        assert_eq!(vm.data_stack.pop(), Some(Data::NUMBER(0)));
+        assert_eq!(string_from_stack(&mut vm), "%a%lang%");
+
+        // case:  the result doesn't fit in the buffer
+        let line: String = format!("S\" %lang% %lang%\" {} 4 SUBSTITUTE\n", buffer);
+        assert!(interpret(&mut vm, &line).is_ok());
+        assert!(matches!(vm.data_stack.pop(), Some(Data::NUMBER(n)) if n < 0));
+        assert_eq!(string_from_stack(&mut vm), "Fort");
+
+        // case:  UNESCAPE protects text from substitution
+        let line: String = format!("S\" 100% %lang%\" {} UNESCAPE\n", buffer);
+        assert!(interpret(&mut vm, &line).is_ok());
+        assert_eq!(string_from_stack(&mut vm), "100%% %%lang%%");
+        let line: String = format!("{} 14 {} 32 + 32 SUBSTITUTE\n", buffer, buffer);
+        assert!(interpret(&mut vm, &line).is_ok());
+        assert_eq!(vm.data_stack.pop(), Some(Data::NUMBER(0)));
+        assert_eq!(string_from_stack(&mut vm), "100% %lang%");
+
+        empty_stack_test_case!(vm, Operation::REPLACES);
+        empty_stack_test_case!(vm, Operation::SUBSTITUTE);
+        empty_stack_test_case!(vm, Operation::UNESCAPE);
+    }
+
+    #[test]
+    fn operation_test__sliteral() {
+        let mut vm: VM = VM::default();
+        vm.define_core_words();
+
+        // case:  there are no definitions to compile into
+        assert!(interpret(&mut vm, "S\" text\" SLITERAL\n").is_err());
+        vm.data_stack.clear();
+
+        // the string is copied, so it outlives the transient region it came from
+        assert!(interpret(&mut vm, "S\" text\" : TEXT SLITERAL ;\n").is_ok());
+        assert!(interpret(&mut vm, "S\" a\" S\" b\" DROP DROP DROP DROP TEXT\n").is_ok());
+        assert_eq!(string_from_stack(&mut vm), "text");
+    }
+
+    #[test]
+    fn operation_test__output() {
+        let mut vm: VM = VM::default();
+        vm.define_core_words();
+        let output: BufferSink = capture_output(&mut vm);
+
+        macro_rules! output_test_case {
+            ($line:expr, $expected:expr) => {{
+                output.clear();
+                assert!(interpret(&mut vm, $line).is_ok());
+                assert_eq!(output.contents(), $expected);
+            }};
+        }
+
+        output_test_case!("1 2 + .\n", "3 ");
+        output_test_case!("5 NEGATE . 0 .\n", "-5 0 ");
+        output_test_case!("42 6 .R 1 NEGATE 3 .R\n", "    42 -1");
+        output_test_case!("123 1 .R\n", "123");
+        output_test_case!("72 EMIT 105 EMIT CR\n", "Hi\n");
+        output_test_case!("SPACE 3 SPACES 0 SPACES 1 NEGATE SPACES CHAR | EMIT\n", "    |");
+        output_test_case!("PAGE\n", "\x1b[2J\x1b[H");
+
+        // case:  .S displays the stack, bottom first, without changing it
+        output_test_case!(".S\n", "<0> ");
+        output_test_case!("1 2 3 .S\n", "<3> 1 2 3 ");
+        assert_eq!(vm.data_stack.len(), 3);
+        vm.data_stack.clear();
+
+        // case:  ? displays the value stored at an address
+        let addr: i64 = vm.memory.here() as i64;
+        vm.allot(8).unwrap();
+        output_test_case!(&format!("99 {} ! {} ?\n", addr, addr), "99 ");
+
+        // case:  output made before an error is kept
+        output.clear();
+        assert!(interpret(&mut vm, "1 . .\n").is_err());
+        assert_eq!(output.contents(), "1 ");
+
+        // case:  output errors are reported
+        struct BrokenSink;
+        impl OutputSink for BrokenSink {
+            fn write(&mut self, _bytes: &[u8]) -> std::io::Result<()> {
+                return Result::Err(std::io::Error::from(std::io::ErrorKind::BrokenPipe));
+            }
+        }
+        vm.output = Box::new(BrokenSink);
+        assert!(interpret(&mut vm, "1 .\n").is_err());
+        assert!(interpret(&mut vm, "CR\n").is_err());
+
+        empty_stack_test_case!(vm, Operation::DOT);
+        empty_stack_test_case!(vm, Operation::DOT_R);
+        empty_stack_test_case!(vm, Operation::EMIT);
+        empty_stack_test_case!(vm, Operation::QUESTION);
+        empty_stack_test_case!(vm, Operation::SPACES);
+    }
+
+    #[test]
+    fn operation_test__stack_manipulation() {
+        let mut vm: VM = VM::default();
+        vm.define_core_words();
+
+        assert!(interpret(&mut vm, "1 2 SWAP\n").is_ok());
+        assert_eq!(vm.data_stack, vec![Data::NUMBER(2), Data::NUMBER(1)]);
+        vm.data_stack.clear();
+
+        assert!(interpret(&mut vm, "1 2 OVER\n").is_ok());
+        assert_eq!(vm.data_stack, vec![Data::NUMBER(1), Data::NUMBER(2), Data::NUMBER(1)]);
+        vm.data_stack.clear();
+
+        assert!(interpret(&mut vm, "1 2 3 ROT\n").is_ok());
+        assert_eq!(vm.data_stack, vec![Data::NUMBER(2), Data::NUMBER(3), Data::NUMBER(1)]);
+        vm.data_stack.clear();
+
+        assert!(interpret(&mut vm, "5 S>D 5 NEGATE S>D\n").is_ok());
+        assert_eq!(
+            vm.data_stack,
+            vec![
+                Data::NUMBER(5),
+                Data::NUMBER(0),
+                Data::NUMBER(-5),
+                Data::NUMBER(-1),
+            ]
+        );
+        vm.data_stack.clear();
+
+        // case:  stack underflow leaves the stack empty
+        vm.data_stack = vec![Data::NUMBER(1), Data::NUMBER(2)];
+        vm.operations = VecDeque::from([Operation::ROT]);
+        assert!(execute(&mut vm).is_err());
+        assert_eq!(vm.data_stack, vec![Data::NUMBER(1), Data::NUMBER(2)]);
+        vm.data_stack.clear();
+
+        empty_stack_test_case!(vm, Operation::SWAP);
+        empty_stack_test_case!(vm, Operation::OVER);
+        empty_stack_test_case!(vm, Operation::S_TO_D);
+    }
+
+    #[test]
+    fn operation_test__pictured_numeric_output() {
+        let mut vm: VM = VM::default();
+        vm.define_core_words();
+
+        macro_rules! picture_test_case {
+            ($line:expr, $expected:expr) => {{
+                assert!(interpret(&mut vm, $line).is_ok());
+                assert_eq!(string_from_stack(&mut vm), $expected);
+                assert!(vm.data_stack.is_empty());
+            }};
+        }
+
+        picture_test_case!("0 0 <# #S #>\n", "0");
+        picture_test_case!("1234 0 <# #S #>\n", "1234");
+        picture_test_case!("1234 0 <# # # #>\n", "34");
+        picture_test_case!("5 0 <# # # # #>\n", "005");
+
+        // case:  negative numbers are converted by magnitude, with SIGN adding the "-"
+        picture_test_case!("42 NEGATE DUP ABS S>D <# #S ROT SIGN #>\n", "-42");
+        picture_test_case!("42 DUP ABS S>D <# #S ROT SIGN #>\n", "42");
+
+        // case:  double-cell numbers; 2^64 doesn't fit in a single cell
+        picture_test_case!("0 1 <# #S #>\n", "18446744073709551616");
+        picture_test_case!("1 NEGATE 1 NEGATE <# #S #>\n", "340282366920938463463374607431768211455");
+
+        // case:  a single negative cell is a large unsigned number
+        picture_test_case!("1 NEGATE 0 <# #S #>\n", "18446744073709551615");
+
+        // case:  HOLD and HOLDS insert text; e.g. fixed-point with two decimal places
+        picture_test_case!("12345 0 <# # # CHAR . HOLD #S S\" $\" HOLDS #>\n", "$123.45");
+
+        // case:  BASE is honored, by number literals too
+        picture_test_case!("255 HEX 0 <# #S #> DECIMAL\n", "FF");
+        picture_test_case!("HEX FF 0 <# #S #> DECIMAL\n", "FF");
+        picture_test_case!("2 BASE ! 101 0 <# #S #> DECIMAL\n", "101");
+        picture_test_case!("35 36 BASE ! 0 <# #S #> DECIMAL\n", "Z");
+        picture_test_case!("HEX 0 1 <# #S #> DECIMAL\n", "10000000000000000");
+
+        // case:  the largest binary picture fits in the hold buffer
+        let line: &str = "2 BASE ! 1 NEGATE 1 NEGATE <# #S 1 NEGATE SIGN #> DECIMAL\n";
+        picture_test_case!(line, format!("-{}", "1".repeat(128)));
+
+        // case:  BASE must be usable
+        assert!(interpret(&mut vm, "1 BASE ! 1 0 <# #S #>\n").is_err());
+        assert!(interpret(&mut vm, "DECIMAL\n").is_ok());
+        vm.data_stack.clear();
+
+        empty_stack_test_case!(vm, Operation::NUM);
+        empty_stack_test_case!(vm, Operation::NUM_S);
+        empty_stack_test_case!(vm, Operation::NUM_END);
+        empty_stack_test_case!(vm, Operation::HOLD);
+        empty_stack_test_case!(vm, Operation::HOLDS);
+        empty_stack_test_case!(vm, Operation::SIGN);
+    }
+
+    #[test]
+    fn operation_test__output_base() {
+        let mut vm: VM = VM::default();
+        vm.define_core_words();
+        let output: BufferSink = capture_output(&mut vm);
+
+        assert!(interpret(&mut vm, "HEX FF . FF NEGATE . A 4 .R 1 2 .S DECIMAL\n").is_ok());
+        assert_eq!(output.contents(), "FF -FF    A<2> 1 2 ");
+        vm.data_stack.clear();
+
+        // words that look like numbers are still words
+        assert!(interpret(&mut vm, ": BEEF 7 ; HEX BEEF BEE DECIMAL\n").is_ok());
+        assert_eq!(vm.data_stack, vec![Data::NUMBER(7), Data::NUMBER(0xBEE)]);
+        vm.data_stack.clear();
+
+        output.clear();
+        assert!(interpret(&mut vm, "5 2 BASE ! . BASE ? DECIMAL BASE ?\n").is_ok());
+        assert_eq!(output.contents(), "101 10 10 ");
+
+        // padding is limited
+        output.clear();
+        assert!(interpret(&mut vm, "2 100000000000 .R 100000000000 SPACES 3 1 NEGATE .R\n").is_ok());
+        let padding: String = " ".repeat(MAX_PADDING);
+        assert_eq!(output.contents(), format!("{}2{}3", padding, padding));
+    }
+
+    #[test]
+    fn operation_test__keyboard_input() {
+        let mut vm: VM = VM::default();
+        vm.define_core_words();
+        vm.input = Box::new(ScriptedSource::new(vec![
+            Script::Pause,
+            Script::Text(String::from("ab")),
+            Script::Pause,
+            Script::Text(String::from("\x1b[A\x1bx\x1b")),
+        ]));
+
+        // KEY? polls without consuming, KEY consumes
+        assert!(interpret(&mut vm, "KEY? KEY? KEY KEY KEY?\n").is_ok());
+        assert_eq!(vm.data_stack, vec![
+            Data::NUMBER(0), Data::NUMBER(1), Data::NUMBER(b'a' as i64), Data::NUMBER(b'b' as i64), Data::NUMBER(0),
+        ]);
+        vm.data_stack.clear();
+
+        // EKEY packs escape sequences, which EKEY>CHAR rejects
+        assert!(interpret(&mut vm, "EKEY EKEY>CHAR\n").is_ok());
+        let up: i64 = EKEY_SEQUENCE | (b'A' as i64) << 8 | b'[' as i64;
+        assert_eq!(vm.data_stack, vec![Data::NUMBER(up), Data::NUMBER(0)]);
+        vm.data_stack.clear();
+        assert!(interpret(&mut vm, "EKEY EKEY>CHAR\n").is_ok());
+        assert_eq!(vm.data_stack, vec![Data::NUMBER(EKEY_SEQUENCE | b'x' as i64), Data::NUMBER(0)]);
+        vm.data_stack.clear();
+
+        // case:  a lone escape is a character
+        assert!(interpret(&mut vm, "EKEY EKEY>CHAR\n").is_ok());
+        assert_eq!(vm.data_stack, vec![Data::NUMBER(0x1b), Data::NUMBER(1)]);
+        vm.data_stack.clear();
+
+        // case:  input is exhausted
+        assert!(interpret(&mut vm, "KEY\n").is_err());
+        assert!(interpret(&mut vm, "EKEY\n").is_err());
+        vm.data_stack.clear();
+
+        empty_stack_test_case!(vm, Operation::EKEY_TO_CHAR);
+    }
+
+    #[test]
+    fn operation_test__accept() {
+        let mut vm: VM = VM::default();
+        vm.define_core_words();
+        vm.input = Box::new(BufferSource::new("hello\r\ntoo long\n"));
+        let addr: i64 = allot_string(&mut vm, "________");
+
+        let line: String = format!("{} 8 ACCEPT\n", addr);
+        assert!(interpret(&mut vm, &line).is_ok());
+        assert_eq!(vm.data_stack, vec![Data::NUMBER(5)]);
+        assert_eq!(vm.memory.slice(addr, 8).unwrap(), b"hello___");
+        vm.data_stack.clear();
+
+        // case:  the line is truncated to fit
+        let line: String = format!("{} 3 ACCEPT\n", addr);
+        assert!(interpret(&mut vm, &line).is_ok());
+        assert_eq!(vm.data_stack, vec![Data::NUMBER(3)]);
+        assert_eq!(vm.memory.slice(addr, 8).unwrap(), b"toolo___");
+        vm.data_stack.clear();
+
+        // case:  input is exhausted
+        assert!(interpret(&mut vm, &line).is_ok());
+        assert_eq!(vm.data_stack, vec![Data::NUMBER(0)]);
+        vm.data_stack.clear();
+
+        // case:  the buffer must be in data space
+        vm.input = Box::new(BufferSource::new("hello\n"));
+        assert!(interpret(&mut vm, "1 NEGATE 5 ACCEPT\n").is_err());
+        vm.data_stack.clear();
+
+        empty_stack_test_case!(vm, Operation::ACCEPT);
+    }
+
+    #[test]
+    fn operation_test__bye() {
+        let mut vm: VM = VM::default();
+        vm.define_core_words();
+
+        // the rest of the line is abandoned
+        assert_eq!(interpret(&mut vm, "1 BYE 2\n"), Ok(Outcome::HALTED(None)));
+        assert_eq!(vm.data_stack, vec![Data::NUMBER(1)]);
+        vm.data_stack.clear();
+
+        // pending operations are discarded
+        vm.operations.extend([Operation::BYE, Operation::NOP_INC]);
+        assert_eq!(execute(&mut vm).unwrap(), Outcome::HALTED(None));
+        assert!(vm.operations.is_empty());
+
+        // the VM remains usable if the host carries on
+        assert_eq!(interpret(&mut vm, "3 (BYE)\n"), Ok(Outcome::HALTED(Some(3))));
+        assert!(vm.data_stack.is_empty());
+        assert_eq!(interpret(&mut vm, "4\n"), Ok(Outcome::COMPLETED));
+
+        // case:  exit codes must fit
+        assert!(interpret(&mut vm, "4294967296 (BYE)\n").is_err());
+        vm.data_stack.clear();
+
+        empty_stack_test_case!(vm, Operation::BYE_CODE);
+    }
+
+    #[test]
+    fn operation_test__colon_definitions() {
+        let mut vm: VM = VM::default();
+        vm.define_core_words();
+        let output: BufferSink = capture_output(&mut vm);
+
+        assert!(interpret(&mut vm, ": SQUARE DUP * ;\n").is_ok());
+        assert!(interpret(&mut vm, "3 SQUARE 4 SQUARE +\n").is_ok());
+        assert_eq!(vm.data_stack, vec![Data::NUMBER(25)]);
+        vm.data_stack.clear();
+
+        // definitions may span lines, and call each other
+        assert!(interpret(&mut vm, ": GREET\n").is_ok());
+        assert_eq!(vm.compiling, Some(vm.definitions.len() - 1));
+        assert!(interpret(&mut vm, ".\" hi \" [CHAR] ! EMIT\n").is_ok());
+        assert!(interpret(&mut vm, "S\" there\" TYPE C\" x\" COUNT TYPE 2 SQUARE . ;\n").is_ok());
+        assert_eq!(vm.compiling, None);
+        assert!(output.contents().is_empty());
+        assert!(interpret(&mut vm, "GREET GREET\n").is_ok());
+        assert_eq!(output.contents(), "hi !therex4 hi !therex4 ");
+        assert!(vm.data_stack.is_empty());
+        assert!(vm.return_stack.is_empty());
+
+        // case:  the name being defined isn't found until the definition is complete
+        assert!(interpret(&mut vm, ": UNDEFINED UNDEFINED ;\n").is_err());
+        assert_eq!(vm.compiling, None);
+        assert!(vm.find("UNDEFINED").is_none());
+
+        // case:  errors abandon the definition
+        let count: usize = vm.definitions.len();
+        assert!(interpret(&mut vm, ": BROKEN 1 NOT-A-WORD ;\n").is_err());
+        assert_eq!(vm.definitions.len(), count);
+        assert!(interpret(&mut vm, "1\n").is_ok());
+        assert_eq!(vm.data_stack, vec![Data::NUMBER(1)]);
+        vm.data_stack.clear();
+
+        // case:  names are required, and definitions can't be nested
+        assert!(interpret(&mut vm, ":\n").is_err());
+        assert!(interpret(&mut vm, ": OUTER : INNER ;\n").is_err());
+
+        // case:  compile-only words can't be interpreted
+        assert!(interpret(&mut vm, ";\n").is_err());
+        assert!(interpret(&mut vm, "1 IF\n").is_err());
+        assert!(interpret(&mut vm, "RECURSE\n").is_err());
+        assert!(interpret(&mut vm, "EXIT\n").is_err());
+        vm.data_stack.clear();
+    }
+
+    #[test]
+    fn operation_test__if_else_then() {
+        let mut vm: VM = VM::default();
+        vm.define_core_words();
+
+        assert!(interpret(&mut vm, ": SIGNUM DUP 0< IF DROP 1 NEGATE ELSE 0> IF 1 ELSE 0 THEN THEN ;\n").is_ok());
+        assert!(interpret(&mut vm, ": CLAMP DUP 10 > IF DROP 10 THEN ;\n").is_ok());
+        assert!(interpret(&mut vm, "5 NEGATE SIGNUM 0 SIGNUM 5 SIGNUM 3 CLAMP 30 CLAMP\n").is_ok());
+        assert_eq!(vm.data_stack, vec![
+            Data::NUMBER(-1), Data::NUMBER(0), Data::NUMBER(1), Data::NUMBER(3), Data::NUMBER(10),
+        ]);
+        vm.data_stack.clear();
+
+        // case:  control structures must be balanced
+        assert!(interpret(&mut vm, ": BAD 1 IF ;\n").is_err());
+        assert!(interpret(&mut vm, ": BAD THEN ;\n").is_err());
+        assert!(interpret(&mut vm, ": BAD 1 IF ELSE ;\n").is_err());
+        assert!(interpret(&mut vm, ": BAD 0 THEN ;\n").is_err());
+        assert!(vm.find("BAD").is_none());
+        vm.data_stack.clear();
+
+        empty_stack_test_case!(vm, Operation::BRANCH_IF_ZERO(0));
+    }
+
+    #[test]
+    fn operation_test__recurse_and_exit() {
+        let mut vm: VM = VM::default();
+        vm.define_core_words();
+
+        assert!(interpret(&mut vm, ": FACTORIAL DUP 1 > IF DUP 1 - RECURSE * THEN ;\n").is_ok());
+        assert!(interpret(&mut vm, "1 FACTORIAL 5 FACTORIAL 20 FACTORIAL\n").is_ok());
+        assert_eq!(vm.data_stack, vec![Data::NUMBER(1), Data::NUMBER(120), Data::NUMBER(2432902008176640000)]);
+        vm.data_stack.clear();
+
+        // EXIT returns early, from the innermost definition only
+        assert!(interpret(&mut vm, ": FIRST-POSITIVE DUP 0> IF EXIT THEN DROP 1 ;\n").is_ok());
+        assert!(interpret(&mut vm, ": BOTH FIRST-POSITIVE 2 ;\n").is_ok());
+        assert!(interpret(&mut vm, "7 BOTH 0 BOTH\n").is_ok());
+        assert_eq!(vm.data_stack, vec![Data::NUMBER(7), Data::NUMBER(2), Data::NUMBER(1), Data::NUMBER(2)]);
+        vm.data_stack.clear();
+
+        // calls followed by a return become tail calls, including through ELSE
+        assert!(interpret(&mut vm, ": COUNTDOWN DUP 0> IF 1 - RECURSE THEN ;\n").is_ok());
+        assert!(interpret(&mut vm, ": EVEN? DUP 2 < IF 0= ELSE 2 - RECURSE THEN ;\n").is_ok());
+        let countdown: usize = vm.find("COUNTDOWN").unwrap();
+        let even: usize = vm.find("EVEN?").unwrap();
+        assert!(vm.definitions[countdown].body.contains(&Operation::TAIL_CALL(countdown)));
+        assert!(!vm.definitions[countdown].body.contains(&Operation::CALL(countdown)));
+        assert!(vm.definitions[even].body.contains(&Operation::TAIL_CALL(even)));
+
+        // ... but calls with work left to do don't
+        let factorial: usize = vm.find("FACTORIAL").unwrap();
+        assert!(vm.definitions[factorial].body.contains(&Operation::CALL(factorial)));
+        let first: usize = vm.find("FIRST-POSITIVE").unwrap();
+        let both: usize = vm.find("BOTH").unwrap();
+        assert!(vm.definitions[both].body.contains(&Operation::CALL(first)));
+
+        // so deep recursion runs in constant return stack space
+        assert!(interpret(&mut vm, "200000 COUNTDOWN 200001 EVEN?\n").is_ok());
+        assert_eq!(vm.data_stack, vec![Data::NUMBER(0), Data::NUMBER(0)]);
+        assert!(vm.return_stack.is_empty());
+    }
+
+    #[test]
+    fn operation_test__do_loops() {
+        let mut vm: VM = VM::default();
+        vm.define_core_words();
+        let output: BufferSink = capture_output(&mut vm);
+
+        macro_rules! loop_test_case {
+            ($definition:expr, $line:expr, $expected:expr) => {{
+                output.clear();
+                assert!(interpret(&mut vm, $definition).is_ok());
+                assert!(interpret(&mut vm, $line).is_ok());
+                assert_eq!(output.contents(), $expected);
+                assert!(vm.data_stack.is_empty());
+                assert!(vm.return_stack.is_empty());
+            }};
+        }
+
+        loop_test_case!(": COUNT-UP 0 DO I . LOOP ;\n", "3 COUNT-UP 1 COUNT-UP\n", "0 1 2 0 ");
+        loop_test_case!(": GRID 2 0 DO 3 0 DO J . I . LOOP LOOP ;\n", "GRID\n", "0 0 0 1 0 2 1 0 1 1 1 2 ");
+        loop_test_case!(": EVENS 0 DO I . 2 +LOOP ;\n", "7 EVENS 6 EVENS\n", "0 2 4 6 0 2 4 ");
+        loop_test_case!(": DOWN 0 SWAP DO I . 1 NEGATE +LOOP ;\n", "3 DOWN\n", "3 2 1 0 ");
+        loop_test_case!(": FIRST-OVER 100 0 DO I DUP * OVER > IF I . LEAVE THEN LOOP DROP ;\n", "50 FIRST-OVER\n", "8 ");
+        loop_test_case!(": MAYBE ?DO I . LOOP ;\n", "2 0 MAYBE 0 0 MAYBE\n", "0 1 ");
+        loop_test_case!(": FOUND 10 0 DO I 4 = IF I UNLOOP EXIT THEN LOOP 0 ;\n", "FOUND . \n", "4 ");
+
+        // the index may be negative, and +LOOP may step past the limit
+        loop_test_case!(": NEGATIVES 0 3 NEGATE DO I . LOOP ;\n", "NEGATIVES\n", "-3 -2 -1 ");
+        loop_test_case!(": FOURS 10 0 DO I . 4 +LOOP ;\n", "FOURS\n", "0 4 8 ");
+
+        // case:  loop words are compile-only, or need a loop
+        assert!(interpret(&mut vm, "3 0 DO\n").is_err());
+        assert!(interpret(&mut vm, ": NO-I I ; NO-I\n").is_err());
+        assert!(interpret(&mut vm, ": NO-J 1 0 DO J LOOP ; NO-J\n").is_err());
+        assert!(interpret(&mut vm, ": NO-LEAVE LEAVE ; NO-LEAVE\n").is_err());
+        assert!(interpret(&mut vm, ": NO-UNLOOP UNLOOP ; NO-UNLOOP\n").is_err());
+        vm.data_stack.clear();
+
+        // case:  loops must be balanced
+        assert!(interpret(&mut vm, ": BAD 3 0 DO ;\n").is_err());
+        assert!(interpret(&mut vm, ": BAD LOOP ;\n").is_err());
+        assert!(interpret(&mut vm, ": BAD 3 0 DO 1 IF LOOP THEN ;\n").is_err());
+        assert!(interpret(&mut vm, ": BAD 1 IF 3 0 DO THEN LOOP ;\n").is_err());
+        assert!(vm.find("BAD").is_none());
+        vm.data_stack.clear();
+    }
+
+    #[test]
+    fn operation_test__case() {
+        let mut vm: VM = VM::default();
+        vm.define_core_words();
+        let output: BufferSink = capture_output(&mut vm);
+
+        assert!(interpret(&mut vm, ": NAME CASE 1 OF .\" one \" ENDOF 2 OF .\" two \" ENDOF .\" other \" ENDCASE ;\n").is_ok());
+        assert!(interpret(&mut vm, "1 NAME 2 NAME 3 NAME\n").is_ok());
+        assert_eq!(output.contents(), "one two other ");
+        assert!(vm.data_stack.is_empty());
+
+        // the selector is dropped when nothing matches
+        assert!(interpret(&mut vm, ": EMPTY CASE ENDCASE ; : DEFAULT CASE 1 OF 10 ENDOF DUP ENDCASE ;\n").is_ok());
+        assert!(interpret(&mut vm, "5 EMPTY 1 DEFAULT 7 DEFAULT\n").is_ok());
+        assert_eq!(vm.data_stack, vec![Data::NUMBER(10), Data::NUMBER(7)]);
+        vm.data_stack.clear();
+
+        // inside a loop, calling a word with a CASE
+        output.clear();
+        assert!(interpret(&mut vm, ": NAMES 4 0 DO I NAME LOOP ;\n").is_ok());
+        assert!(interpret(&mut vm, "NAMES\n").is_ok());
+        assert_eq!(output.contents(), "other one two other ");
+
+        // inside a loop, in the same definition, leaving from within a clause
+        let line: &str = ": SCAN 10 0 DO I CASE 0 OF 10 ENDOF 1 OF 20 ENDOF 3 OF LEAVE ENDOF 30 SWAP ENDCASE LOOP ;\n";
+        assert!(interpret(&mut vm, line).is_ok());
+        assert!(interpret(&mut vm, "SCAN\n").is_ok());
+        assert_eq!(vm.data_stack, vec![Data::NUMBER(10), Data::NUMBER(20), Data::NUMBER(30)]);
+        vm.data_stack.clear();
+
+        // nested inside nested loops, with a CASE nested in a clause
+        output.clear();
+        let line: &str = ": TABLE 3 0 DO 3 0 DO J CASE I OF .\" = \" ENDOF \
+            0 OF I CASE 1 OF .\" a \" ENDOF .\" b \" ENDCASE ENDOF .\" - \" ENDCASE LOOP LOOP ;\n";
+        assert!(interpret(&mut vm, line).is_ok());
+        assert!(interpret(&mut vm, "TABLE\n").is_ok());
+        assert_eq!(output.contents(), "= a b - = - - - = ");
+        assert!(vm.data_stack.is_empty());
+
+        // case:  CASE words are compile-only, and must be balanced
+        assert!(interpret(&mut vm, "CASE\n").is_err());
+        assert!(interpret(&mut vm, ": BAD CASE 1 OF ENDCASE ;\n").is_err());
+        assert!(interpret(&mut vm, ": BAD CASE 1 OF ;\n").is_err());
+        assert!(interpret(&mut vm, ": BAD ENDOF ;\n").is_err());
+        assert!(interpret(&mut vm, ": BAD 1 IF ENDCASE ;\n").is_err());
+        assert!(vm.find("BAD").is_none());
+        vm.data_stack.clear();
+    }
+
+    #[test]
+    fn operation_test__execution_tokens() {
+        let mut vm: VM = VM::default();
+        vm.define_core_words();
+
+        // built-in words and colon definitions alike
+        assert!(interpret(&mut vm, ": SQUARE DUP * ; 3 ' SQUARE EXECUTE 4 ' DUP EXECUTE *\n").is_ok());
+        assert_eq!(vm.data_stack, vec![Data::NUMBER(9), Data::NUMBER(16)]);
+        vm.data_stack.clear();
+
+        assert!(interpret(&mut vm, ": APPLY-SQUARE ['] SQUARE EXECUTE ; 5 APPLY-SQUARE\n").is_ok());
+        assert_eq!(vm.data_stack, vec![Data::NUMBER(25)]);
+        vm.data_stack.clear();
+
+        // case:  the word must exist, and the token must be valid
+        assert!(interpret(&mut vm, "' NOT-A-WORD\n").is_err());
+        assert!(interpret(&mut vm, "'\n").is_err());
+        assert!(interpret(&mut vm, ": BAD ['] NOT-A-WORD ;\n").is_err());
+        assert!(interpret(&mut vm, "['] SQUARE\n").is_err());
+        assert!(interpret(&mut vm, "1 NEGATE EXECUTE\n").is_err());
+        assert!(interpret(&mut vm, "1000000 EXECUTE\n").is_err());
+        vm.data_stack.clear();
+
+        empty_stack_test_case!(vm, Operation::EXECUTE);
+    }
+
+    #[test]
+    fn operation_test__deferred_words() {
+        let mut vm: VM = VM::default();
+        vm.define_core_words();
+        let output: BufferSink = capture_output(&mut vm);
+
+        // mutual recursion
+        assert!(interpret(&mut vm, "DEFER ODD?\n").is_ok());
+        assert!(interpret(&mut vm, ": EVEN? DUP 0= IF DROP 1 ELSE 1 - ODD? THEN ;\n").is_ok());
+        assert!(interpret(&mut vm, ": (ODD?) DUP 0= IF DROP 0 ELSE 1 - EVEN? THEN ;\n").is_ok());
+        assert!(interpret(&mut vm, "' (ODD?) IS ODD?\n").is_ok());
+        assert!(interpret(&mut vm, "10 EVEN? 7 EVEN? 100001 ODD?\n").is_ok());
+        assert_eq!(vm.data_stack, vec![Data::NUMBER(1), Data::NUMBER(0), Data::NUMBER(1)]);
+        assert!(vm.return_stack.is_empty());
+        vm.data_stack.clear();
+
+        // swapping implementations, e.g. to mock hardware
+        assert!(interpret(&mut vm, "DEFER SENSOR : REPORT SENSOR . ;\n").is_ok());
+        assert!(interpret(&mut vm, ": FAKE 42 ; : MOCK ['] FAKE IS SENSOR ; MOCK REPORT\n").is_ok());
+        assert!(interpret(&mut vm, "' DUP ' SENSOR DEFER! 7 REPORT .\n").is_ok());
+        assert_eq!(output.contents(), "42 7 7 ");
+
+        // the current action is available
+        assert!(interpret(&mut vm, "ACTION-OF SENSOR ' DUP = ' SENSOR DEFER@ ' DUP =\n").is_ok());
+        assert!(interpret(&mut vm, ": ACTION ACTION-OF SENSOR ; ACTION ' DUP =\n").is_ok());
+        assert_eq!(vm.data_stack, vec![Data::NUMBER(1), Data::NUMBER(1), Data::NUMBER(1)]);
+        vm.data_stack.clear();
+
+        // case:  uninitialized deferred words say so
+        assert!(interpret(&mut vm, "DEFER UNSET\n").is_ok());
+        assert_eq!(interpret(&mut vm, "UNSET\n"), Err(String::from("uninitialized deferred word: UNSET")));
+        assert_eq!(interpret(&mut vm, ": CALLER 1 UNSET 2 ; CALLER\n"), Err(String::from("uninitialized deferred word: UNSET")));
+        assert!(vm.return_stack.is_empty());
+        assert!(interpret(&mut vm, "ACTION-OF UNSET\n").is_err());
+        assert!(interpret(&mut vm, "' UNSET DEFER@\n").is_err());
+        vm.data_stack.clear();
+
+        // case:  only deferred words have actions
+        assert!(interpret(&mut vm, "' DUP IS REPORT\n").is_err());
+        assert!(interpret(&mut vm, "' DUP ' REPORT DEFER!\n").is_err());
+        assert!(interpret(&mut vm, "' REPORT DEFER@\n").is_err());
+        assert!(interpret(&mut vm, ": BAD ['] DUP IS REPORT ;\n").is_err());
+        assert!(interpret(&mut vm, "1000000 IS SENSOR\n").is_err());
+        assert!(interpret(&mut vm, "DEFER\n").is_err());
+        vm.data_stack.clear();
+
+        empty_stack_test_case!(vm, Operation::DEFER_FETCH);
+        empty_stack_test_case!(vm, Operation::DEFER_STORE);
+    }
+
+    #[test]
+    fn operation_test__locals() {
+        let mut vm: VM = VM::default();
+        vm.define_core_words();
+
+        macro_rules! locals_test_case {
+            ($definition:expr, $line:expr, $expected:expr) => {{
+                assert!(interpret(&mut vm, $definition).is_ok());
+                assert!(interpret(&mut vm, $line).is_ok());
+                assert_eq!(vm.data_stack, $expected);
+                assert!(vm.return_stack.is_empty());
+                vm.data_stack.clear();
+            }};
+        }
+
+        // the first argument takes the deepest item, and the rest of the stack is untouched
+        locals_test_case!(": FLIP {: a b :} b a ;\n", "1 2 3 FLIP\n", vec![Data::NUMBER(1), Data::NUMBER(3), Data::NUMBER(2)]);
+
+        // uninitialized locals are zero, and outputs are ignored
+        locals_test_case!(
+            ": SUM3 {: a b c | total -- n :} total a + b + c + TO total total total ;\n",
+            "1 2 3 SUM3\n",
+            vec![Data::NUMBER(6), Data::NUMBER(6)]
+        );
+        locals_test_case!(": SCRATCH {: | x y :} x y 5 TO y y ;\n", "SCRATCH\n", vec![Data::NUMBER(0), Data::NUMBER(0), Data::NUMBER(5)]);
+        locals_test_case!(": NONE {: -- x :} 7 ;\n", "NONE\n", vec![Data::NUMBER(7)]);
+
+        // LOCALS| takes the top item first
+        locals_test_case!(": OLD LOCALS| a b | a b ;\n", "1 2 OLD\n", vec![Data::NUMBER(2), Data::NUMBER(1)]);
+        locals_test_case!(": M LOCALS| a b | a b - ;\n", "10 3 M\n", vec![Data::NUMBER(-7)]);
+
+        // TO stores into arguments and uninitialized locals alike
+        locals_test_case!(": BUMP {: n :} n 1 + TO n n n ;\n", "4 BUMP\n", vec![Data::NUMBER(5), Data::NUMBER(5)]);
+        locals_test_case!(": SET LOCALS| a | 8 TO a a ;\n", "1 SET\n", vec![Data::NUMBER(8)]);
+        locals_test_case!(": FRESH {: | x :} x 9 TO x ;\n", "FRESH FRESH\n", vec![Data::NUMBER(0), Data::NUMBER(0)]);
+
+        // each call has its own locals, including recursive calls
+        locals_test_case!(
+            ": FACT {: n :} n 1 > IF n 1 - RECURSE n * ELSE 1 THEN ;\n",
+            "5 FACT\n",
+            vec![Data::NUMBER(120)]
+        );
+        locals_test_case!(": OUTER {: x :} 10 20 FLIP x ;\n", "1 OUTER\n", vec![Data::NUMBER(20), Data::NUMBER(10), Data::NUMBER(1)]);
+
+        // locals work in loops, and shadow words of the same name
+        locals_test_case!(
+            ": TOTAL {: n | sum :} n 0 DO sum I + TO sum LOOP sum ;\n",
+            "5 TOTAL\n",
+            vec![Data::NUMBER(10)]
+        );
+        locals_test_case!(": SHADOW {: DUP :} DUP DUP * ;\n", "3 SHADOW\n", vec![Data::NUMBER(9)]);
+        locals_test_case!(
+            ": TWICE 2 * ; : USE {: TWICE :} TWICE TWICE ;\n",
+            "3 USE 4 TWICE\n",
+            vec![Data::NUMBER(3), Data::NUMBER(3), Data::NUMBER(8)]
+        );
+
+        // locals are scoped to their definition
+        assert!(interpret(&mut vm, ": LEAKED a ;\n").is_err());
+        assert!(interpret(&mut vm, "3 DUP\n").is_ok());
+        assert_eq!(vm.data_stack, vec![Data::NUMBER(3), Data::NUMBER(3)]);
+        vm.data_stack.clear();
+
+        // case:  locals need a definition, and declarations must be terminated
+        assert!(interpret(&mut vm, "1 {: a :}\n").is_err());
+        assert!(interpret(&mut vm, "1 LOCALS| a |\n").is_err());
+        assert!(interpret(&mut vm, ": BAD {: a b\n").is_err());
+        assert!(interpret(&mut vm, ": BAD {: a | b | c :} ;\n").is_err());
+        assert!(interpret(&mut vm, ": BAD LOCALS| a\n").is_err());
+        assert!(interpret(&mut vm, "5 TO DUP\n").is_err());
+        assert!(interpret(&mut vm, ": BAD {: a :} 5 TO b ;\n").is_err());
+        assert!(vm.find("BAD").is_none());
+        vm.data_stack.clear();
+
+        // case:  arguments must be on the stack
+        assert!(interpret(&mut vm, ": NEEDS-TWO {: a b :} a b + ;\n").is_ok());
+        assert!(interpret(&mut vm, "1 NEEDS-TWO\n").is_err());
+        assert_eq!(vm.data_stack, vec![Data::NUMBER(1)]);
+        vm.data_stack.clear();
+
+        empty_stack_test_case!(vm, Operation::LOCALS(1, 0));
+    }
+
+    #[test]
+    fn operation_test__locals_calls() {
+        let mut vm: VM = VM::default();
+        vm.define_core_words();
+
+        // recursive calls don't disturb the caller's locals
+        assert!(interpret(&mut vm, ": FIB {: n | a b :} n 2 < IF n EXIT THEN n 1 - RECURSE TO a n 2 - RECURSE TO b a b + ;\n").is_ok());
+        assert!(interpret(&mut vm, "10 FIB\n").is_ok());
+        assert_eq!(vm.data_stack, vec![Data::NUMBER(55)]);
+        assert!(vm.return_stack.is_empty());
+        vm.data_stack.clear();
+
+        // tail calls replace the caller's locals with the callee's
+        assert!(interpret(&mut vm, ": SUM-TO {: n total :} n 0= IF total ELSE n 1 - total n + RECURSE THEN ;\n").is_ok());
+        assert!(interpret(&mut vm, ": HALF {: x :} x 2 / ; : ONE-MORE {: y :} y 1 + HALF ;\n").is_ok());
+        let sum: usize = vm.find("SUM-TO").unwrap();
+        let half: usize = vm.find("HALF").unwrap();
+        let more: usize = vm.find("ONE-MORE").unwrap();
+        assert!(vm.definitions[sum].body.contains(&Operation::TAIL_CALL(sum)));
+        assert!(vm.definitions[more].body.contains(&Operation::TAIL_CALL(half)));
+        assert!(interpret(&mut vm, "200000 0 SUM-TO 9 ONE-MORE\n").is_ok());
+        assert_eq!(vm.data_stack, vec![Data::NUMBER(20000100000), Data::NUMBER(5)]);
+        assert!(vm.return_stack.is_empty());
+    }
+
+    #[test]
+    fn operation_test__quotations() {
+        let mut vm: VM = VM::default();
+        vm.define_core_words();
+        let output: BufferSink = capture_output(&mut vm);
+
+        // executed inside a loop
+        assert!(interpret(&mut vm, ": SQUARES 4 0 DO I [: DUP * ;] EXECUTE . LOOP ;\n").is_ok());
+        assert!(interpret(&mut vm, "SQUARES\n").is_ok());
+        assert_eq!(output.contents(), "0 1 4 9 ");
+        assert!(vm.data_stack.is_empty());
+
+        // passed to a higher-order word, which executes it inside a loop
+        output.clear();
+        assert!(interpret(&mut vm, ": MAP {: n xt :} n 0 DO I xt EXECUTE . LOOP ;\n").is_ok());
+        assert!(interpret(&mut vm, ": TENS 4 [: 10 * ;] MAP ; TENS 3 [: 1 + ;] MAP\n").is_ok());
+        assert_eq!(output.contents(), "0 10 20 30 1 2 3 ");
+        assert!(vm.data_stack.is_empty());
+
+        // nested, with their own control flow and locals
+        assert!(interpret(&mut vm, ": NESTED [: [: 5 ;] EXECUTE 1 + ;] EXECUTE ;\n").is_ok());
+        assert!(interpret(&mut vm, ": CHOOSE IF [: 1 ;] ELSE [: 2 ;] THEN EXECUTE ;\n").is_ok());
+        assert!(interpret(&mut vm, ": SCOPED {: a :} a [: {: a :} a a * ;] EXECUTE a + ;\n").is_ok());
+        assert!(interpret(&mut vm, "NESTED 1 CHOOSE 0 CHOOSE 3 SCOPED\n").is_ok());
+        assert_eq!(vm.data_stack, vec![Data::NUMBER(6), Data::NUMBER(1), Data::NUMBER(2), Data::NUMBER(12)]);
+        vm.data_stack.clear();
+
+        // quotations are nameless, and may be interpreted too
+        assert!(interpret(&mut vm, "[: 2 3 * ;] DUP EXECUTE\n").is_ok());
+        let Data::NUMBER(xt) = vm.data_stack[0];
+        assert_eq!(vm.definitions[xt as usize].name, "");
+        assert_eq!(vm.data_stack[1], Data::NUMBER(6));
+        vm.data_stack.clear();
+
+        // case:  the enclosing definition's locals aren't visible
+        let count: usize = vm.definitions.len();
+        assert!(interpret(&mut vm, ": LEAKY {: a :} [: a ;] ;\n").is_err());
+        assert_eq!(vm.definitions.len(), count);
+        assert_eq!(vm.compiling, None);
+
+        // case:  quotations must be balanced
+        assert!(interpret(&mut vm, ";]\n").is_err());
+        assert!(interpret(&mut vm, ": BAD [: 1 ;\n").is_err());
+        assert!(interpret(&mut vm, ": BAD [: 1 IF ;] ;\n").is_err());
+        assert!(interpret(&mut vm, ": BAD 1 IF [: THEN ;] ;\n").is_err());
+        assert!(interpret(&mut vm, "[: [: 1 ;\n").is_err());
+        assert!(vm.find("BAD").is_none());
+        assert_eq!(vm.definitions.len(), count);
+        vm.data_stack.clear();
+    }
+
+    #[test]
+    fn operation_test__quotation_scopes() {
+        let mut vm: VM = VM::default();
+        vm.define_core_words();
+
+        // nested several deep, each executed by the one enclosing it
+        assert!(interpret(&mut vm, ": DEEP [: 1 [: 2 [: 3 ;] EXECUTE + ;] EXECUTE + ;] EXECUTE ;\n").is_ok());
+        assert!(interpret(&mut vm, ": MAKER [: [: 7 ;] ;] ;\n").is_ok());
+        assert!(interpret(&mut vm, "DEEP MAKER EXECUTE EXECUTE\n").is_ok());
+        assert_eq!(vm.data_stack, vec![Data::NUMBER(6), Data::NUMBER(7)]);
+        vm.data_stack.clear();
+
+        // each quotation has its own locals, which are fresh each time it's executed
+        assert!(interpret(&mut vm, ": PAIRS {: a b :} a b [: {: x y | z :} z x y * + ;] EXECUTE a b + ;\n").is_ok());
+        assert!(interpret(&mut vm, ": LAYERS {: a :} a [: {: b :} b [: {: c :} c c * ;] EXECUTE b + ;] EXECUTE a + ;\n").is_ok());
+        assert!(interpret(&mut vm, ": TIMES {: n xt :} n 0 DO I xt EXECUTE LOOP ; 3 [: {: i :} i i + ;] TIMES\n").is_ok());
+        assert!(interpret(&mut vm, "3 4 PAIRS 3 LAYERS 5 [: {: x :} x x * ;] EXECUTE\n").is_ok());
+        assert_eq!(vm.data_stack, vec![
+            Data::NUMBER(0), Data::NUMBER(2), Data::NUMBER(4),
+            Data::NUMBER(12), Data::NUMBER(7), Data::NUMBER(15), Data::NUMBER(25),
+        ]);
+        assert!(vm.return_stack.is_empty());
+        vm.data_stack.clear();
+
+        // case:  neither the enclosing definition's locals, nor an enclosing
+        // quotation's, are visible, and a quotation's locals end with it
+        assert!(interpret(&mut vm, ": OUTSIDE {: a :} [: {: b :} a b + ;] ;\n").is_err());
+        assert!(interpret(&mut vm, ": INSIDE [: {: a :} [: a ;] ;] ;\n").is_err());
+        assert!(interpret(&mut vm, ": AFTER [: {: q :} q ;] DROP q ;\n").is_err());
+        assert!(vm.find("OUTSIDE").is_none());
+        assert!(vm.find("INSIDE").is_none());
+        assert!(vm.find("AFTER").is_none());
+        assert_eq!(vm.compiling, None);
+        vm.data_stack.clear();
+
+        // ... so a local of the same name is another local
+        assert!(interpret(&mut vm, ": SAME {: a :} [: {: a :} a 10 * ;] a SWAP EXECUTE a + ;\n").is_ok());
+        assert!(interpret(&mut vm, "2 SAME\n").is_ok());
+        assert_eq!(vm.data_stack, vec![Data::NUMBER(22)]);
+    }
+
+    #[test]
+    fn operation_test__search_order() {
+        let mut vm: VM = VM::default();
+        vm.define_core_words();
+        let output: BufferSink = capture_output(&mut vm);
+        assert!(interpret(&mut vm, "ORDER\n").is_ok());
+        assert_eq!(output.contents(), "FORTH  FORTH ");
+
+        // words in a vocabulary shadow the words searched after it
+        output.clear();
+        assert!(interpret(&mut vm, ": TWICE DUP + ;\n").is_ok());
+        assert!(interpret(&mut vm, "VOCABULARY EDITOR ALSO EDITOR DEFINITIONS : DUP 42 ; ORDER\n").is_ok());
+        assert_eq!(output.contents(), "EDITOR FORTH  EDITOR ");
+        assert!(interpret(&mut vm, "1 DUP 2 TWICE\n").is_ok());
+        assert_eq!(vm.data_stack, vec![Data::NUMBER(1), Data::NUMBER(42), Data::NUMBER(4)]);
+        vm.data_stack.clear();
+
+        // the shadowed words are still there
+        assert!(interpret(&mut vm, "PREVIOUS 1 DUP\n").is_ok());
+        assert_eq!(vm.data_stack, vec![Data::NUMBER(1), Data::NUMBER(1)]);
+        vm.data_stack.clear();
+        assert!(interpret(&mut vm, "ALSO EDITOR FORTH 1 DUP ONLY FORTH DEFINITIONS\n").is_ok());
+        assert_eq!(vm.data_stack, vec![Data::NUMBER(1), Data::NUMBER(1)]);
+        vm.data_stack.clear();
+
+        // wordlists, and the search order as a whole
+        assert!(interpret(&mut vm, "WORDLIST DUP SET-CURRENT : HIDDEN 7 ; GET-CURRENT FORTH-WORDLIST SET-CURRENT\n").is_ok());
+        let Data::NUMBER(wid) = vm.data_stack[0];
+        assert_eq!(vm.data_stack[1], Data::NUMBER(wid));
+        assert!(vm.find("HIDDEN").is_none());
+        vm.data_stack.clear();
+        vm.data_stack.push(Data::NUMBER(wid));
+        assert!(interpret(&mut vm, "FORTH-WORDLIST SWAP 2 SET-ORDER HIDDEN GET-ORDER 1 NEGATE SET-ORDER\n").is_ok());
+        assert_eq!(vm.data_stack, vec![Data::NUMBER(7), Data::NUMBER(0), Data::NUMBER(wid), Data::NUMBER(2)]);
+        assert_eq!(vm.dictionary.search_order, vec![FORTH_WORDLIST]);
+        vm.data_stack.clear();
+
+        // words may be found in a particular wordlist
+        let name: i64 = allot_string(&mut vm, "HIDDEN");
+        let control: i64 = allot_string(&mut vm, "IF");
+        for (addr, length, wid, found) in [(name, 6, wid, -1), (control, 2, 0, 1), (name, 6, 0, 0)] {
+            vm.data_stack.extend([Data::NUMBER(addr), Data::NUMBER(length), Data::NUMBER(wid)]);
+            vm.operations.push_back(Operation::SEARCH_WORDLIST);
+            assert!(execute(&mut vm).is_ok());
+            assert_eq!(vm.data_stack.pop(), Some(Data::NUMBER(found)));
+            if found != 0 {
+                let Data::NUMBER(xt) = vm.data_stack.pop().unwrap();
+                assert_eq!(vm.definitions[xt as usize].name, String::from_utf8_lossy(vm.memory.slice(addr, length).unwrap()));
+            }
+        }
+        assert!(vm.data_stack.is_empty());
+
+        // nothing is found in an empty search order
+        assert!(interpret(&mut vm, "0 SET-ORDER 1 DUP\n").is_err());
+        assert_eq!(vm.data_stack, vec![Data::NUMBER(1)]);
+        vm.data_stack.clear();
+        vm.dictionary.search_order = vec![FORTH_WORDLIST];
+
+        // case:  the search order can't underflow or overflow
+        assert!(interpret(&mut vm, "PREVIOUS ALSO\n").is_err());
+        vm.dictionary.search_order = vec![FORTH_WORDLIST];
+        assert!(interpret(&mut vm, ": DEEP 17 0 DO FORTH-WORDLIST LOOP 17 SET-ORDER ; DEEP\n").is_err());
+        assert_eq!(vm.dictionary.search_order, vec![FORTH_WORDLIST]);
+        assert_eq!(vm.data_stack.len(), 18);
+        vm.data_stack.clear();
+
+        // case:  wordlists must exist
+        assert!(interpret(&mut vm, "99 SET-CURRENT\n").is_err());
+        assert!(interpret(&mut vm, "99 1 SET-ORDER\n").is_err());
+        assert_eq!(vm.data_stack, vec![Data::NUMBER(99), Data::NUMBER(1)]);
+        vm.data_stack.clear();
+        assert!(interpret(&mut vm, "FORTH-WORDLIST 2 SET-ORDER\n").is_err());
+        assert_eq!(vm.data_stack, vec![Data::NUMBER(FORTH_WORDLIST as i64), Data::NUMBER(2)]);
+        vm.data_stack.clear();
+        assert!(interpret(&mut vm, "2 NEGATE SET-ORDER\n").is_err());
+        assert!(interpret(&mut vm, "VOCABULARY\n").is_err());
+        assert_eq!(vm.dictionary.current, FORTH_WORDLIST);
+        assert_eq!(vm.dictionary.search_order, vec![FORTH_WORDLIST]);
+        vm.data_stack.clear();
+
+        empty_stack_test_case!(vm, Operation::SET_CURRENT);
+        empty_stack_test_case!(vm, Operation::SET_ORDER);
+        empty_stack_test_case!(vm, Operation::SEARCH_WORDLIST);
+    }
 
-        }
-        vm._ops_applied += 1;
+    #[test]
+    fn operation_test__redefinition() {
+        let mut vm: VM = VM::default();
+        vm.define_core_words();
+        let output: BufferSink = capture_output(&mut vm);
+
+        // words compiled against earlier definitions keep their meaning
+        assert!(interpret(&mut vm, ": DOUBLE 2 * ; : QUAD DOUBLE DOUBLE ; : PLUS3 3 + ;\n").is_ok());
+        assert_eq!(output.contents(), "");
+        assert!(interpret(&mut vm, ": DOUBLE 3 * ; : + - ;\n").is_ok());
+        assert_eq!(output.contents(), "redefined DOUBLE redefined + ");
+        assert!(interpret(&mut vm, "2 QUAD 2 DOUBLE 1 PLUS3 5 3 +\n").is_ok());
+        assert_eq!(vm.data_stack, vec![Data::NUMBER(8), Data::NUMBER(6), Data::NUMBER(4), Data::NUMBER(2)]);
+        vm.data_stack.clear();
+
+        // forgetting a redefinition reveals what it shadowed
+        assert!(interpret(&mut vm, "FORGET DOUBLE 2 DOUBLE 5 3 +\n").is_ok());
+        assert_eq!(vm.data_stack, vec![Data::NUMBER(4), Data::NUMBER(8)]);
+        assert!(vm.find("PLUS3").is_some());
+        vm.data_stack.clear();
+
+        // data space allotted since is released
+        let here: usize = vm.memory.here();
+        assert!(interpret(&mut vm, ": GREETING S\" hello\" ; 16 ALLOT FORGET GREETING\n").is_ok());
+        assert_eq!(vm.memory.here(), here);
+
+        // case:  words built into the VM, or being executed, can't be forgotten
+        assert!(interpret(&mut vm, "FORGET DUP\n").is_err());
+        assert!(interpret(&mut vm, "FORGET UNDEFINED\n").is_err());
+        assert!(interpret(&mut vm, "FORGET\n").is_err());
+        assert!(interpret(&mut vm, ": ZAP FORGET ; ZAP ZAP\n").is_err());
+        assert!(vm.find("ZAP").is_some());
+        assert!(vm.find("DUP").is_some());
+        assert!(vm.data_stack.is_empty());
     }
-    return Result::Ok(());
-}
 
-#[cfg(test)]
-#[allow(non_snake_case)]
-mod tests {
-    use super::*;
+    #[test]
+    fn operation_test__marker() {
+        let mut vm: VM = VM::default();
+        vm.define_core_words();
+        let here: usize = vm.memory.here();
+        let count: usize = vm.definitions.len();
 
-    use std::collections::VecDeque;
+        // everything since the marker is rolled back, search order included
+        assert!(interpret(&mut vm, "MARKER EMPTY : A 1 ; 16 ALLOT VOCABULARY V ALSO V DEFINITIONS : B 2 ; A B\n").is_ok());
+        assert_eq!(vm.data_stack, vec![Data::NUMBER(1), Data::NUMBER(2)]);
+        assert_eq!(vm.dictionary.search_order.len(), 2);
+        vm.data_stack.clear();
+        assert!(interpret(&mut vm, "EMPTY\n").is_ok());
+        assert!(vm.find("A").is_none());
+        assert!(vm.find("B").is_none());
+        assert!(vm.find("V").is_none());
+        assert!(vm.find("EMPTY").is_none());
+        assert_eq!(vm.memory.here(), here);
+        assert_eq!(vm.definitions.len(), count);
+        assert_eq!(vm.dictionary.wordlists.len(), 1);
+        assert_eq!(vm.dictionary.search_order, vec![FORTH_WORDLIST]);
+        assert_eq!(vm.dictionary.current, FORTH_WORDLIST);
 
-    use super::Data;
+        // markers may be executed by words defined before them
+        assert!(interpret(&mut vm, "MARKER FIRST DEFER CLEAN MARKER SECOND ' SECOND IS CLEAN : C 3 ; C CLEAN\n").is_ok());
+        assert!(vm.find("C").is_none());
+        assert!(vm.find("SECOND").is_none());
+        assert!(interpret(&mut vm, "4 FIRST\n").is_ok());
+        assert_eq!(vm.data_stack, vec![Data::NUMBER(3), Data::NUMBER(4)]);
+        assert_eq!(vm.definitions.len(), count);
+        assert_eq!(vm.memory.here(), here);
+        vm.data_stack.clear();
 
-    /// Helper macro to confirm operations that require data on the stack produce an
-    /// error if the stack doesn't contain enough data.
-    macro_rules! empty_stack_test_case {
-        ($vm:expr, $operation:expr) => {{
-            assert!($vm.data_stack.is_empty());
-            $vm.operations.push_back($operation);
-            assert!(execute(&mut $vm).is_err());
-        }};
+        // case:  markers can't be executed by words defined after them
+        assert!(interpret(&mut vm, "MARKER THIRD : D THIRD 1 ; D\n").is_err());
+        assert!(vm.find("D").is_some());
+        assert!(interpret(&mut vm, ": E THIRD ; E\n").is_ok());
+        assert_eq!(vm.definitions.len(), count);
+        assert!(interpret(&mut vm, "MARKER\n").is_err());
+
+        // what a marker rolls back to is out of the program's reach
+        assert!(interpret(&mut vm, "24 ALLOT HERE MARKER FOURTH HERE =\n").is_ok());
+        assert_eq!(vm.data_stack, vec![Data::NUMBER(1)]);
+        vm.data_stack.clear();
+        assert!(interpret(&mut vm, "HERE 24 - 24 BLANK 0 HERE 24 - ! FOURTH 1 DUP\n").is_ok());
+        assert_eq!(vm.data_stack, vec![Data::NUMBER(1), Data::NUMBER(1)]);
+        assert_eq!(vm.dictionary.wordlists.len(), 1);
+        assert_eq!(vm.dictionary.search_order, vec![FORTH_WORDLIST]);
+        assert!(vm.markers.is_empty());
     }
 
-    /// Helper macro to test operations that take and return a single value.
-    macro_rules! single_value_op_test_case {
-        ($vm:expr, $value:expr, $operation:expr, $expected:expr) => {{
-            $vm.data_stack = vec![
-                Data::STRING(String::from("bottom of stack - should be ignored")),
-                Data::NUMBER($value),
-            ];
-            $vm.operations = VecDeque::from([$operation]);
-            assert!(execute(&mut $vm).is_ok());
-            assert_eq!(
-                $vm.data_stack,
-                vec![
-                    Data::STRING(String::from("bottom of stack - should be ignored")),
-                    Data::NUMBER($expected),
-                ]
-            );
-        }};
+    #[test]
+    fn operation_test__float_arithmetic() {
+        let mut vm: VM = VM::default();
+        vm.define_core_words();
+
+        // literals are floats, and are kept apart from the data stack
+        assert!(interpret(&mut vm, "1.5e0 1E F+ 2 3e0 F* 1E1 F/ 4E F-\n").is_ok());
+        assert_eq!(vm.float_stack, vec![-3.25]);
+        assert_eq!(vm.data_stack, vec![Data::NUMBER(2)]);
+        vm.float_stack.clear();
+        vm.data_stack.clear();
+
+        // stack manipulation
+        assert!(interpret(&mut vm, "1E 2E FSWAP FOVER 3E FROT FDUP FDROP FDEPTH\n").is_ok());
+        assert_eq!(vm.float_stack, vec![2.0, 2.0, 3.0, 1.0]);
+        assert_eq!(vm.data_stack, vec![Data::NUMBER(4)]);
+        vm.float_stack.clear();
+        vm.data_stack.clear();
+
+        // comparisons leave flags on the data stack
+        assert!(interpret(&mut vm, "1E 2E F< 2E 1E F< 0E F0= -1E F0< 1E F0< 1E 1.05E 0.1E F~ 1E 1.05E 0.01E F~\n").is_ok());
+        assert_eq!(vm.data_stack, vec![
+            Data::NUMBER(1), Data::NUMBER(0), Data::NUMBER(1), Data::NUMBER(1), Data::NUMBER(0), Data::NUMBER(1), Data::NUMBER(0),
+        ]);
+        vm.data_stack.clear();
+        assert!(interpret(&mut vm, "0E -0E 0E F~ 1E 1.01E -0.01E F~ 1E 2E FMAX 1E 2E FMIN\n").is_ok());
+        assert_eq!(vm.data_stack, vec![Data::NUMBER(0), Data::NUMBER(1)]);
+        assert_eq!(vm.float_stack, vec![2.0, 1.0]);
+        vm.float_stack.clear();
+        vm.data_stack.clear();
+
+        // rounding
+        assert!(interpret(&mut vm, "-2.5E FLOOR -2.5E FTRUNC 2.5E FROUND 3.5E FROUND -2.5E FROUND 2.6E FROUND\n").is_ok());
+        assert_eq!(vm.float_stack, vec![-3.0, -2.0, 2.0, 4.0, -2.0, 3.0]);
+        vm.float_stack.clear();
+
+        // transcendental functions
+        assert!(interpret(&mut vm, "2E FSQRT 2E 10E F** 2E FALOG 100E FLOG 1E FEXP FLN 0E FSINCOS 1E 1E FATAN2 -3E FABS\n").is_ok());
+        assert_eq!(vm.float_stack, vec![2_f64.sqrt(), 1024.0, 100.0, 2.0, 1.0, 0.0, 1.0, std::f64::consts::FRAC_PI_4, 3.0]);
+        vm.float_stack.clear();
+        for (word, expected) in [
+            ("0E FSIN", 0.0), ("0E FCOS", 1.0), ("0E FTAN", 0.0), ("0E FASIN", 0.0), ("1E FACOS", 0.0), ("0E FATAN", 0.0),
+            ("0E FSINH", 0.0), ("0E FCOSH", 1.0), ("0E FTANH", 0.0), ("0E FASINH", 0.0), ("1E FACOSH", 0.0),
+            ("0E FATANH", 0.0), ("0E FEXPM1", 0.0), ("0E FLNP1", 0.0), ("1E FNEGATE", -1.0),
+        ] {
+            assert!(interpret(&mut vm, format!("{}\n", word).as_str()).is_ok());
+            assert_eq!(vm.float_stack.pop(), Some(expected), "{}", word);
+        }
+
+        // conversions to and from integers
+        assert!(interpret(&mut vm, "7 S>F -2.7E F>S 3 0 D>F 1E20 F>D\n").is_ok());
+        assert_eq!(vm.float_stack, vec![7.0, 3.0]);
+        assert_eq!(vm.data_stack[0], Data::NUMBER(-2));
+        vm.data_stack.remove(0);
+        assert_eq!(double_from_stack(&mut vm).unwrap() as i128, 100_000_000_000_000_000_000);
+        vm.float_stack.clear();
+
+        // compiled
+        assert!(interpret(&mut vm, ": AREA FDUP F* 314E-2 F* ; 2E AREA\n").is_ok());
+        assert_eq!(vm.float_stack, vec![12.56]);
+        vm.float_stack.clear();
+        assert!(interpret(&mut vm, "1.5E FCONSTANT HALVES 2 FLITERAL\n").is_err());
+        vm.data_stack.clear();
+        assert!(interpret(&mut vm, ": THIRDS HALVES 3E F* ; THIRDS HALVES\n").is_ok());
+        assert_eq!(vm.float_stack, vec![4.5, 1.5]);
+        vm.float_stack.clear();
+
+        // case:  floats must be on the floating-point stack
+        assert!(interpret(&mut vm, "1 2 F+\n").is_err());
+        assert!(interpret(&mut vm, "1E F+\n").is_err());
+        assert_eq!(vm.float_stack, vec![1.0]);
+        vm.float_stack.clear();
+        assert!(interpret(&mut vm, "1E 2E F~\n").is_err());
+        vm.float_stack.clear();
+        assert!(interpret(&mut vm, "FDUP\n").is_err());
+        assert!(interpret(&mut vm, "FCONSTANT NOTHING\n").is_err());
+        assert!(vm.find("NOTHING").is_none());
+        vm.float_stack.clear();
+        vm.data_stack.clear();
     }
 
-    /// Helper macro to test operations that pop two values and push a single value.
-    macro_rules! two_in_one_out_op_test_case {
-        ($vm:expr, $value1:expr, $value2:expr, $operation:expr, $expected:expr) => {{
-            $vm.data_stack = vec![
-                Data::STRING(String::from("bottom of stack - should be ignored")),
-                Data::NUMBER($value1),
-                Data::NUMBER($value2),
-            ];
-            $vm.operations = VecDeque::from([$operation]);
-            assert!(execute(&mut $vm).is_ok());
-            assert_eq!(
-                $vm.data_stack,
-                vec![
-                    Data::STRING(String::from("bottom of stack - should be ignored")),
-                    Data::NUMBER($expected),
-                ]
-            );
-        }};
+    #[test]
+    fn operation_test__float_memory() {
+        let mut vm: VM = VM::default();
+        vm.define_core_words();
+
+        assert!(interpret(&mut vm, "1 FLOATS 1 SFLOATS 1 DFLOATS 8 FLOAT+ 8 SFLOAT+ 8 DFLOAT+\n").is_ok());
+        assert_eq!(vm.data_stack, vec![
+            Data::NUMBER(8), Data::NUMBER(4), Data::NUMBER(8), Data::NUMBER(16), Data::NUMBER(12), Data::NUMBER(16),
+        ]);
+        vm.data_stack.clear();
+
+        // alignment
+        assert!(interpret(&mut vm, "1 ALLOT FALIGN HERE 1 ALLOT SFALIGN HERE 9 FALIGNED 9 SFALIGNED 8 DFALIGNED\n").is_ok());
+        let Data::NUMBER(faligned) = vm.data_stack[0];
+        let Data::NUMBER(sfaligned) = vm.data_stack[1];
+        assert_eq!(faligned % 8, 0);
+        assert_eq!(sfaligned, faligned + 4);
+        assert_eq!(vm.data_stack[2..], [Data::NUMBER(16), Data::NUMBER(12), Data::NUMBER(8)]);
+        vm.data_stack.clear();
+        assert!(interpret(&mut vm, "0 1 - FALIGNED 9223372036854775807 SFALIGNED 7 NEGATE DFALIGNED\n").is_ok());
+        assert_eq!(vm.data_stack, vec![Data::NUMBER(0), Data::NUMBER(i64::MIN), Data::NUMBER(0)]);
+        vm.data_stack.clear();
+
+        // variables
+        assert!(interpret(&mut vm, "FVARIABLE X 2.5E X F! X F@ X DF@ 0.1E X SF! X SF@\n").is_ok());
+        assert_eq!(vm.float_stack, vec![2.5, 2.5, 0.1_f32 as f64]);
+        vm.float_stack.clear();
+        assert!(interpret(&mut vm, "1E X DF! X F@ FVARIABLE Y X Y <>\n").is_ok());
+        assert_eq!(vm.float_stack, vec![1.0]);
+        assert_eq!(vm.data_stack, vec![Data::NUMBER(1)]);
+        vm.float_stack.clear();
+        vm.data_stack.clear();
+
+        // variables are released when forgotten
+        let here: usize = vm.memory.here();
+        assert!(interpret(&mut vm, "FVARIABLE Z FORGET Z\n").is_ok());
+        assert_eq!(vm.memory.here(), here);
+
+        // case:  addresses must be valid
+        assert!(interpret(&mut vm, "1E HERE F!\n").is_err());
+        assert!(interpret(&mut vm, "HERE F@\n").is_err());
+        assert!(interpret(&mut vm, "HERE SF@\n").is_err());
+        assert!(interpret(&mut vm, "1 F!\n").is_err());
+        vm.float_stack.clear();
+        vm.data_stack.clear();
     }
 
     #[test]
-    fn base_interpret_test() {
+    fn operation_test__float_display() {
         let mut vm: VM = VM::default();
-        vm.operations.push_back(Operation::NOP);
-        vm.operations.push_back(Operation::NOP);
-        assert_eq!(
-            execute(&mut vm).unwrap(),
-            (),
-        );
+        vm.define_core_words();
+        let output: BufferSink = capture_output(&mut vm);
+
+        assert!(interpret(&mut vm, "1.5E F. 1E F. 100E F. 0.001E F. -12.25E F. 0E F. 1E 3E F/ F.\n").is_ok());
+        assert_eq!(output.contents(), "1.5 1. 100. 0.001 -12.25 0. 0.333333333333333 ");
+        output.clear();
+
+        assert!(interpret(&mut vm, "5 SET-PRECISION PRECISION 1.5E FS. 12345E FS. -0.00012E FS. 0E FS.\n").is_ok());
+        assert_eq!(output.contents(), "1.5000E0 1.2345E4 -1.2000E-4 0.0000E0 ");
+        assert_eq!(vm.data_stack, vec![Data::NUMBER(5)]);
+        vm.data_stack.clear();
+        output.clear();
+
+        assert!(interpret(&mut vm, "12345E FE. 1.5E FE. 0.00012E FE. 123456E FE. 1E 0E F/ FE.\n").is_ok());
+        assert_eq!(output.contents(), "12.345E3 1.5000E0 120.00E-6 123.46E3 inf ");
+        output.clear();
+
+        // case:  PRECISION must be usable
+        assert!(interpret(&mut vm, "0 SET-PRECISION\n").is_err());
+        assert!(interpret(&mut vm, "18 SET-PRECISION\n").is_err());
+        assert!(interpret(&mut vm, "F.\n").is_err());
+        assert!(interpret(&mut vm, "PRECISION\n").is_ok());
+        assert_eq!(vm.data_stack, vec![Data::NUMBER(5)]);
+        vm.data_stack.clear();
     }
 
     #[test]
-    fn operation_test__single_value_ops() {
+    fn operation_test__float_conversion() {
         let mut vm: VM = VM::default();
+        vm.define_core_words();
 
-        // case:  stack underflow error on empty stack
-        empty_stack_test_case!(vm, Operation::ABS);
-        empty_stack_test_case!(vm, Operation::NEGATE);
+        // REPRESENT
+        let buffer: i64 = allot_string(&mut vm, "          ");
+        for (r, digits, n, negative) in [(1.5, "15000", 1, 0), (-0.0123456, "12346", -1, 1), (12345678.0, "12346", 8, 0)] {
+            vm.float_stack.push(r);
+            vm.data_stack.extend([Data::NUMBER(buffer), Data::NUMBER(5)]);
+            vm.operations.push_back(Operation::REPRESENT);
+            assert!(execute(&mut vm).is_ok());
+            assert_eq!(vm.data_stack, vec![Data::NUMBER(n), Data::NUMBER(negative), Data::NUMBER(1)]);
+            assert_eq!(vm.memory.slice(buffer, 5).unwrap(), digits.as_bytes());
+            vm.data_stack.clear();
+        }
+        vm.float_stack.push(f64::NAN);
+        vm.data_stack.extend([Data::NUMBER(buffer), Data::NUMBER(5)]);
+        vm.operations.push_back(Operation::REPRESENT);
+        assert!(execute(&mut vm).is_ok());
+        assert_eq!(vm.data_stack.pop(), Some(Data::NUMBER(0)));
+        vm.data_stack.clear();
 
-        // case:  refuses to cast a string -> int
-        vm.data_stack = vec![
-            Data::STRING(String::from("item1")),
-        ];
-        vm.operations = VecDeque::from([Operation::ABS]);
-        assert!(execute(&mut vm).is_err());
+        // digits beyond the 17 significant ones are zeros
+        let buffer: i64 = allot_string(&mut vm, &" ".repeat(20));
+        assert!(interpret(&mut vm, &format!("1E 3E F/ {} 20 REPRESENT\n", buffer)).is_ok());
+        assert_eq!(vm.data_stack, vec![Data::NUMBER(0), Data::NUMBER(0), Data::NUMBER(1)]);
+        assert_eq!(vm.memory.slice(buffer, 20).unwrap(), b"33333333333333331000");
+        vm.data_stack.clear();
 
-        vm.data_stack = vec![
-            Data::STRING(String::from("item1")),
-        ];
-        vm.operations = VecDeque::from([Operation::NEGATE]);
-        assert!(execute(&mut vm).is_err());
+        // case:  buffers beyond data space
+        assert!(interpret(&mut vm, "1E HERE 10000000000 REPRESENT\n").is_err());
+        assert!(interpret(&mut vm, "1E HERE 100000000000 REPRESENT\n").is_err());
+        vm.data_stack.clear();
+        vm.float_stack.clear();
 
-        single_value_op_test_case!(vm, 42, Operation::ABS, 42);
-        single_value_op_test_case!(vm, -42, Operation::ABS, 42);
+        // >FLOAT
+        for (text, r) in [("1.5", Some(1.5)), ("-25E-1", Some(-2.5)), (".5", Some(0.5)), ("1d2", Some(100.0)), ("   ", Some(0.0)),
+                          ("1.5.", None), ("E2", None), ("abc", None)] {
+            let addr: i64 = allot_string(&mut vm, text);
+            vm.data_stack.extend([Data::NUMBER(addr), Data::NUMBER(text.len() as i64)]);
+            vm.operations.push_back(Operation::TO_FLOAT);
+            assert!(execute(&mut vm).is_ok());
+            assert_eq!(vm.data_stack.pop(), Some(Data::NUMBER(r.is_some() as i64)), "{}", text);
+            assert_eq!(vm.float_stack.pop(), r);
+        }
 
-        single_value_op_test_case!(vm,  0, Operation::NEGATE,  0);
-        single_value_op_test_case!(vm,  1, Operation::NEGATE, -1);
-        single_value_op_test_case!(vm, -1, Operation::NEGATE,  1);
+        // float literals are only recognized in decimal; elsewhere 1E is a number
+        assert!(interpret(&mut vm, "HEX 1E DECIMAL\n").is_ok());
+        assert_eq!((vm.data_stack.pop(), vm.float_stack.is_empty()), (Some(Data::NUMBER(30)), true));
+        assert!(interpret(&mut vm, "HEX 1.5E0\n").is_err());
+        assert!(interpret(&mut vm, "DECIMAL 1E\n").is_ok());
+        assert_eq!(vm.float_stack, vec![1.0]);
+        vm.float_stack.clear();
 
+        empty_stack_test_case!(vm, Operation::REPRESENT);
+        empty_stack_test_case!(vm, Operation::TO_FLOAT);
+        empty_stack_test_case!(vm, Operation::S_TO_F);
     }
 
     #[test]
-    fn operation_test__arithmetic() {
+    fn operation_test__arguments() {
         let mut vm: VM = VM::default();
+        vm.define_core_words();
 
-        empty_stack_test_case!(vm, Operation::ADD);
-        empty_stack_test_case!(vm, Operation::SUB);
-        empty_stack_test_case!(vm, Operation::MUL);
-        empty_stack_test_case!(vm, Operation::DIV);
-        empty_stack_test_case!(vm, Operation::MOD);
-
-        two_in_one_out_op_test_case!(vm,  0,  0, Operation::ADD,  0);
-        two_in_one_out_op_test_case!(vm,  0,  1, Operation::ADD,  1);
-        two_in_one_out_op_test_case!(vm,  1,  0, Operation::ADD,  1);
-        two_in_one_out_op_test_case!(vm,  1,  1, Operation::ADD,  2);
-        two_in_one_out_op_test_case!(vm,  0, -1, Operation::ADD, -1);
-        two_in_one_out_op_test_case!(vm, -1,  0, Operation::ADD, -1);
-        two_in_one_out_op_test_case!(vm, -1, -1, Operation::ADD, -2);
+        // no program, no arguments
+        assert!(interpret(&mut vm, "ARGC 0 ARG NEXT-ARG\n").is_ok());
+        assert_eq!(vm.data_stack, vec![Data::NUMBER(0), Data::NUMBER(0), Data::NUMBER(0), Data::NUMBER(0), Data::NUMBER(0)]);
+        vm.data_stack.clear();
 
-        two_in_one_out_op_test_case!(vm,  0,  0, Operation::SUB,  0);
-        two_in_one_out_op_test_case!(vm,  0,  1, Operation::SUB, -1);
-        two_in_one_out_op_test_case!(vm,  1,  0, Operation::SUB,  1);
-        two_in_one_out_op_test_case!(vm,  1,  1, Operation::SUB,  0);
-        two_in_one_out_op_test_case!(vm,  0, -1, Operation::SUB,  1);
-        two_in_one_out_op_test_case!(vm, -1,  0, Operation::SUB, -1);
-        two_in_one_out_op_test_case!(vm, -1, -1, Operation::SUB,  0);
+        vm.arguments = vec![String::from("main.fs"), String::from("first"), String::from("second")];
+        assert!(interpret(&mut vm, "ARGC\n").is_ok());
+        assert_eq!(vm.data_stack.pop(), Some(Data::NUMBER(3)));
+        assert!(interpret(&mut vm, "0 ARG\n").is_ok());
+        assert_eq!(string_from_stack(&mut vm), "main.fs");
+        assert!(interpret(&mut vm, "2 ARG\n").is_ok());
+        assert_eq!(string_from_stack(&mut vm), "second");
 
-        two_in_one_out_op_test_case!(vm,  0,  0, Operation::MUL,  0);
-        two_in_one_out_op_test_case!(vm,  0,  1, Operation::MUL,  0);
-        two_in_one_out_op_test_case!(vm,  1,  0, Operation::MUL,  0);
-        two_in_one_out_op_test_case!(vm,  1,  1, Operation::MUL,  1);
-        two_in_one_out_op_test_case!(vm,  0, -1, Operation::MUL,  0);
-        two_in_one_out_op_test_case!(vm, -1,  0, Operation::MUL,  0);
-        two_in_one_out_op_test_case!(vm, -1, -1, Operation::MUL,  1);
-        two_in_one_out_op_test_case!(vm,  1, -1, Operation::MUL, -1);
-        two_in_one_out_op_test_case!(vm, -1,  1, Operation::MUL, -1);
+        // arguments are consumed in turn, leaving the program
+        assert!(interpret(&mut vm, "NEXT-ARG\n").is_ok());
+        assert_eq!(string_from_stack(&mut vm), "first");
+        assert!(interpret(&mut vm, "NEXT-ARG ARGC 1 ARG\n").is_ok());
+        assert_eq!(string_from_stack(&mut vm), "");
+        assert_eq!(vm.data_stack.pop(), Some(Data::NUMBER(1)));
+        assert_eq!(string_from_stack(&mut vm), "second");
+        assert!(interpret(&mut vm, "NEXT-ARG 1 NEGATE ARG\n").is_ok());
+        assert_eq!(vm.data_stack, vec![Data::NUMBER(0), Data::NUMBER(0), Data::NUMBER(0), Data::NUMBER(0)]);
+        assert_eq!(vm.arguments, vec![String::from("main.fs")]);
+        vm.data_stack.clear();
 
-        two_in_one_out_op_test_case!(vm,  0,  1, Operation::DIV,  0);
-        two_in_one_out_op_test_case!(vm,  1,  1, Operation::DIV,  1);
-        two_in_one_out_op_test_case!(vm,  0, -1, Operation::DIV,  0);
-        two_in_one_out_op_test_case!(vm, -1, -1, Operation::DIV,  1);
-        two_in_one_out_op_test_case!(vm,  1, -1, Operation::DIV, -1);
-        two_in_one_out_op_test_case!(vm, -1,  1, Operation::DIV, -1);
+        // arguments longer than a transient region are allotted in data space
+        let long: String = "x".repeat(1000);
+        vm.arguments = vec![String::from("main.fs"), long.clone()];
+        assert!(interpret(&mut vm, "1 ARG\n").is_ok());
+        assert_eq!(string_from_stack(&mut vm), long);
+        assert!(interpret(&mut vm, "NEXT-ARG\n").is_ok());
+        assert_eq!(string_from_stack(&mut vm), long);
 
-        two_in_one_out_op_test_case!(vm,  0,  1, Operation::MOD,  0);
-        two_in_one_out_op_test_case!(vm,  1,  1, Operation::MOD,  0);
-        two_in_one_out_op_test_case!(vm,  2,  1, Operation::MOD,  0);
-        two_in_one_out_op_test_case!(vm,  1,  2, Operation::MOD,  1);
-        two_in_one_out_op_test_case!(vm, -1, -2, Operation::MOD,  1);
-        two_in_one_out_op_test_case!(vm,  1, -2, Operation::MOD,  1);
-        two_in_one_out_op_test_case!(vm, -1,  2, Operation::MOD,  1);
+        empty_stack_test_case!(vm, Operation::ARG);
+    }
 
+    /// Create an empty directory for a test to keep its files in
+    fn test_directory(name: &str) -> PathBuf {
+        let directory: PathBuf = std::env::temp_dir().join(format!("oxforth-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&directory);
+        std::fs::create_dir_all(directory.join("lib")).unwrap();
+        return directory;
     }
 
     #[test]
-    fn operation_test__comparisons() {
+    fn operation_test__file_access() {
         let mut vm: VM = VM::default();
+        vm.define_core_words();
+        let directory: PathBuf = test_directory("file-access");
+        let path: String = directory.join("data.txt").display().to_string();
+        let buffer: i64 = vm.memory.here() as i64;
+        vm.allot(16).unwrap();
 
-        empty_stack_test_case!(vm, Operation::CMP_EQ);
-        empty_stack_test_case!(vm, Operation::CMP_GT);
-        empty_stack_test_case!(vm, Operation::CMP_LT);
-        empty_stack_test_case!(vm, Operation::CMP_NE);
-        empty_stack_test_case!(vm, Operation::MAX);
-        empty_stack_test_case!(vm, Operation::MIN);
+        // case:  files must exist to be opened
+        assert!(interpret(&mut vm, &format!("S\" {}\" R/O OPEN-FILE\n", path)).is_ok());
+        assert_eq!(vm.data_stack, vec![Data::NUMBER(0), Data::NUMBER(IOR_NOT_FOUND)]);
+        vm.data_stack.clear();
 
-        two_in_one_out_op_test_case!(vm,  1,  1, Operation::CMP_EQ,  1);
-        two_in_one_out_op_test_case!(vm,  1,  0, Operation::CMP_EQ,  0);
-        two_in_one_out_op_test_case!(vm,  0,  1, Operation::CMP_EQ,  0);
-        two_in_one_out_op_test_case!(vm, -1, -1, Operation::CMP_EQ,  1);
-        two_in_one_out_op_test_case!(vm,  0, -1, Operation::CMP_EQ,  0);
-        two_in_one_out_op_test_case!(vm, -1,  0, Operation::CMP_EQ,  0);
+        // writing
+        assert!(interpret(&mut vm, &format!("S\" {}\" W/O BIN CREATE-FILE\n", path)).is_ok());
+        assert_eq!(vm.data_stack.pop(), Some(Data::NUMBER(0)));
+        let Some(Data::NUMBER(fileid)) = vm.data_stack.pop() else { panic!() };
+        assert!(interpret(&mut vm, &format!("S\" first\" {0} WRITE-LINE S\" second line\" {0} WRITE-FILE\n", fileid)).is_ok());
+        assert!(interpret(&mut vm, &format!("{0} FILE-POSITION {0} FILE-SIZE {0} CLOSE-FILE {0} CLOSE-FILE\n", fileid)).is_ok());
+        assert_eq!(vm.data_stack, vec![
+            Data::NUMBER(0), Data::NUMBER(0),
+            Data::NUMBER(17), Data::NUMBER(0), Data::NUMBER(0),
+            Data::NUMBER(17), Data::NUMBER(0), Data::NUMBER(0),
+            Data::NUMBER(0), Data::NUMBER(IOR_FAILURE),
+        ]);
+        vm.data_stack.clear();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "first\nsecond line");
 
-        two_in_one_out_op_test_case!(vm,  1,  1, Operation::CMP_GT,  0);
-        two_in_one_out_op_test_case!(vm,  1,  0, Operation::CMP_GT,  1);
-        two_in_one_out_op_test_case!(vm,  0,  1, Operation::CMP_GT,  0);
-        two_in_one_out_op_test_case!(vm, -1, -1, Operation::CMP_GT,  0);
-        two_in_one_out_op_test_case!(vm,  0, -1, Operation::CMP_GT,  1);
-        two_in_one_out_op_test_case!(vm, -1,  0, Operation::CMP_GT,  0);
+        // reading, a line at a time
+        assert!(interpret(&mut vm, &format!("S\" {}\" R/O OPEN-FILE DROP\n", path)).is_ok());
+        let Some(Data::NUMBER(fileid)) = vm.data_stack.pop() else { panic!() };
+        assert!(interpret(&mut vm, &format!("{} 16 {} READ-LINE\n", buffer, fileid)).is_ok());
+        assert_eq!(vm.data_stack, vec![Data::NUMBER(5), Data::NUMBER(1), Data::NUMBER(0)]);
+        assert_eq!(vm.memory.slice(buffer, 5).unwrap(), b"first");
+        vm.data_stack.clear();
 
-        two_in_one_out_op_test_case!(vm,  1,  1, Operation::CMP_LT,  0);
-        two_in_one_out_op_test_case!(vm,  1,  0, Operation::CMP_LT,  0);
-        two_in_one_out_op_test_case!(vm,  0,  1, Operation::CMP_LT,  1);
-        two_in_one_out_op_test_case!(vm, -1, -1, Operation::CMP_LT,  0);
-        two_in_one_out_op_test_case!(vm,  0, -1, Operation::CMP_LT,  0);
-        two_in_one_out_op_test_case!(vm, -1,  0, Operation::CMP_LT,  1);
+        // lines longer than the buffer are read a buffer at a time
+        assert!(interpret(&mut vm, &format!("{0} 6 {1} READ-LINE {0} 6 {1} READ-LINE {0} 6 {1} READ-LINE\n", buffer, fileid)).is_ok());
+        assert_eq!(vm.data_stack, vec![
+            Data::NUMBER(6), Data::NUMBER(1), Data::NUMBER(0),
+            Data::NUMBER(5), Data::NUMBER(1), Data::NUMBER(0),
+            Data::NUMBER(0), Data::NUMBER(0), Data::NUMBER(0),
+        ]);
+        assert_eq!(vm.memory.slice(buffer, 5).unwrap(), b" line");
+        vm.data_stack.clear();
 
-        two_in_one_out_op_test_case!(vm,  1,  1, Operation::CMP_NE,  0);
-        two_in_one_out_op_test_case!(vm,  1,  0, Operation::CMP_NE,  1);
-        two_in_one_out_op_test_case!(vm,  0,  1, Operation::CMP_NE,  1);
-        two_in_one_out_op_test_case!(vm, -1, -1, Operation::CMP_NE,  0);
-        two_in_one_out_op_test_case!(vm,  0, -1, Operation::CMP_NE,  1);
-        two_in_one_out_op_test_case!(vm, -1,  0, Operation::CMP_NE,  1);
+        // reading characters, from anywhere in the file
+        assert!(interpret(&mut vm, &format!("3 0 {0} REPOSITION-FILE {1} 4 {0} READ-FILE {1} 16 {0} READ-FILE\n", fileid, buffer)).is_ok());
+        assert_eq!(vm.data_stack, vec![Data::NUMBER(0), Data::NUMBER(4), Data::NUMBER(0), Data::NUMBER(10), Data::NUMBER(0)]);
+        assert_eq!(vm.memory.slice(buffer, 10).unwrap(), b"econd line");
+        vm.data_stack.clear();
+        assert!(interpret(&mut vm, &format!("{} CLOSE-FILE DROP\n", fileid)).is_ok());
 
-        two_in_one_out_op_test_case!(vm,  0,  0, Operation::MAX,  0);
-        two_in_one_out_op_test_case!(vm,  1,  0, Operation::MAX,  1);
-        two_in_one_out_op_test_case!(vm,  0,  1, Operation::MAX,  1);
-        two_in_one_out_op_test_case!(vm, -1, -1, Operation::MAX, -1);
-        two_in_one_out_op_test_case!(vm,  0, -1, Operation::MAX,  0);
-        two_in_one_out_op_test_case!(vm, -1,  0, Operation::MAX,  0);
+        // renaming and deleting
+        let renamed: String = directory.join("renamed.txt").display().to_string();
+        assert!(interpret(&mut vm, &format!("S\" {}\" S\" {}\" RENAME-FILE\n", path, renamed)).is_ok());
+        assert!(interpret(&mut vm, &format!("S\" {0}\" DELETE-FILE S\" {0}\" DELETE-FILE\n", renamed)).is_ok());
+        assert_eq!(vm.data_stack, vec![Data::NUMBER(0), Data::NUMBER(0), Data::NUMBER(IOR_NOT_FOUND)]);
+        vm.data_stack.clear();
 
-        two_in_one_out_op_test_case!(vm,  1,  1, Operation::MIN,  1);
-        two_in_one_out_op_test_case!(vm,  1,  0, Operation::MIN,  0);
-        two_in_one_out_op_test_case!(vm,  0,  1, Operation::MIN,  0);
-        two_in_one_out_op_test_case!(vm, -1, -1, Operation::MIN, -1);
-        two_in_one_out_op_test_case!(vm,  0, -1, Operation::MIN, -1);
-        two_in_one_out_op_test_case!(vm, -1,  0, Operation::MIN, -1);
+        // access methods
+        assert!(interpret(&mut vm, "R/O R/W W/O R/O BIN\n").is_ok());
+        assert_eq!(vm.data_stack, vec![
+            Data::NUMBER(READ_ONLY), Data::NUMBER(READ_WRITE), Data::NUMBER(WRITE_ONLY), Data::NUMBER(READ_ONLY | BINARY),
+        ]);
+        vm.data_stack.clear();
 
+        empty_stack_test_case!(vm, Operation::READ_LINE);
+        std::fs::remove_dir_all(&directory).unwrap();
     }
 
     #[test]
-    fn operation_test__drop() {
+    fn operation_test__include() {
         let mut vm: VM = VM::default();
+        vm.define_core_words();
+        let directory: PathBuf = test_directory("include");
+        let main: String = directory.join("main.fs").display().to_string();
+        std::fs::write(&main, "REQUIRE lib/a.fs\nINCLUDE lib/b.fs\n: FROM-MAIN 3 ;\n").unwrap();
+        std::fs::write(directory.join("lib/a.fs"), "S\" b.fs\" REQUIRED\nREQUIRE b.fs 2\n").unwrap();
+        std::fs::write(directory.join("lib/b.fs"), "1\n").unwrap();
 
-        // case:  stack underflow error on empty stack
-        empty_stack_test_case!(vm, Operation::DROP);
+        // paths are relative to the file including them, and files are required once
+        assert!(interpret(&mut vm, &format!("INCLUDE {} FROM-MAIN\n", main)).is_ok());
+        assert_eq!(vm.data_stack, vec![Data::NUMBER(1), Data::NUMBER(2), Data::NUMBER(1), Data::NUMBER(3)]);
+        assert!(vm.files.including.is_empty());
+        vm.data_stack.clear();
 
-        // case:  drop removes a single stack item from the top
-        vm.data_stack = vec![
-            Data::STRING(String::from("item1")),
-            Data::STRING(String::from("item2")),  // <- top of stack
-        ];
-        vm.operations = VecDeque::from([Operation::DROP]);
-        assert!(execute(&mut vm).is_ok());
+        // what's being executed is picked up where it left off
+        assert!(interpret(&mut vm, &format!(": LOAD S\" {}\" INCLUDED 4 ; LOAD 5\n", directory.join("lib/b.fs").display())).is_ok());
+        assert_eq!(vm.data_stack, vec![Data::NUMBER(1), Data::NUMBER(4), Data::NUMBER(5)]);
+        vm.data_stack.clear();
+
+        // errors are described along with where they occurred
+        let broken: PathBuf = directory.join("broken.fs");
+        std::fs::write(&broken, "1\n2 UNDEFINED\n").unwrap();
         assert_eq!(
-            vm.data_stack,
-            vec![
-                Data::STRING(String::from("item1")),
-            ]
+            interpret(&mut vm, &format!("INCLUDE {}\n", broken.display())),
+            Err(format!("{}:2: undefined word: Token {{ token: \"UNDEFINED\", symbol: UNDEFINED }}", broken.display())),
         );
+        assert!(vm.files.including.is_empty());
+        vm.data_stack.clear();
+
+        // case:  files including themselves. Test threads get a smaller stack
+        // than a process' main thread, so this runs on one of the usual size.
+        let recursive: PathBuf = directory.join("recursive.fs");
+        std::fs::write(&recursive, "INCLUDE recursive.fs\n").unwrap();
+        let recursion = std::thread::Builder::new().stack_size(8 << 20).spawn(move || {
+            let mut vm: VM = VM::default();
+            vm.define_core_words();
+            let result: Result<Outcome, String> = interpret(&mut vm, &format!("INCLUDE {}\n", recursive.display()));
+            return (result, vm.files.including.is_empty());
+        });
+        let (result, unwound): (Result<Outcome, String>, bool) = recursion.unwrap().join().unwrap();
+        assert!(result.unwrap_err().ends_with("since includes are nested too deeply"));
+        assert!(unwound);
 
+        // case:  missing files
+        assert!(interpret(&mut vm, &format!("INCLUDE {}\n", directory.join("missing.fs").display())).is_err());
+
+        // halting stops interpretation altogether
+        let halting: PathBuf = directory.join("halting.fs");
+        std::fs::write(&halting, "7 (BYE) 8\n9\n").unwrap();
+        assert_eq!(interpret(&mut vm, &format!("INCLUDE {} 10\n", halting.display())), Ok(Outcome::HALTED(Some(7))));
+        assert!(vm.data_stack.is_empty());
+
+        // case:  files outside the sandbox, unless the host includes them
+        vm.files.policy = Policy::sandboxed();
+        assert!(vm.files.policy.allow(&directory.join("lib"), Access::READ).is_ok());
+        assert!(interpret(&mut vm, &format!("INCLUDE {}\n", main)).is_err());
+        assert!(interpret(&mut vm, &format!("INCLUDE {}\n", directory.join("lib/b.fs").display())).is_ok());
+        assert!(include_source(&mut vm, Path::new(&main), "INCLUDE lib/b.fs\n").is_ok());
+        assert_eq!(vm.data_stack, vec![Data::NUMBER(1), Data::NUMBER(1)]);
+        vm.data_stack.clear();
+
+        empty_stack_test_case!(vm, Operation::INCLUDED);
+        std::fs::remove_dir_all(&directory).unwrap();
     }
 
     #[test]
-    fn operation_test__dup() {
+    fn operation_test__limits() {
         let mut vm: VM = VM::default();
+        vm.define_core_words();
+        assert!(interpret(&mut vm, ": THREE 1 2 3 ; : SPIN RECURSE ; : DEEP RECURSE 1 ;\n").is_ok());
 
-        // case:  stack underflow error on empty stack
-        empty_stack_test_case!(vm, Operation::DUP);
+        // execution picks up where it stopped once the limit is raised
+        vm.limits.operations = Some(vm._ops_applied + 3);
+        let err: VirtualMachineError = evaluate(&mut vm, "THREE\n").unwrap_err();
+        assert_eq!(err.limit, Some(Limit::OPERATIONS));
+        assert_eq!(err.limit.unwrap().code(), None);
+        assert_eq!(vm.data_stack, vec![Data::NUMBER(1), Data::NUMBER(2)]);
+        assert_eq!(vm.return_stack.len(), 1);
+        vm.limits.operations = None;
+        assert_eq!(execute(&mut vm).unwrap(), Outcome::COMPLETED);
+        assert_eq!(vm.data_stack, vec![Data::NUMBER(1), Data::NUMBER(2), Data::NUMBER(3)]);
+        vm.data_stack.clear();
 
-        // case:  duplicates the top stack entry
-        vm.data_stack = vec![
-            Data::STRING(String::from("item1")),
-            Data::STRING(String::from("item2")),  // <- top of stack
-        ];
-        vm.operations = VecDeque::from([Operation::DUP]);
-        assert!(execute(&mut vm).is_ok());
-        assert_eq!(
-            vm.data_stack,
-            vec![
-                Data::STRING(String::from("item1")),
-                Data::STRING(String::from("item2")),
-                Data::STRING(String::from("item2")),  // <- top of stack
-            ]
-        );
+        // case:  endless loops
+        vm.limits.operations = Some(vm._ops_applied + 1000);
+        assert_eq!(evaluate(&mut vm, "SPIN\n").unwrap_err().limit, Some(Limit::OPERATIONS));
+        vm.limits.operations = Some(vm._ops_applied + 1000);
+        assert_eq!(execute(&mut vm).unwrap_err().limit, Some(Limit::OPERATIONS));
+        vm.reset();
+        vm.limits.operations = None;
+
+        // case:  limits exceeded by included files, which carry on from there
+        let directory: PathBuf = test_directory("limits");
+        std::fs::write(directory.join("three.fs"), "THREE\nDROP DROP DROP 5\n").unwrap();
+        assert!(interpret(&mut vm, &format!(": LOAD S\" {}\" INCLUDED 4 ;\n", directory.join("three.fs").display())).is_ok());
+        vm.limits.operations = Some(vm._ops_applied + 7);
+        let err: VirtualMachineError = evaluate(&mut vm, "LOAD 6\n").unwrap_err();
+        assert_eq!(err.limit, Some(Limit::OPERATIONS));
+        assert!(err.msg.ends_with("three.fs:1: operation limit exceeded"));
+        assert_eq!(vm.data_stack, vec![Data::NUMBER(1), Data::NUMBER(2)]);
+        vm.limits.operations = None;
+        assert_eq!(resume(&mut vm, Slice::OPERATIONS(1000)).unwrap(), Outcome::COMPLETED);
+        assert_eq!(vm.data_stack, vec![Data::NUMBER(5), Data::NUMBER(4), Data::NUMBER(6)]);
+        assert!(vm.sources.is_empty() && vm.files.including.is_empty());
+        vm.data_stack.clear();
+
+        // case:  other errors abandon included files
+        std::fs::write(directory.join("three.fs"), "THREE\nNOSUCHWORD 5\n").unwrap();
+        assert!(evaluate(&mut vm, "LOAD\n").unwrap_err().msg.contains("three.fs:2: undefined word"));
+        assert!(vm.sources.is_empty() && vm.files.including.is_empty() && vm.return_stack.is_empty());
+        vm.reset();
+        vm.data_stack.clear();
+
+        // stacks
+        vm.limits.data_stack = Some(2);
+        let err: VirtualMachineError = evaluate(&mut vm, "THREE\n").unwrap_err();
+        assert_eq!((err.msg.as_str(), err.limit.unwrap().code()), ("stack overflow", Some(-3)));
+        vm.reset();
+        vm.data_stack.clear();
+        assert_eq!(evaluate(&mut vm, "1 2 3 DROP\n").unwrap_err().limit, Some(Limit::DATA_STACK));
+        vm.reset();
+        vm.data_stack.clear();
+        assert_eq!(evaluate(&mut vm, "1E 2E 3E\n").unwrap_err().limit, Some(Limit::FLOAT_STACK));
+        vm.float_stack.clear();
+        assert!(interpret(&mut vm, "1 2 DROP DROP\n").is_ok());
+        vm.limits.data_stack = None;
+
+        vm.limits.return_stack = Some(8);
+        let err: VirtualMachineError = evaluate(&mut vm, "DEEP\n").unwrap_err();
+        assert_eq!((err.msg.as_str(), err.limit.unwrap().code()), ("return stack overflow", Some(-5)));
+        assert_eq!(vm.return_stack.len(), 9);
+        vm.reset();
+        vm.limits.return_stack = None;
 
+        // data space
+        vm.limits.data_space = Some(vm.memory.here() - DATA_SPACE_ADDR + 16);
+        assert!(interpret(&mut vm, "8 ALLOT 8 ALLOT\n").is_ok());
+        let err: VirtualMachineError = evaluate(&mut vm, "1 ALLOT\n").unwrap_err();
+        assert_eq!((err.msg.as_str(), err.limit.unwrap().code()), ("data space overflow", Some(-8)));
+        vm.reset();
+        vm.data_stack.clear();
+        assert!(interpret(&mut vm, "8 NEGATE ALLOT 8 ALLOT\n").is_ok());
+        let err: VirtualMachineError = evaluate(&mut vm, ": TOO-BIG S\" too big\" ;\n").unwrap_err();
+        assert_eq!((err.msg.as_str(), err.limit), ("data space overflow", None));
+        vm.reset();
+
+        // ALLOT and FALIGN are applied again if execution resumes
+        assert!(interpret(&mut vm, ": GROW 1 ALLOT 7 ;\n").is_ok());
+        vm.limits.data_space = Some(vm.memory.here() - DATA_SPACE_ADDR);
+        let here: usize = vm.memory.here();
+        assert_eq!(evaluate(&mut vm, "GROW\n").unwrap_err().limit, Some(Limit::DATA_SPACE));
+        assert_eq!(vm.data_stack, vec![Data::NUMBER(1)]);
+        vm.limits.data_space = Some(vm.memory.here() - DATA_SPACE_ADDR + 1);
+        assert_eq!(execute(&mut vm).unwrap(), Outcome::COMPLETED);
+        assert_eq!((vm.memory.here(), vm.data_stack.clone()), (here + 1, vec![Data::NUMBER(7)]));
+        vm.data_stack.clear();
+        assert_eq!(evaluate(&mut vm, "FALIGN\n").unwrap_err().limit, Some(Limit::DATA_SPACE));
+        vm.limits.data_space = None;
+        assert_eq!(execute(&mut vm).unwrap(), Outcome::COMPLETED);
+        assert_eq!(vm.memory.here() % FLOAT_SIZE, 0);
+        assert!(vm.data_stack.is_empty());
+        vm.limits.data_space = None;
+
+        // without limits, the VM still mustn't exhaust the host or panic
+        assert_eq!(interpret(&mut vm, "HERE 1000000000000 ALLOT HERE -\n").unwrap_err(), "data space overflow");
+        vm.data_stack.clear();
+        assert_eq!(interpret(&mut vm, "99999999999999999999\n").unwrap_err(), "number out of range: 99999999999999999999");
+        assert!(interpret(&mut vm, "9223372036854775807 DUP 1 + SWAP NEGATE 1 - 1 NEGATE /\n").is_ok());
+        assert_eq!(vm.data_stack, vec![Data::NUMBER(i64::MIN), Data::NUMBER(i64::MIN)]);
+        assert!(interpret(&mut vm, "1 NEGATE MOD SWAP ABS\n").is_ok());
+        assert_eq!(vm.data_stack, vec![Data::NUMBER(0), Data::NUMBER(i64::MIN)]);
     }
 
     #[test]
-    fn operation_test__zero_comparisons() {
+    fn operation_test__time_slices() {
         let mut vm: VM = VM::default();
+        vm.define_core_words();
+        assert!(interpret(&mut vm, ": THREE 1 2 3 ; : SPIN RECURSE ;\n").is_ok());
 
-        // case:  stack underflow error on empty stack
-        empty_stack_test_case!(vm, Operation::ZERO_EQ);
-        empty_stack_test_case!(vm, Operation::ZERO_GT);
-        empty_stack_test_case!(vm, Operation::ZERO_LT);
-        empty_stack_test_case!(vm, Operation::ZERO_NE);
+        // the rest of the line is interpreted as execution is resumed
+        assert_eq!(evaluate_for(&mut vm, "THREE 4\n", Slice::OPERATIONS(2)).unwrap(), Outcome::YIELDED);
+        assert_eq!(vm.data_stack, vec![Data::NUMBER(1)]);
+        assert_eq!(resume(&mut vm, Slice::OPERATIONS(2)).unwrap(), Outcome::YIELDED);
+        assert_eq!(vm.data_stack, vec![Data::NUMBER(1), Data::NUMBER(2), Data::NUMBER(3)]);
+        assert_eq!(vm.return_stack.len(), 1);
+        assert_eq!(resume(&mut vm, Slice::OPERATIONS(2)).unwrap(), Outcome::COMPLETED);
+        assert_eq!(vm.data_stack, vec![Data::NUMBER(1), Data::NUMBER(2), Data::NUMBER(3), Data::NUMBER(4)]);
+        assert!(vm.return_stack.is_empty());
+        vm.data_stack.clear();
 
-        // case:  refuses to cast a string -> int, drops value from stack
-        vm.data_stack = vec![Data::STRING(String::from("item1"))];
-        vm.operations = VecDeque::from([Operation::ZERO_EQ]);
-        assert!(execute(&mut vm).is_err());
-        assert_eq!(vm.data_stack.len(), 0);
+        // endless loops yield when their time is up
+        let ops_applied: u64 = vm._ops_applied;
+        assert_eq!(evaluate_for(&mut vm, "SPIN\n", Slice::OPERATIONS(1000)).unwrap(), Outcome::YIELDED);
+        assert_eq!(vm._ops_applied, ops_applied + 1000);
+        let start: Instant = Instant::now();
+        assert_eq!(execute_for(&mut vm, Slice::DURATION(Duration::from_millis(10))).unwrap(), Outcome::YIELDED);
+        assert!(start.elapsed() >= Duration::from_millis(10));
+        assert_eq!(vm.return_stack.len(), 1);
+        vm.reset();
 
-        vm.data_stack = vec![Data::STRING(String::from("item1"))];
-        vm.operations = VecDeque::from([Operation::ZERO_GT]);
-        assert!(execute(&mut vm).is_err());
-        assert_eq!(vm.data_stack.len(), 0);
+        // included files yield too, and carry on from there
+        let directory: PathBuf = test_directory("time_slices");
+        std::fs::write(directory.join("three.fs"), "THREE\nDROP DROP DROP 5\n").unwrap();
+        let line: String = format!("S\" {}\" INCLUDED 4\n", directory.join("three.fs").display());
+        assert_eq!(evaluate_for(&mut vm, &line, Slice::OPERATIONS(5)).unwrap(), Outcome::YIELDED);
+        assert_eq!(vm.data_stack, vec![Data::NUMBER(1), Data::NUMBER(2)]);
+        assert_eq!(vm.sources.len(), 1);
+        assert_eq!(execute_for(&mut vm, Slice::OPERATIONS(1000)).unwrap(), Outcome::COMPLETED);
+        assert_eq!(vm.data_stack, vec![Data::NUMBER(5)]);
+        assert!(vm.sources.is_empty());
+        assert_eq!(resume(&mut vm, Slice::OPERATIONS(1000)).unwrap(), Outcome::COMPLETED);
+        assert_eq!(vm.data_stack, vec![Data::NUMBER(5), Data::NUMBER(4)]);
+        vm.data_stack.clear();
+        std::fs::remove_dir_all(&directory).unwrap();
 
-        vm.data_stack = vec![Data::STRING(String::from("item1"))];
-        vm.operations = VecDeque::from([Operation::ZERO_LT]);
-        assert!(execute(&mut vm).is_err());
-        assert_eq!(vm.data_stack.len(), 0);
+        // execution that finishes within its time slice doesn't yield
+        assert_eq!(evaluate_for(&mut vm, "THREE\n", Slice::DURATION(Duration::from_secs(60))).unwrap(), Outcome::COMPLETED);
+        assert_eq!(evaluate_for(&mut vm, "BYE\n", Slice::OPERATIONS(1)).unwrap(), Outcome::HALTED(None));
+        assert_eq!(vm.data_stack, vec![Data::NUMBER(1), Data::NUMBER(2), Data::NUMBER(3)]);
+    }
 
-        vm.data_stack = vec![Data::STRING(String::from("item1"))];
-        vm.operations = VecDeque::from([Operation::ZERO_NE]);
-        assert!(execute(&mut vm).is_err());
-        assert_eq!(vm.data_stack.len(), 0);
+    #[test]
+    fn operation_test__native_words() {
+        let mut vm: VM = VM::default();
+        vm.define_core_words();
+        vm.define_native("HYPOT", 2, 1, |vm: &mut VM| {
+            let b: i64 = vm.pop()?;
+            let a: i64 = vm.pop()?;
+            vm.push(((a * a + b * b) as f64).sqrt() as i64);
+            return Result::Ok(());
+        });
+        let count: Rc<Cell<i64>> = Rc::new(Cell::new(0));
+        let counter: Rc<Cell<i64>> = count.clone();
+        vm.define_native("TICK", 0, 0, move |_: &mut VM| {
+            counter.set(counter.get() + 1);
+            return Result::Ok(());
+        });
+        vm.define_native("LEAKY", 1, 1, |vm: &mut VM| {
+            vm.push(0);
+            return Result::Ok(());
+        });
+        vm.define_native("FAILS", 0, 1, |_: &mut VM| {
+            return Result::Err(
+                VirtualMachineError {
+                    msg: String::from("failed"),
+                    ..VirtualMachineError::default()
+                }
+            );
+        });
+        vm.define_native("GREEDY", 1, 1, |vm: &mut VM| {
+            let a: i64 = vm.pop()?;
+            let b: i64 = vm.pop()?;
+            vm.push(a + b);
+            return Result::Ok(());
+        });
+        vm.define_native("HALVE", 1, 1, |vm: &mut VM| {
+            let n: i64 = vm.pop()?;
+            if n % 2 != 0 {
+                return Result::Err(
+                    VirtualMachineError {
+                        msg: String::from("odd"),
+                        ..VirtualMachineError::default()
+                    }
+                );
+            }
+            vm.push(n / 2);
+            return Result::Ok(());
+        });
+        vm.define_native("HUGE", 0, usize::MAX, |_: &mut VM| {
+            return Result::Ok(());
+        });
 
-        // case:  normal comparisons
-        single_value_op_test_case!(vm, 1, Operation::ZERO_EQ, 0);
-        single_value_op_test_case!(vm, 0, Operation::ZERO_EQ, 1);
-        single_value_op_test_case!(vm, -1, Operation::ZERO_EQ, 0);
+        // interpreted, compiled, and executed
+        assert!(interpret(&mut vm, "3 4 HYPOT\n").is_ok());
+        assert_eq!(vm.data_stack, vec![Data::NUMBER(5)]);
+        vm.data_stack.clear();
+        assert!(interpret(&mut vm, ": TICKS 0 DO TICK LOOP ; : H 5 12 HYPOT ;\n").is_ok());
+        assert!(interpret(&mut vm, "3 TICKS TICK H 8 15 ' HYPOT EXECUTE\n").is_ok());
+        assert_eq!(vm.data_stack, vec![Data::NUMBER(13), Data::NUMBER(17)]);
+        assert_eq!(count.get(), 4);
+        vm.data_stack.clear();
 
-        single_value_op_test_case!(vm, 1, Operation::ZERO_GT, 1);
-        single_value_op_test_case!(vm, 0, Operation::ZERO_GT, 0);
-        single_value_op_test_case!(vm, -1, Operation::ZERO_GT, 0);
+        // stack effects are checked
+        assert_eq!(interpret(&mut vm, "1 HYPOT\n").unwrap_err(), "stack underflow");
+        vm.data_stack.clear();
+        assert_eq!(interpret(&mut vm, "1 LEAKY\n").unwrap_err(), "LEAKY didn't keep to its stack effect ( 1 -- 1 )");
+        vm.data_stack.clear();
+        assert_eq!(interpret(&mut vm, "1 HUGE\n").unwrap_err(), "HUGE didn't keep to its stack effect ( 0 -- 18446744073709551615 )");
+        vm.data_stack.clear();
 
-        single_value_op_test_case!(vm, 1, Operation::ZERO_LT, 0);
-        single_value_op_test_case!(vm, 0, Operation::ZERO_LT, 0);
-        single_value_op_test_case!(vm, -1, Operation::ZERO_LT, 1);
+        // cells below the inputs are out of reach, but left as they were
+        assert_eq!(interpret_line(&mut vm, "1 2 GREEDY\n").unwrap_err(), "stack underflow");
+        assert_eq!(vm.data_stack, vec![Data::NUMBER(1), Data::NUMBER(2)]);
+        assert_eq!(vm.stack_base, 0);
+        vm.data_stack.clear();
+        assert!(interpret(&mut vm, "1 2 3 4 HYPOT 6 8 HYPOT\n").is_ok());
+        assert_eq!(vm.data_stack, vec![Data::NUMBER(1), Data::NUMBER(2), Data::NUMBER(5), Data::NUMBER(10)]);
+        vm.data_stack.clear();
 
-        single_value_op_test_case!(vm, 1, Operation::ZERO_NE, 1);
-        single_value_op_test_case!(vm, 0, Operation::ZERO_NE, 0);
-        single_value_op_test_case!(vm, -1, Operation::ZERO_NE, 1);
+        // the stack is left as it was if the word fails
+        assert!(interpret(&mut vm, "7 8 HALVE\n").is_ok());
+        assert_eq!(vm.data_stack, vec![Data::NUMBER(7), Data::NUMBER(4)]);
+        assert_eq!(interpret_line(&mut vm, "HALVE HALVE HALVE\n").unwrap_err(), "odd");
+        assert_eq!(vm.data_stack, vec![Data::NUMBER(7), Data::NUMBER(1)]);
+        vm.data_stack.clear();
+        assert_eq!(interpret_line(&mut vm, "7 HALVE\n").unwrap_err(), "odd");
+        assert_eq!(vm.data_stack, vec![Data::NUMBER(7)]);
+        vm.data_stack.clear();
 
+        // errors are propagated
+        assert_eq!(interpret(&mut vm, "FAILS\n").unwrap_err(), "failed");
+        assert!(vm.data_stack.is_empty());
     }
 
 }
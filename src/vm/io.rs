@@ -0,0 +1,417 @@
+//! Character I/O
+//!
+//! The VM never touches the process' stdin or stdout by itself. Words that
+//! display text write to an OutputSink, and words that read text read from an
+//! InputSource, both of which are owned by the VM and chosen by the host.
+//! Until the host says otherwise, output is discarded and input is empty.
+//!
+//! There's no standard input or output on WebAssembly (wasm32), so the sinks
+//! and sources for them aren't built there. Hosts such as a browser pass the
+//! output to a callback instead (e.g. via CallbackSink).
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::io;
+#[cfg(not(target_arch = "wasm32"))]
+use std::io::{stdin, stdout, BufRead, Read, Write};
+use std::rc::Rc;
+
+/// Destination of text displayed by a Forth program (e.g. by TYPE or ".")
+pub trait OutputSink {
+
+    /// Display the given characters
+    fn write(&mut self, bytes: &[u8]) -> io::Result<()>;
+
+    /// Make sure everything written so far has been displayed
+    fn flush(&mut self) -> io::Result<()> {
+        return Result::Ok(());
+    }
+
+}
+
+/// Origin of text read by a Forth program, a line or a key at a time
+pub trait InputSource {
+
+    /// Read the next line, including its line terminator (if any). Returns
+    /// None once the input is exhausted.
+    fn read_line(&mut self) -> io::Result<Option<String>>;
+
+    /// Read the next character (e.g. for KEY). Returns None once the input is
+    /// exhausted.
+    fn read_key(&mut self) -> io::Result<Option<u8>>;
+
+    /// Whether read_key() would return without waiting (e.g. for KEY?)
+    fn key_available(&mut self) -> io::Result<bool>;
+
+}
+
+/// Output written to the process' standard output
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Default)]
+pub struct StdoutSink;
+
+#[cfg(not(target_arch = "wasm32"))]
+impl OutputSink for StdoutSink {
+    fn write(&mut self, bytes: &[u8]) -> io::Result<()> {
+        return stdout().write_all(bytes);
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        return stdout().flush();
+    }
+}
+
+/// Input read from the process' standard input
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Default)]
+pub struct StdinSource;
+
+#[cfg(not(target_arch = "wasm32"))]
+impl InputSource for StdinSource {
+    fn read_line(&mut self) -> io::Result<Option<String>> {
+        let mut line: String = String::new();
+        if stdin().lock().read_line(&mut line)? == 0 {
+            return Result::Ok(None);
+        }
+        return Result::Ok(Some(line));
+    }
+    fn read_key(&mut self) -> io::Result<Option<u8>> {
+        let mut key: [u8; 1] = [0];
+        if stdin().lock().read(&mut key)? == 0 {
+            return Result::Ok(None);
+        }
+        return Result::Ok(Some(key[0]));
+    }
+    fn key_available(&mut self) -> io::Result<bool> {
+        // There's no portable way to ask without reading, so assume the best
+        return Result::Ok(true);
+    }
+}
+
+/// Output collected in memory. Clones share the same buffer, so the host can
+/// keep one to inspect what the VM has written to the other.
+#[derive(Clone, Default)]
+pub struct BufferSink {
+    buffer: Rc<RefCell<Vec<u8>>>,
+}
+
+impl BufferSink {
+
+    /// Everything written so far, lossily converted to UTF-8
+    pub fn contents(&self) -> String {
+        return String::from_utf8_lossy(&self.buffer.borrow()).into_owned();
+    }
+
+    /// Remove and return everything written so far
+    pub fn take(&self) -> Vec<u8> {
+        return self.buffer.borrow_mut().split_off(0);
+    }
+
+    /// Discard everything written so far
+    pub fn clear(&self) {
+        self.buffer.borrow_mut().clear();
+    }
+
+}
+
+impl OutputSink for BufferSink {
+    fn write(&mut self, bytes: &[u8]) -> io::Result<()> {
+        self.buffer.borrow_mut().extend_from_slice(bytes);
+        return Result::Ok(());
+    }
+}
+
+/// Function output is handed to by a CallbackSink
+pub type OutputCallback = Box<dyn FnMut(&[u8])>;
+
+/// Output handed to a function as it's written (e.g. a JavaScript callback
+/// displaying it in a web page)
+pub struct CallbackSink {
+    callback: OutputCallback,
+}
+
+impl CallbackSink {
+    pub fn new<F: FnMut(&[u8]) + 'static>(callback: F) -> Self {
+        return CallbackSink {
+            callback: Box::new(callback),
+        };
+    }
+}
+
+impl OutputSink for CallbackSink {
+    fn write(&mut self, bytes: &[u8]) -> io::Result<()> {
+        (self.callback)(bytes);
+        return Result::Ok(());
+    }
+}
+
+/// Input read from an in-memory buffer
+#[derive(Default)]
+pub struct BufferSource {
+    buffer: VecDeque<u8>,
+}
+
+impl BufferSource {
+    pub fn new(text: &str) -> Self {
+        return BufferSource {
+            buffer: VecDeque::from(text.as_bytes().to_vec()),
+        };
+    }
+}
+
+impl InputSource for BufferSource {
+    fn read_line(&mut self) -> io::Result<Option<String>> {
+        if self.buffer.is_empty() {
+            return Result::Ok(None);
+        }
+        let length: usize = match self.buffer.iter().position(|char| *char == b'\n') {
+            Some(newline) => newline + 1,
+            None => self.buffer.len(),
+        };
+        let line: Vec<u8> = self.buffer.drain(..length).collect();
+        return Result::Ok(Some(String::from_utf8_lossy(&line).into_owned()));
+    }
+    fn read_key(&mut self) -> io::Result<Option<u8>> {
+        return Result::Ok(self.buffer.pop_front());
+    }
+    fn key_available(&mut self) -> io::Result<bool> {
+        return Result::Ok(!self.buffer.is_empty());
+    }
+}
+
+/// A step of the input given by a ScriptedSource
+#[derive(Clone, Debug, PartialEq)]
+pub enum Script {
+
+    /// Text that's available to be read
+    Text(String),
+
+    /// Nothing is available the next time KEY? asks; as if the user hasn't
+    /// typed anything yet. Reads skip over pauses.
+    Pause,
+
+}
+
+/// Input that arrives in a fixed sequence of steps, so that programs polling
+/// for keys can be tested deterministically.
+#[derive(Default)]
+pub struct ScriptedSource {
+
+    /// Text left in each step, as bytes so that keys are read a byte at a time
+    /// like any other source's, or None for a pause
+    steps: VecDeque<Option<VecDeque<u8>>>,
+
+}
+
+impl ScriptedSource {
+
+    pub fn new(steps: Vec<Script>) -> Self {
+        return ScriptedSource {
+            steps: steps.into_iter().map(|step| {
+                return match step {
+                    Script::Text(text) => Some(VecDeque::from(text.into_bytes())),
+                    Script::Pause => None,
+                };
+            }).collect(),
+        };
+    }
+
+    /// Drop pauses and exhausted text in front of the next character
+    fn skip_to_text(&mut self) {
+        while let Some(step) = self.steps.front() {
+            match step {
+                Some(text) if !text.is_empty() => break,
+                _ => self.steps.pop_front(),
+            };
+        }
+    }
+
+}
+
+impl InputSource for ScriptedSource {
+    fn read_line(&mut self) -> io::Result<Option<String>> {
+        self.skip_to_text();
+        let mut line: Vec<u8> = Vec::new();
+        while let Some(Some(text)) = self.steps.front_mut() {
+            match text.iter().position(|char| *char == b'\n') {
+                Some(newline) => {
+                    line.extend(text.drain(..newline + 1));
+                    break;
+                },
+                None => {
+                    line.extend(text.drain(..));
+                    self.steps.pop_front();
+                },
+            }
+        }
+        if line.is_empty() {
+            return Result::Ok(None);
+        }
+        return Result::Ok(Some(String::from_utf8_lossy(&line).into_owned()));
+    }
+    fn read_key(&mut self) -> io::Result<Option<u8>> {
+        self.skip_to_text();
+        if let Some(Some(text)) = self.steps.front_mut() {
+            return Result::Ok(text.pop_front());
+        }
+        return Result::Ok(None);
+    }
+    fn key_available(&mut self) -> io::Result<bool> {
+        while let Some(step) = self.steps.front() {
+            match step {
+                Some(text) if text.is_empty() => {
+                    self.steps.pop_front();
+                },
+                Some(_) => {
+                    return Result::Ok(true);
+                },
+                None => {
+                    self.steps.pop_front();
+                    return Result::Ok(false);
+                },
+            }
+        }
+        return Result::Ok(false);
+    }
+}
+
+/// Output that's discarded
+#[derive(Default)]
+pub struct NullSink;
+
+impl OutputSink for NullSink {
+    fn write(&mut self, _bytes: &[u8]) -> io::Result<()> {
+        return Result::Ok(());
+    }
+}
+
+/// Input that's always exhausted
+#[derive(Default)]
+pub struct NullSource;
+
+impl InputSource for NullSource {
+    fn read_line(&mut self) -> io::Result<Option<String>> {
+        return Result::Ok(None);
+    }
+    fn read_key(&mut self) -> io::Result<Option<u8>> {
+        return Result::Ok(None);
+    }
+    fn key_available(&mut self) -> io::Result<bool> {
+        return Result::Ok(false);
+    }
+}
+
+impl Default for Box<dyn OutputSink> {
+    fn default() -> Self {
+        return Box::new(NullSink);
+    }
+}
+
+impl Default for Box<dyn InputSource> {
+    fn default() -> Self {
+        return Box::new(NullSource);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn io_test_buffer_sink() {
+        let output: BufferSink = BufferSink::default();
+        let mut sink: Box<dyn OutputSink> = Box::new(output.clone());
+
+        assert!(sink.write(b"hello ").is_ok());
+        assert!(sink.write(b"world").is_ok());
+        assert!(sink.flush().is_ok());
+        assert_eq!(output.contents(), "hello world");
+
+        assert_eq!(output.take(), b"hello world");
+        assert_eq!(output.contents(), "");
+
+        assert!(sink.write(b"again").is_ok());
+        output.clear();
+        assert_eq!(output.contents(), "");
+    }
+
+    #[test]
+    fn io_test_callback_sink() {
+        let output: Rc<RefCell<Vec<u8>>> = Rc::default();
+        let written: Rc<RefCell<Vec<u8>>> = output.clone();
+        let mut sink: Box<dyn OutputSink> = Box::new(CallbackSink::new(move |bytes: &[u8]| {
+            written.borrow_mut().extend_from_slice(bytes);
+        }));
+
+        assert!(sink.write(b"hello ").is_ok());
+        assert!(sink.write(b"world").is_ok());
+        assert!(sink.flush().is_ok());
+        assert_eq!(*output.borrow(), b"hello world");
+    }
+
+    #[test]
+    fn io_test_buffer_source() {
+        let mut source: BufferSource = BufferSource::new("ab\ncd\nef");
+
+        assert_eq!(source.read_key().unwrap(), Some(b'a'));
+        assert_eq!(source.read_line().unwrap(), Some(String::from("b\n")));
+        assert_eq!(source.read_line().unwrap(), Some(String::from("cd\n")));
+        assert_eq!(source.read_line().unwrap(), Some(String::from("ef")));
+        assert_eq!(source.read_line().unwrap(), None);
+        assert_eq!(source.read_key().unwrap(), None);
+        assert!(!source.key_available().unwrap());
+    }
+
+    #[test]
+    fn io_test_scripted_source() {
+        let mut source: ScriptedSource = ScriptedSource::new(vec![
+            Script::Pause,
+            Script::Pause,
+            Script::Text(String::from("ab")),
+            Script::Pause,
+            Script::Text(String::from("c\nd")),
+            Script::Text(String::from("e\n")),
+        ]);
+
+        // KEY? sees each pause exactly once
+        assert!(!source.key_available().unwrap());
+        assert!(!source.key_available().unwrap());
+        assert!(source.key_available().unwrap());
+        assert_eq!(source.read_key().unwrap(), Some(b'a'));
+        assert_eq!(source.read_key().unwrap(), Some(b'b'));
+        assert!(!source.key_available().unwrap());
+        assert!(source.key_available().unwrap());
+
+        // reads skip over pauses and join text steps into lines
+        assert_eq!(source.read_line().unwrap(), Some(String::from("c\n")));
+        assert_eq!(source.read_line().unwrap(), Some(String::from("de\n")));
+        assert_eq!(source.read_line().unwrap(), None);
+        assert_eq!(source.read_key().unwrap(), None);
+        assert!(!source.key_available().unwrap());
+
+        // pauses never block reading
+        let mut source: ScriptedSource = ScriptedSource::new(vec![Script::Pause, Script::Text(String::from("x"))]);
+        assert_eq!(source.read_key().unwrap(), Some(b'x'));
+
+        // characters are read a byte at a time
+        let mut source: ScriptedSource = ScriptedSource::new(vec![Script::Text(String::from("é")), Script::Text(String::from("\u{20ac}\n"))]);
+        assert_eq!(source.read_key().unwrap(), Some(0xc3));
+        assert!(source.key_available().unwrap());
+        assert_eq!(source.read_key().unwrap(), Some(0xa9));
+        assert_eq!(source.read_key().unwrap(), Some(0xe2));
+        assert_eq!(source.read_key().unwrap(), Some(0x82));
+        assert_eq!(source.read_key().unwrap(), Some(0xac));
+        assert_eq!(source.read_line().unwrap(), Some(String::from("\n")));
+    }
+
+    #[test]
+    fn io_test_null() {
+        let mut sink: Box<dyn OutputSink> = Box::default();
+        assert!(sink.write(b"discarded").is_ok());
+
+        let mut source: Box<dyn InputSource> = Box::default();
+        assert_eq!(source.read_line().unwrap(), None);
+        assert_eq!(source.read_key().unwrap(), None);
+        assert!(!source.key_available().unwrap());
+    }
+
+}
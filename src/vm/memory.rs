@@ -0,0 +1,402 @@
+//! Data space
+//!
+//! Data space is a contiguous, byte-addressed region of VM memory. Strings,
+//! variables, and anything else a Forth program stores are kept here and are
+//! referred to from the data stack by address (and length, for strings).
+//!
+//! The lowest addresses are reserved by the VM for system variables (e.g. BASE)
+//! and transient regions (e.g. the buffers interpreted string literals are
+//! copied into). User data space begins at DATA_SPACE_ADDR and grows upward via
+//! ALLOT.
+
+use std::ops::Range;
+
+use super::VirtualMachineError;
+
+/// Size of a cell in address units (bytes)
+pub const CELL_SIZE: usize = 8;
+
+/// Size of a floating-point number in address units. Floats are IEEE doubles,
+/// so this is also the size of a "dfloat".
+pub const FLOAT_SIZE: usize = 8;
+
+/// Size of a single-precision floating-point number ("sfloat") in address units
+pub const SFLOAT_SIZE: usize = 4;
+
+/// Size of each transient region holding an interpreted string literal
+pub const STRING_BUFFER_SIZE: usize = 256;
+
+/// Number of transient string regions. Forth-2012 requires at least two, so
+/// that e.g. `S" old" S" new"` can both be used before the first is clobbered.
+pub const STRING_BUFFER_COUNT: usize = 2;
+
+/// Size of the region pictured numeric output is built in. It must hold at
+/// least the 2 * 64 + 2 characters needed to display a double cell in binary.
+pub const HOLD_BUFFER_SIZE: usize = 256;
+
+/// Address of the cell holding the current number-conversion radix ("BASE").
+/// Address zero is never used.
+pub const BASE_ADDR: usize = CELL_SIZE;
+
+/// Address of the cell holding the number of significant digits floats are
+/// displayed with (e.g. by F.), as set by SET-PRECISION
+pub const PRECISION_ADDR: usize = BASE_ADDR + CELL_SIZE;
+
+/// Significant digits floats are displayed with until SET-PRECISION is used
+pub const DEFAULT_PRECISION: i64 = 15;
+
+/// Most significant digits a float can be displayed with. Beyond this many, a
+/// double's digits are meaningless.
+pub const MAX_PRECISION: i64 = 17;
+
+/// Address of the first transient string region
+pub const STRING_BUFFER_ADDR: usize = PRECISION_ADDR + CELL_SIZE;
+
+/// Address of the region pictured numeric output is built in
+pub const HOLD_BUFFER_ADDR: usize = STRING_BUFFER_ADDR + STRING_BUFFER_SIZE * STRING_BUFFER_COUNT;
+
+/// Address of the first byte of user data space ("HERE" of a new VM)
+pub const DATA_SPACE_ADDR: usize = HOLD_BUFFER_ADDR + HOLD_BUFFER_SIZE;
+
+/// Most bytes data space may grow to, reserved regions included, whatever the
+/// VM's limits. Allotting past it is an error rather than exhausting the host's
+/// memory.
+pub const MAX_DATA_SPACE_SIZE: usize = 1 << 30;
+
+#[derive(Debug)]
+pub struct DataSpace {
+
+    /// Every byte of data space, reserved regions included. The length of
+    /// this vector is the data-space pointer ("HERE").
+    bytes: Vec<u8>,
+
+    /// Which transient string region is handed out next
+    next_string_buffer: usize,
+
+    /// Address of the first character of the pictured numeric output string.
+    /// The string is built from the end of the hold buffer toward its start.
+    hold: usize,
+
+}
+
+impl Default for DataSpace {
+    fn default() -> Self {
+        let mut memory: DataSpace = DataSpace {
+            bytes: vec![0; DATA_SPACE_ADDR],
+            next_string_buffer: 0,
+            hold: HOLD_BUFFER_ADDR + HOLD_BUFFER_SIZE,
+        };
+        memory.bytes[BASE_ADDR..BASE_ADDR + CELL_SIZE].copy_from_slice(&10_i64.to_le_bytes());
+        memory.bytes[PRECISION_ADDR..PRECISION_ADDR + CELL_SIZE].copy_from_slice(&DEFAULT_PRECISION.to_le_bytes());
+        return memory;
+    }
+}
+
+impl DataSpace {
+
+    /// The data-space pointer: address of the next free byte of data space.
+    pub fn here(&self) -> usize {
+        return self.bytes.len();
+    }
+
+    /// Reserve `n` bytes of data space, or release them if `n` is negative.
+    /// Reserved bytes are zeroed. Releasing past the start of user data space
+    /// is an error.
+    pub fn allot(&mut self, n: i64) -> Result<(), VirtualMachineError> {
+        let here: i64 = self.here() as i64;
+        let new_here: i64 = here.checked_add(n).ok_or_else(invalid_address)?;
+        if new_here < DATA_SPACE_ADDR as i64 {
+            return Result::Err(
+                VirtualMachineError {
+                    msg: String::from("data space underflow"),
+                    ..VirtualMachineError::default()
+                }
+            );
+        }
+        let new_here: usize = new_here as usize;
+        if new_here > MAX_DATA_SPACE_SIZE || self.bytes.try_reserve(new_here.saturating_sub(self.bytes.len())).is_err() {
+            return Result::Err(
+                VirtualMachineError {
+                    msg: String::from("data space overflow"),
+                    ..VirtualMachineError::default()
+                }
+            );
+        }
+        self.bytes.resize(new_here, 0);
+        return Result::Ok(());
+    }
+
+    /// Translate an address and length from the data stack into a range of
+    /// valid data-space indices.
+    fn range(&self, addr: i64, len: i64) -> Result<Range<usize>, VirtualMachineError> {
+        if addr < 0 || len < 0 {
+            return Result::Err(invalid_address());
+        }
+        let end: i64 = addr.checked_add(len).ok_or_else(invalid_address)?;
+        if end as usize > self.bytes.len() {
+            return Result::Err(invalid_address());
+        }
+        return Result::Ok(addr as usize..end as usize);
+    }
+
+    /// Borrow `len` bytes of data space starting at `addr`.
+    pub fn slice(&self, addr: i64, len: i64) -> Result<&[u8], VirtualMachineError> {
+        let range: Range<usize> = self.range(addr, len)?;
+        return Result::Ok(&self.bytes[range]);
+    }
+
+    /// Copy `bytes` into data space starting at `addr`.
+    pub fn write(&mut self, addr: i64, bytes: &[u8]) -> Result<(), VirtualMachineError> {
+        let range: Range<usize> = self.range(addr, bytes.len() as i64)?;
+        self.bytes[range].copy_from_slice(bytes);
+        return Result::Ok(());
+    }
+
+    /// Set `len` bytes of data space starting at `addr` to `byte` (e.g. as done
+    /// by BLANK)
+    pub fn fill(&mut self, addr: i64, len: i64, byte: u8) -> Result<(), VirtualMachineError> {
+        let range: Range<usize> = self.range(addr, len)?;
+        self.bytes[range].fill(byte);
+        return Result::Ok(());
+    }
+
+    /// ( c-addr -- char ) as done by C@
+    pub fn fetch_byte(&self, addr: i64) -> Result<u8, VirtualMachineError> {
+        return Result::Ok(self.slice(addr, 1)?[0]);
+    }
+
+    /// ( char c-addr -- ) as done by C!
+    pub fn store_byte(&mut self, addr: i64, byte: u8) -> Result<(), VirtualMachineError> {
+        return self.write(addr, &[byte]);
+    }
+
+    /// ( a-addr -- x ) as done by @
+    pub fn fetch_cell(&self, addr: i64) -> Result<i64, VirtualMachineError> {
+        let mut cell: [u8; CELL_SIZE] = [0; CELL_SIZE];
+        cell.copy_from_slice(self.slice(addr, CELL_SIZE as i64)?);
+        return Result::Ok(i64::from_le_bytes(cell));
+    }
+
+    /// ( x a-addr -- ) as done by !
+    pub fn store_cell(&mut self, addr: i64, x: i64) -> Result<(), VirtualMachineError> {
+        return self.write(addr, &x.to_le_bytes());
+    }
+
+    /// ( f-addr -- ) ( F: -- r ) as done by F@
+    pub fn fetch_float(&self, addr: i64) -> Result<f64, VirtualMachineError> {
+        let mut float: [u8; FLOAT_SIZE] = [0; FLOAT_SIZE];
+        float.copy_from_slice(self.slice(addr, FLOAT_SIZE as i64)?);
+        return Result::Ok(f64::from_le_bytes(float));
+    }
+
+    /// ( f-addr -- ) ( F: r -- ) as done by F!
+    pub fn store_float(&mut self, addr: i64, r: f64) -> Result<(), VirtualMachineError> {
+        return self.write(addr, &r.to_le_bytes());
+    }
+
+    /// ( sf-addr -- ) ( F: -- r ) as done by SF@
+    pub fn fetch_sfloat(&self, addr: i64) -> Result<f64, VirtualMachineError> {
+        let mut float: [u8; SFLOAT_SIZE] = [0; SFLOAT_SIZE];
+        float.copy_from_slice(self.slice(addr, SFLOAT_SIZE as i64)?);
+        return Result::Ok(f32::from_le_bytes(float) as f64);
+    }
+
+    /// ( sf-addr -- ) ( F: r -- ) as done by SF!, rounding r to single precision
+    pub fn store_sfloat(&mut self, addr: i64, r: f64) -> Result<(), VirtualMachineError> {
+        return self.write(addr, &(r as f32).to_le_bytes());
+    }
+
+    /// Copy `bytes` into the next transient string region and return its
+    /// address. The contents remain valid until the region is reused, which
+    /// happens every STRING_BUFFER_COUNT calls.
+    pub fn transient(&mut self, bytes: &[u8]) -> Result<usize, VirtualMachineError> {
+        if bytes.len() > STRING_BUFFER_SIZE {
+            return Result::Err(
+                VirtualMachineError {
+                    msg: format!("string exceeds {} characters", STRING_BUFFER_SIZE),
+                    ..VirtualMachineError::default()
+                }
+            );
+        }
+        let addr: usize = STRING_BUFFER_ADDR + self.next_string_buffer * STRING_BUFFER_SIZE;
+        self.next_string_buffer = (self.next_string_buffer + 1) % STRING_BUFFER_COUNT;
+        self.bytes[addr..addr + bytes.len()].copy_from_slice(bytes);
+        return Result::Ok(addr);
+    }
+
+    /// The current number-conversion radix, as set via BASE. Radixes outside of
+    /// 2 through 36 can't be represented with digits 0-9 and A-Z.
+    pub fn base(&self) -> Result<u32, VirtualMachineError> {
+        let base: i64 = self.fetch_cell(BASE_ADDR as i64)?;
+        if !(2..=36).contains(&base) {
+            return Result::Err(
+                VirtualMachineError {
+                    msg: format!("invalid BASE: {}", base),
+                    ..VirtualMachineError::default()
+                }
+            );
+        }
+        return Result::Ok(base as u32);
+    }
+
+    /// Number of significant digits floats are displayed with, as set via
+    /// SET-PRECISION
+    pub fn precision(&self) -> Result<usize, VirtualMachineError> {
+        let precision: i64 = self.fetch_cell(PRECISION_ADDR as i64)?;
+        if !(1..=MAX_PRECISION).contains(&precision) {
+            return Result::Err(
+                VirtualMachineError {
+                    msg: format!("invalid PRECISION: {}", precision),
+                    ..VirtualMachineError::default()
+                }
+            );
+        }
+        return Result::Ok(precision as usize);
+    }
+
+    /// Begin a new pictured numeric output string, as done by <#
+    pub fn hold_start(&mut self) {
+        self.hold = HOLD_BUFFER_ADDR + HOLD_BUFFER_SIZE;
+    }
+
+    /// Prepend `bytes` to the pictured numeric output string, as done by HOLDS
+    pub fn hold(&mut self, bytes: &[u8]) -> Result<(), VirtualMachineError> {
+        if self.hold - HOLD_BUFFER_ADDR < bytes.len() {
+            return Result::Err(
+                VirtualMachineError {
+                    msg: String::from("pictured numeric output string overflow"),
+                    ..VirtualMachineError::default()
+                }
+            );
+        }
+        self.hold -= bytes.len();
+        self.bytes[self.hold..self.hold + bytes.len()].copy_from_slice(bytes);
+        return Result::Ok(());
+    }
+
+    /// Address and length of the pictured numeric output string, as given by #>
+    pub fn held(&self) -> (usize, usize) {
+        return (self.hold, HOLD_BUFFER_ADDR + HOLD_BUFFER_SIZE - self.hold);
+    }
+
+}
+
+/// Common error for accesses outside of data space
+fn invalid_address() -> VirtualMachineError {
+    return VirtualMachineError {
+        msg: String::from("invalid memory address"),
+        ..VirtualMachineError::default()
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn memory_test_allot() {
+        let mut memory: DataSpace = DataSpace::default();
+        assert_eq!(memory.here(), DATA_SPACE_ADDR);
+
+        assert!(memory.allot(16).is_ok());
+        assert_eq!(memory.here(), DATA_SPACE_ADDR + 16);
+
+        assert!(memory.allot(-8).is_ok());
+        assert_eq!(memory.here(), DATA_SPACE_ADDR + 8);
+
+        // can't release the reserved regions
+        assert!(memory.allot(-16).is_err());
+        assert_eq!(memory.here(), DATA_SPACE_ADDR + 8);
+
+        // nor grow past the maximum size
+        assert!(memory.allot(MAX_DATA_SPACE_SIZE as i64).is_err());
+        assert!(memory.allot(i64::MAX).is_err());
+        assert_eq!(memory.here(), DATA_SPACE_ADDR + 8);
+    }
+
+    #[test]
+    fn memory_test_cells_and_bytes() {
+        let mut memory: DataSpace = DataSpace::default();
+        let addr: i64 = memory.here() as i64;
+        assert!(memory.allot(CELL_SIZE as i64).is_ok());
+
+        assert!(memory.store_cell(addr, -2).is_ok());
+        assert_eq!(memory.fetch_cell(addr).unwrap(), -2);
+        assert_eq!(memory.fetch_byte(addr).unwrap(), 0xFE);
+
+        assert!(memory.store_byte(addr, 1).is_ok());
+        assert_eq!(memory.fetch_cell(addr).unwrap(), -255);
+
+        assert!(memory.fill(addr, 2, 0).is_ok());
+        assert_eq!(memory.fetch_cell(addr).unwrap(), -65536);
+        assert!(memory.fill(addr, i64::MAX, 0).is_err());
+
+        // floats, in either precision
+        assert!(memory.store_float(addr, -1.5).is_ok());
+        assert_eq!(memory.fetch_float(addr).unwrap(), -1.5);
+        assert!(memory.store_sfloat(addr, 0.1).is_ok());
+        assert_eq!(memory.fetch_sfloat(addr).unwrap(), 0.1_f32 as f64);
+        assert_eq!(memory.fetch_float(addr).unwrap().to_bits() >> 32, (-1.5_f64).to_bits() >> 32);
+
+        // case:  out of bounds
+        assert!(memory.fetch_cell(addr + 1).is_err());
+        assert!(memory.fetch_float(addr + 1).is_err());
+        assert!(memory.store_sfloat(addr + 5, 0.0).is_err());
+        assert!(memory.fetch_byte(addr + CELL_SIZE as i64).is_err());
+        assert!(memory.fetch_byte(-1).is_err());
+        assert!(memory.store_cell(i64::MAX, 0).is_err());
+        assert!(memory.slice(addr, -1).is_err());
+    }
+
+    #[test]
+    fn memory_test_transient_regions() {
+        let mut memory: DataSpace = DataSpace::default();
+
+        let first: usize = memory.transient(b"first").unwrap();
+        let second: usize = memory.transient(b"second").unwrap();
+        assert_ne!(first, second);
+        assert_eq!(memory.slice(first as i64, 5).unwrap(), b"first");
+        assert_eq!(memory.slice(second as i64, 6).unwrap(), b"second");
+
+        // regions are reused in turn
+        let third: usize = memory.transient(b"third").unwrap();
+        assert_eq!(first, third);
+        assert_eq!(memory.slice(second as i64, 6).unwrap(), b"second");
+
+        // strings must fit
+        assert!(memory.transient(&[0; STRING_BUFFER_SIZE + 1]).is_err());
+    }
+
+    #[test]
+    fn memory_test_hold_buffer() {
+        let mut memory: DataSpace = DataSpace::default();
+        assert_eq!(memory.base().unwrap(), 10);
+
+        memory.hold_start();
+        assert_eq!(memory.held().1, 0);
+        assert!(memory.hold(b"3").is_ok());
+        assert!(memory.hold(b"12").is_ok());
+        let (addr, length): (usize, usize) = memory.held();
+        assert_eq!(memory.slice(addr as i64, length as i64).unwrap(), b"123");
+
+        // case:  the hold buffer can't overflow into user data space
+        assert!(memory.hold(&[b'0'; HOLD_BUFFER_SIZE]).is_err());
+        memory.hold_start();
+        assert!(memory.hold(&[b'0'; HOLD_BUFFER_SIZE]).is_ok());
+        assert!(memory.hold(b"0").is_err());
+
+        // case:  PRECISION must be usable for display
+        assert_eq!(memory.precision().unwrap(), DEFAULT_PRECISION as usize);
+        assert!(memory.store_cell(PRECISION_ADDR as i64, MAX_PRECISION + 1).is_ok());
+        assert!(memory.precision().is_err());
+        assert!(memory.store_cell(PRECISION_ADDR as i64, 0).is_ok());
+        assert!(memory.precision().is_err());
+
+        // case:  BASE must be usable for conversion
+        assert!(memory.store_cell(BASE_ADDR as i64, 37).is_ok());
+        assert!(memory.base().is_err());
+        assert!(memory.store_cell(BASE_ADDR as i64, 1).is_ok());
+        assert!(memory.base().is_err());
+    }
+
+}
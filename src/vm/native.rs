@@ -0,0 +1,82 @@
+//! Native words
+//!
+//! A host embedding the VM can extend Forth with words of its own, written in
+//! Rust, without adding operations to the VM. Each native word is a function
+//! of the VM, registered under a name along with its stack effect: how many
+//! cells it takes off the data stack, and how many it leaves in their place.
+//! The stack effect is checked each time the word is executed: the word may
+//! pop only its inputs off the data stack (via VM::pop()), and must leave
+//! exactly its outputs there. So a native word can rely on its inputs being
+//! there, and a mistake in one is reported as an error rather than corrupting
+//! the program's stack. The floating-point stack isn't part of the stack
+//! effect, and isn't checked: a word may use it as it likes, and is trusted to
+//! leave it as it should.
+//!
+//! Functions are closures, so they may capture whatever state the host likes.
+//! State that a word changes must be shared (e.g. via Rc<Cell<_>>), since a
+//! word may be executed again while it's being executed (e.g. if it evaluates
+//! Forth source that executes it).
+
+use std::rc::Rc;
+
+use super::{Data, VM, VirtualMachineError};
+
+/// Function run when a native word is executed
+pub type NativeFunction = Rc<dyn Fn(&mut VM) -> Result<(), VirtualMachineError>>;
+
+/// A word written in Rust by the host
+#[derive(Clone)]
+pub struct Native {
+
+    /// Name the word was registered under
+    pub name: String,
+
+    /// Number of cells the word takes off the data stack
+    pub inputs: usize,
+
+    /// Number of cells the word leaves on the data stack in place of its inputs
+    pub outputs: usize,
+
+    /// Function run when the word is executed
+    pub function: NativeFunction,
+
+}
+
+impl Native {
+
+    /// Execute the word, checking that it keeps to its stack effect
+    pub fn call(&self, vm: &mut VM) -> Result<(), VirtualMachineError> {
+        let depth: usize = vm.data_stack.len();
+        if depth < self.inputs {
+            return Result::Err(
+                VirtualMachineError {
+                    msg: String::from("stack underflow"),
+                    ..VirtualMachineError::default()
+                }
+            );
+        }
+        let base: usize = depth - self.inputs;
+        let inputs: Vec<Data> = vm.data_stack[base..].to_vec();
+        let outer_base: usize = std::mem::replace(&mut vm.stack_base, base);
+        let result: Result<(), VirtualMachineError> = (self.function)(vm).and_then(|_| {
+            if vm.data_stack.len().checked_sub(base) != Some(self.outputs) {
+                return Result::Err(
+                    VirtualMachineError {
+                        msg: format!("{} didn't keep to its stack effect ( {} -- {} )", self.name, self.inputs, self.outputs),
+                        ..VirtualMachineError::default()
+                    }
+                );
+            }
+            return Result::Ok(());
+        });
+        vm.stack_base = outer_base;
+
+        // the stack is left as it was if the word failed
+        if result.is_err() {
+            vm.data_stack.truncate(base);
+            vm.data_stack.extend(inputs);
+        }
+        return result;
+    }
+
+}
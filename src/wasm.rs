@@ -0,0 +1,332 @@
+//! WebAssembly
+//!
+//! For running OxForth in a web page, the library is built for WebAssembly:
+//!
+//! ```text
+//! cargo build --lib --release --target wasm32-unknown-unknown
+//! ```
+//!
+//! Playground is what a page interacts with: Forth source goes in, and the
+//! stacks and output come out. On wasm32 each of its methods is exported from
+//! the module as a function of a Playground handle, in the style of
+//! wasm-bindgen (e.g. playground_eval()), and web/oxforth.js wraps them in a
+//! JavaScript class. There's no stdin, stdout or file system to speak of in a
+//! browser, so output goes to a callback, and the program may not open files.
+//! Nor can a page interrupt a program that never finishes, so each eval() is
+//! given a budget of operations, beyond which the program is abandoned.
+
+use super::ffi::{OXFORTH_ERROR, OXFORTH_HALTED, OXFORTH_OK};
+use super::forth::Forth;
+use super::vm::files::Policy;
+use super::vm::io::CallbackSink;
+use super::vm::{Data, Outcome, VirtualMachineError};
+
+/// Operations a single call to Playground::eval() may apply by default, which
+/// takes about a second
+pub const EVAL_BUDGET: u64 = 10_000_000;
+
+/// A Forth system for interactive use by a web page
+pub struct Playground {
+
+    /// System the source is interpreted by
+    pub forth: Forth,
+
+    /// Most operations a single call to eval() may apply, so that an endless
+    /// loop can't freeze the page
+    pub budget: u64,
+
+    /// Why the last call to eval() failed, if it did
+    error: String,
+
+    /// Exit code the program gave when it last halted
+    code: i32,
+
+}
+
+impl Playground {
+
+    /// Create a Forth system, passing its output to the given function
+    pub fn new<F: FnMut(&[u8]) + 'static>(output: F) -> Playground {
+        let mut forth: Forth = Forth::new();
+        forth.vm.output = Box::new(CallbackSink::new(output));
+        forth.vm.files.policy = Policy::sandboxed();
+        return Playground {
+            forth,
+            budget: EVAL_BUDGET,
+            error: String::new(),
+            code: 0,
+        };
+    }
+
+    /// Interpret Forth source. Returns OXFORTH_OK, OXFORTH_HALTED if the program
+    /// halted (see code()), or OXFORTH_ERROR if it failed (see error()),
+    /// including if it ran through its budget of operations.
+    pub fn eval(&mut self, source: &str) -> i32 {
+        self.error.clear();
+        let ops_applied: u64 = self.forth.vm._ops_applied;
+        self.forth.vm.limits.operations = Some(ops_applied.saturating_add(self.budget));
+        let result: Result<Outcome, VirtualMachineError> = self.forth.eval(source);
+        self.forth.vm.limits.operations = None;
+        match result {
+            Result::Ok(Outcome::HALTED(code)) => {
+                self.code = code.unwrap_or(0);
+                return OXFORTH_HALTED;
+            },
+            Result::Ok(_) => {
+                return OXFORTH_OK;
+            },
+            Result::Err(err) => {
+                // The page has no way to resume a program that ran out of
+                // operations, so it's abandoned like any other
+                if err.limit.is_some() {
+                    self.forth.vm.reset();
+                }
+                self.error = err.msg;
+                return OXFORTH_ERROR;
+            },
+        }
+    }
+
+    /// Why the last call to eval() failed, or "" if it didn't
+    pub fn error(&self) -> &str {
+        return &self.error;
+    }
+
+    /// Exit code the program gave when it last halted (0 if none was given)
+    pub fn code(&self) -> i32 {
+        return self.code;
+    }
+
+    /// Numbers on the data stack, from bottom to top
+    pub fn stack(&self) -> Vec<i64> {
+        return self.forth.vm.data_stack.iter().map(|Data::NUMBER(n)| *n).collect();
+    }
+
+    /// Floats on the floating-point stack, from bottom to top
+    pub fn floats(&self) -> Vec<f64> {
+        return self.forth.vm.float_stack.clone();
+    }
+
+    /// Whether a definition is being compiled, i.e. more source is expected
+    /// before it can be executed
+    pub fn compiling(&self) -> bool {
+        return self.forth.vm.compiling.is_some();
+    }
+
+}
+
+/// Functions exported from the WebAssembly module. Strings are passed as a
+/// pointer and length into the module's memory, which the host allocates via
+/// oxforth_alloc(). Stacks are passed as a pointer to a snapshot of them, whose
+/// length is given by the call that took it.
+#[cfg(target_arch = "wasm32")]
+mod exports {
+
+    use std::alloc::{alloc, dealloc, Layout};
+    use std::slice;
+    use std::str;
+
+    use super::*;
+
+    #[link(wasm_import_module = "oxforth")]
+    extern "C" {
+        /// Display output of the playground created with the given sink
+        fn output(sink: u32, bytes: *const u8, length: usize);
+    }
+
+    /// A playground along with the last snapshots taken of its stacks
+    pub struct Handle {
+        playground: Playground,
+        stack: Vec<i64>,
+        floats: Vec<f64>,
+    }
+
+    /// Allocate `length` bytes for the host (e.g. to pass source to eval)
+    #[no_mangle]
+    pub extern "C" fn oxforth_alloc(length: usize) -> *mut u8 {
+        return unsafe { alloc(Layout::from_size_align(length.max(1), 1).unwrap()) };
+    }
+
+    /// Free bytes allocated by oxforth_alloc()
+    ///
+    /// # Safety
+    ///
+    /// `bytes` must have been allocated by oxforth_alloc() with `length`.
+    #[no_mangle]
+    pub unsafe extern "C" fn oxforth_dealloc(bytes: *mut u8, length: usize) {
+        dealloc(bytes, Layout::from_size_align(length.max(1), 1).unwrap());
+    }
+
+    /// Create a playground, whose output is displayed via the imported output
+    /// function along with `sink`
+    #[no_mangle]
+    pub extern "C" fn playground_new(sink: u32) -> *mut Handle {
+        let playground: Playground = Playground::new(move |bytes: &[u8]| {
+            unsafe { output(sink, bytes.as_ptr(), bytes.len()) };
+        });
+        return Box::into_raw(Box::new(Handle { playground, stack: Vec::new(), floats: Vec::new() }));
+    }
+
+    /// # Safety
+    ///
+    /// `handle` must have been created by playground_new() and not yet freed.
+    #[no_mangle]
+    pub unsafe extern "C" fn playground_free(handle: *mut Handle) {
+        drop(Box::from_raw(handle));
+    }
+
+    /// # Safety
+    ///
+    /// `handle` must be live, and `source` must point to `length` bytes.
+    #[no_mangle]
+    pub unsafe extern "C" fn playground_eval(handle: *mut Handle, source: *const u8, length: usize) -> i32 {
+        let handle: &mut Handle = &mut *handle;
+        return match str::from_utf8(slice::from_raw_parts(source, length)) {
+            Result::Ok(source) => handle.playground.eval(source),
+            Result::Err(_) => {
+                handle.playground.error = String::from("source isn't valid UTF-8");
+                OXFORTH_ERROR
+            },
+        };
+    }
+
+    /// # Safety
+    ///
+    /// `handle` must be live.
+    #[no_mangle]
+    pub unsafe extern "C" fn playground_error(handle: *const Handle) -> *const u8 {
+        return (*handle).playground.error().as_ptr();
+    }
+
+    /// # Safety
+    ///
+    /// `handle` must be live.
+    #[no_mangle]
+    pub unsafe extern "C" fn playground_error_length(handle: *const Handle) -> usize {
+        return (*handle).playground.error().len();
+    }
+
+    /// # Safety
+    ///
+    /// `handle` must be live.
+    #[no_mangle]
+    pub unsafe extern "C" fn playground_code(handle: *const Handle) -> i32 {
+        return (*handle).playground.code();
+    }
+
+    /// # Safety
+    ///
+    /// `handle` must be live.
+    #[no_mangle]
+    pub unsafe extern "C" fn playground_compiling(handle: *const Handle) -> bool {
+        return (*handle).playground.compiling();
+    }
+
+    /// Take a snapshot of the data stack, returning its depth. The snapshot is
+    /// found via playground_stack_snapshot().
+    ///
+    /// # Safety
+    ///
+    /// `handle` must be live.
+    #[no_mangle]
+    pub unsafe extern "C" fn playground_stack(handle: *mut Handle) -> usize {
+        let handle: &mut Handle = &mut *handle;
+        handle.stack = handle.playground.stack();
+        return handle.stack.len();
+    }
+
+    /// # Safety
+    ///
+    /// `handle` must be live.
+    #[no_mangle]
+    pub unsafe extern "C" fn playground_stack_snapshot(handle: *const Handle) -> *const i64 {
+        return (*handle).stack.as_ptr();
+    }
+
+    /// Take a snapshot of the floating-point stack, returning its depth. The
+    /// snapshot is found via playground_floats_snapshot().
+    ///
+    /// # Safety
+    ///
+    /// `handle` must be live.
+    #[no_mangle]
+    pub unsafe extern "C" fn playground_floats(handle: *mut Handle) -> usize {
+        let handle: &mut Handle = &mut *handle;
+        handle.floats = handle.playground.floats();
+        return handle.floats.len();
+    }
+
+    /// # Safety
+    ///
+    /// `handle` must be live.
+    #[no_mangle]
+    pub unsafe extern "C" fn playground_floats_snapshot(handle: *const Handle) -> *const f64 {
+        return (*handle).floats.as_ptr();
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use super::*;
+
+    fn playground() -> (Playground, Rc<RefCell<String>>) {
+        let output: Rc<RefCell<String>> = Rc::default();
+        let written: Rc<RefCell<String>> = output.clone();
+        let playground: Playground = Playground::new(move |bytes: &[u8]| {
+            written.borrow_mut().push_str(&String::from_utf8_lossy(bytes));
+        });
+        return (playground, output);
+    }
+
+    #[test]
+    fn wasm_test_eval() {
+        let (mut playground, output) = playground();
+        assert_eq!(playground.eval(": GREET .\" hello\" ;\nGREET 1 2"), OXFORTH_OK);
+        assert_eq!(*output.borrow(), "hello");
+        assert_eq!(playground.stack(), vec![1, 2]);
+        assert_eq!(playground.error(), "");
+
+        // definitions may be entered a line at a time
+        assert_eq!(playground.eval(": THREE"), OXFORTH_OK);
+        assert!(playground.compiling());
+        assert_eq!(playground.eval("3 ;"), OXFORTH_OK);
+        assert!(!playground.compiling());
+
+        // case:  errors
+        assert_eq!(playground.eval("DROP DROP DROP"), OXFORTH_ERROR);
+        assert_eq!(playground.error(), "stack underflow");
+        assert!(playground.stack().is_empty());
+        assert_eq!(playground.eval("THREE 2 (BYE)"), OXFORTH_HALTED);
+        assert_eq!(playground.code(), 2);
+        assert_eq!(playground.stack(), vec![3]);
+        assert_eq!(playground.error(), "");
+
+        // case:  endless loops run out of operations
+        playground.budget = 1000;
+        assert_eq!(playground.eval(": SPIN RECURSE ;\nSPIN"), OXFORTH_ERROR);
+        assert_eq!(playground.error(), "operation limit exceeded");
+        assert_eq!(playground.eval("1 2"), OXFORTH_OK);
+        assert_eq!(playground.stack(), vec![3, 1, 2]);
+    }
+
+    #[test]
+    fn wasm_test_stacks() {
+        let (mut playground, _) = playground();
+        assert_eq!(playground.eval("1 NEGATE 2E 1E F/"), OXFORTH_OK);
+        assert_eq!(playground.stack(), vec![-1]);
+        assert_eq!(playground.floats(), vec![2.0]);
+    }
+
+    #[test]
+    fn wasm_test_files() {
+        let (mut playground, _) = playground();
+        assert_eq!(playground.eval("S\" Cargo.toml\" R/O OPEN-FILE"), OXFORTH_OK);
+        assert_ne!(playground.stack()[1], 0);
+    }
+
+}
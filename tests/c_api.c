@@ -0,0 +1,84 @@
+/*  OxForth - C API test program
+ *
+ *  Built and run by tests/c_api.rs. Exits with 0 if every check passes, or
+ *  prints the failed check and exits with 1.
+ */
+
+#include <stdio.h>
+#include <string.h>
+
+#include "oxforth.h"
+
+#define CHECK(condition) do { \
+    if (!(condition)) { \
+        fprintf(stderr, "%s:%d: check failed: %s\n", __FILE__, __LINE__, #condition); \
+        return 1; \
+    } \
+} while (0)
+
+static int eval(oxforth *forth, const char *source, int *code) {
+    return oxforth_eval(forth, source, strlen(source), code);
+}
+
+/* ( n -- n' ) adds the total kept by the host to n, then keeps the result */
+static int accumulate(oxforth *forth, void *context) {
+    int64_t *total = context;
+    int64_t n;
+    if (oxforth_pop(forth, &n) != OXFORTH_OK) {
+        return 2;
+    }
+    *total += n;
+    oxforth_push(forth, *total);
+    return OXFORTH_OK;
+}
+
+/* ( -- ) always fails */
+static int broken(oxforth *forth, void *context) {
+    (void) forth;
+    (void) context;
+    return 5;
+}
+
+int main(void) {
+    oxforth *forth = oxforth_create();
+    int64_t n = 0;
+    int code = -1;
+    CHECK(forth != NULL);
+
+    /* interpreting source, and passing cells back and forth */
+    CHECK(eval(forth, ": SQUARE DUP * ;\n3 SQUARE", &code) == OXFORTH_OK);
+    CHECK(oxforth_depth(forth) == 1);
+    CHECK(oxforth_pop(forth, &n) == OXFORTH_OK);
+    CHECK(n == 9);
+    oxforth_push(forth, 12);
+    CHECK(eval(forth, "SQUARE", NULL) == OXFORTH_OK);
+    CHECK(oxforth_pop(forth, &n) == OXFORTH_OK);
+    CHECK(n == 144);
+
+    /* native words */
+    int64_t total = 100;
+    CHECK(oxforth_define_native(forth, "ACCUMULATE", 1, 1, accumulate, &total) == OXFORTH_OK);
+    CHECK(oxforth_define_native(forth, "BROKEN", 0, 0, broken, NULL) == OXFORTH_OK);
+    CHECK(eval(forth, ": TWICE ACCUMULATE DROP 1 ACCUMULATE ; 20 TWICE", NULL) == OXFORTH_OK);
+    CHECK(oxforth_pop(forth, &n) == OXFORTH_OK);
+    CHECK(n == 121);
+    CHECK(total == 121);
+
+    /* errors */
+    CHECK(oxforth_last_error() == NULL);
+    CHECK(oxforth_pop(forth, &n) == OXFORTH_ERROR);
+    CHECK(strcmp(oxforth_last_error(), "stack underflow") == 0);
+    CHECK(eval(forth, "ACCUMULATE", NULL) == OXFORTH_ERROR);
+    CHECK(strcmp(oxforth_last_error(), "stack underflow") == 0);
+    CHECK(eval(forth, "BROKEN", NULL) == OXFORTH_ERROR);
+    CHECK(strcmp(oxforth_last_error(), "BROKEN failed: 5") == 0);
+    CHECK(oxforth_depth(forth) == 0);
+
+    /* halting */
+    CHECK(eval(forth, "42 (BYE)", &code) == OXFORTH_HALTED);
+    CHECK(code == 42);
+
+    oxforth_destroy(forth);
+    oxforth_destroy(NULL);
+    return 0;
+}
@@ -0,0 +1,55 @@
+//! OxForth - C API tests
+//!
+//! Builds the C test program (tests/c_api.c) against include/oxforth.h and the
+//! C dynamic library cargo builds alongside the tests, then runs it. The
+//! header must be the one generated from src/ffi.rs.
+
+// Returns are always explicit in this codebase
+#![allow(clippy::needless_return)]
+
+use std::env;
+use std::fs::read_to_string;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+/// Directory the library was built into for the tests (e.g. target/debug/deps),
+/// which is the one holding this test's executable
+fn library_directory() -> PathBuf {
+    let exe: PathBuf = env::current_exe().unwrap();
+    return exe.parent().unwrap().to_path_buf();
+}
+
+#[test]
+#[cfg(unix)]
+fn c_api_test_program() {
+    let root: &Path = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let library: PathBuf = library_directory();
+    let program: PathBuf = Path::new(env!("CARGO_TARGET_TMPDIR")).join("c_api");
+    let compiler: String = env::var("CC").unwrap_or_else(|_| String::from("cc"));
+    let output: Output = Command::new(compiler)
+        .arg("-std=c99")
+        .arg("-Wall")
+        .arg("-Werror")
+        .arg("-I").arg(root.join("include"))
+        .arg(root.join("tests").join("c_api.c"))
+        .arg("-o").arg(&program)
+        .arg("-L").arg(&library)
+        .arg(format!("-Wl,-rpath,{}", library.display()))
+        .arg("-loxforth")
+        .output()
+        .expect("couldn't run the C compiler");
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    let output: Output = Command::new(&program).output().unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+}
+
+#[test]
+fn c_api_test_header() {
+    let root: &Path = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let header: String = read_to_string(root.join("include").join("oxforth.h")).unwrap();
+    assert!(
+        header == oxforth::ffi::header(),
+        "include/oxforth.h is out of date; regenerate it with: cargo run --example c_header > include/oxforth.h",
+    );
+}
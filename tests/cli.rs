@@ -0,0 +1,82 @@
+//! OxForth CLI tests
+//!
+//! Runs the oxforth binary with standard input piped in, as a script.
+
+// Returns are always explicit in this codebase
+#![allow(clippy::needless_return)]
+
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+/// Run oxforth with the given arguments, piping `script` to its standard input
+fn run(arguments: &[&str], script: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_oxforth"))
+        .args(arguments)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(script.as_bytes()).unwrap();
+    return child.wait_with_output().unwrap();
+}
+
+fn stdout(output: &Output) -> String {
+    return String::from_utf8_lossy(&output.stdout).into_owned();
+}
+
+fn stderr(output: &Output) -> String {
+    return String::from_utf8_lossy(&output.stderr).into_owned();
+}
+
+#[test]
+fn cli_test_script() {
+
+    // no banner or prompts, and the script ends with its input
+    let output: Output = run(&[], "1 2 + .\n: SQUARE\nDUP * ;\n3 SQUARE .");
+    assert_eq!(stdout(&output), "3 9 ");
+    assert_eq!(stderr(&output), "");
+    assert_eq!(output.status.code(), Some(0));
+
+    let output: Output = run(&[], "");
+    assert_eq!(stdout(&output), "");
+    assert_eq!(output.status.code(), Some(0));
+
+    // the script stops at the first error
+    let output: Output = run(&[], "1 .\n2 UNDEFINED\n3 .\n");
+    assert_eq!(stdout(&output), "1 ");
+    assert!(stderr(&output).starts_with("error: stdin:2: undefined word"));
+    assert_eq!(output.status.code(), Some(1));
+
+    // definitions can't be left unterminated
+    let output: Output = run(&[], ": UNFINISHED 1\n");
+    assert_eq!(stderr(&output), "error: stdin: unterminated definition at end of input\n");
+    assert_eq!(output.status.code(), Some(1));
+
+    // keys and lines are read from the same input, whole characters at a time
+    let output: Output = run(&[], "HERE 20 ALLOT KEY? . DUP 20 ACCEPT TYPE\n\u{e9}t\u{e9}\nKEY . KEY .\n\u{e9}");
+    assert_eq!(stdout(&output), "1 \u{e9}t\u{e9}195 169 ");
+    assert_eq!(output.status.code(), Some(0));
+
+    // halting exits with the program's code
+    let output: Output = run(&[], "4 .\n7 (BYE)\n5 .\n");
+    assert_eq!(stdout(&output), "4 ");
+    assert_eq!(output.status.code(), Some(7));
+
+}
+
+#[test]
+fn cli_test_interactive_script() {
+
+    // the script is interpreted after the expressions
+    let output: Output = run(&["-i", "-e", ": DOUBLE 2 * ;"], "21 DOUBLE .\n");
+    assert_eq!(stdout(&output), "42 ");
+    assert_eq!(output.status.code(), Some(0));
+
+    // errors in the expressions don't stop the script from being interpreted
+    let output: Output = run(&["-i", "-e", "UNDEFINED"], "6 .\n");
+    assert_eq!(stdout(&output), "6 ");
+    assert!(stderr(&output).starts_with("error: -e:1: undefined word"));
+    assert_eq!(output.status.code(), Some(0));
+
+}
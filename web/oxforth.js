@@ -0,0 +1,110 @@
+// OxForth - JavaScript wrapper of the WebAssembly module
+//
+// The module is built by:
+//
+//     cargo build --lib --release --target wasm32-unknown-unknown
+//
+// and found at target/wasm32-unknown-unknown/release/oxforth.wasm. Each
+// OxForth instance wraps a Playground (see src/wasm.rs):
+//
+//     const forth = await OxForth.load(bytes, (text) => console.log(text));
+//     forth.eval(": SQUARE DUP * ; 7 SQUARE");
+//     forth.stack();  // [49n]
+
+export const OK = 0;
+export const HALTED = 1;
+export const ERROR = -1;
+
+// Output of every instance, by sink, as a function of the bytes written
+const sinks = new Map();
+let nextSink = 0;
+
+// Modules compiled so far, by the bytes they were compiled from
+const modules = new WeakMap();
+
+export class OxForth {
+
+    // Create a Forth system from the WebAssembly module's bytes (or a
+    // compiled WebAssembly.Module), passing its output to the given function
+    static async load(bytes, output) {
+        let module = bytes instanceof WebAssembly.Module ? bytes : modules.get(bytes);
+        if (module === undefined) {
+            module = await WebAssembly.compile(bytes);
+            modules.set(bytes, module);
+        }
+        let exports = null;
+        const instance = await WebAssembly.instantiate(module, {
+            oxforth: {
+                output: (sink, pointer, length) => {
+                    sinks.get(sink)(new Uint8Array(exports.memory.buffer, pointer, length));
+                },
+            },
+        });
+        exports = instance.exports;
+        return new OxForth(exports, output);
+    }
+
+    constructor(exports, output) {
+        this.exports = exports;
+        this.sink = nextSink++;
+
+        // Characters may be split between writes, so the rest of one is kept
+        // by the decoder until the next write
+        const decoder = new TextDecoder();
+        sinks.set(this.sink, (bytes) => output(decoder.decode(bytes, {stream: true})));
+        this.handle = exports.playground_new(this.sink);
+    }
+
+    // Release the Forth system
+    free() {
+        this.exports.playground_free(this.handle);
+        sinks.delete(this.sink);
+        this.handle = 0;
+    }
+
+    // Interpret Forth source, returning OK, or HALTED if the program halted
+    // (see code()). Throws an Error describing why if it failed.
+    eval(source) {
+        const bytes = new TextEncoder().encode(source);
+        const pointer = this.exports.oxforth_alloc(bytes.length);
+        new Uint8Array(this.exports.memory.buffer, pointer, bytes.length).set(bytes);
+        const status = this.exports.playground_eval(this.handle, pointer, bytes.length);
+        this.exports.oxforth_dealloc(pointer, bytes.length);
+        if (status === ERROR) {
+            throw new Error(this.error());
+        }
+        return status;
+    }
+
+    // Why the last call to eval() failed, or "" if it didn't
+    error() {
+        const pointer = this.exports.playground_error(this.handle);
+        const length = this.exports.playground_error_length(this.handle);
+        return new TextDecoder().decode(new Uint8Array(this.exports.memory.buffer, pointer, length));
+    }
+
+    // Exit code the program gave when it last halted
+    code() {
+        return this.exports.playground_code(this.handle);
+    }
+
+    // Whether a definition is being compiled, i.e. more source is expected
+    compiling() {
+        return this.exports.playground_compiling(this.handle) !== 0;
+    }
+
+    // Numbers on the data stack as BigInts, from bottom to top
+    stack() {
+        const depth = this.exports.playground_stack(this.handle);
+        const pointer = this.exports.playground_stack_snapshot(this.handle);
+        return Array.from(new BigInt64Array(this.exports.memory.buffer, pointer, depth));
+    }
+
+    // Floats on the floating-point stack, from bottom to top
+    floats() {
+        const depth = this.exports.playground_floats(this.handle);
+        const pointer = this.exports.playground_floats_snapshot(this.handle);
+        return Array.from(new Float64Array(this.exports.memory.buffer, pointer, depth));
+    }
+
+}